@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+use std::hint::black_box;
+
+use criterion::{BatchSize, BenchmarkId, Criterion, criterion_group, criterion_main};
+use finit::Set;
+use finit::comparisons::SubsetOf;
+use finit::operations::{
+    DifferenceAssign, DisjunctiveUnionAssign, IntersectionAssign, UnionAssign,
+};
+
+const SIZES: [u32; 3] = [10, 1_000, 100_000];
+
+#[derive(
+    Debug,
+    Clone,
+    Set,
+    UnionAssign,
+    DifferenceAssign,
+    IntersectionAssign,
+    DisjunctiveUnionAssign,
+    SubsetOf,
+)]
+pub struct ThemingPerms {
+    can_have_dark_mode: bool,
+    allowed_themes: HashMap<String, bool>,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Set,
+    UnionAssign,
+    DifferenceAssign,
+    IntersectionAssign,
+    DisjunctiveUnionAssign,
+    SubsetOf,
+)]
+pub struct ClanPerms {
+    kick: bool,
+    ban: bool,
+    owner: bool,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Set,
+    UnionAssign,
+    DifferenceAssign,
+    IntersectionAssign,
+    DisjunctiveUnionAssign,
+    SubsetOf,
+)]
+pub struct UserPerms {
+    theming: ThemingPerms,
+    clans: HashMap<String, ClanPerms>,
+    account_access: bool,
+}
+
+/// Permissions for `clans` clans, starting at clan `first`, like those of the `clans` example.
+fn user_perms(first: u32, clans: u32, owner: bool) -> UserPerms {
+    UserPerms {
+        theming: ThemingPerms {
+            can_have_dark_mode: true,
+            allowed_themes: HashMap::from([
+                ("light".to_string(), true),
+                ("dark".to_string(), owner),
+            ]),
+        },
+        clans: (first..first + clans)
+            .map(|clan| {
+                (
+                    format!("clan-{clan}"),
+                    ClanPerms {
+                        kick: true,
+                        ban: owner,
+                        owner,
+                    },
+                )
+            })
+            .collect(),
+        account_access: true,
+    }
+}
+
+fn nested_structs(c: &mut Criterion) {
+    let mut group = c.benchmark_group("nested_structs");
+
+    for size in SIZES {
+        let a = user_perms(0, size, false);
+        let b = user_perms(size / 2, size, true);
+
+        group.bench_with_input(BenchmarkId::new("union", size), &size, |bench, _| {
+            bench.iter_batched(
+                || a.clone(),
+                |mut a| {
+                    a.union_assign(black_box(&b));
+                    a
+                },
+                BatchSize::LargeInput,
+            )
+        });
+
+        group.bench_with_input(BenchmarkId::new("difference", size), &size, |bench, _| {
+            bench.iter_batched(
+                || a.clone(),
+                |mut a| {
+                    a.difference_assign(black_box(&b));
+                    a
+                },
+                BatchSize::LargeInput,
+            )
+        });
+
+        group.bench_with_input(BenchmarkId::new("intersection", size), &size, |bench, _| {
+            bench.iter_batched(
+                || a.clone(),
+                |mut a| {
+                    a.intersection_assign(black_box(&b));
+                    a
+                },
+                BatchSize::LargeInput,
+            )
+        });
+
+        group.bench_with_input(
+            BenchmarkId::new("disjunctive_union", size),
+            &size,
+            |bench, _| {
+                bench.iter_batched(
+                    || a.clone(),
+                    |mut a| {
+                        a.disjunctive_union_assign(black_box(&b));
+                        a
+                    },
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+
+        group.bench_with_input(BenchmarkId::new("subset_of", size), &size, |bench, _| {
+            bench.iter(|| a.subset_of(black_box(&a)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, nested_structs);
+criterion_main!(benches);
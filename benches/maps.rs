@@ -0,0 +1,106 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hint::black_box;
+
+use criterion::{BatchSize, BenchmarkId, Criterion, criterion_group, criterion_main};
+use finit::comparisons::SubsetOf;
+use finit::operations::{
+    DifferenceAssign, DisjunctiveUnionAssign, IntersectionAssign, UnionAssign,
+};
+
+const SIZES: [u32; 4] = [10, 1_000, 100_000, 1_000_000];
+
+/// Two maps over `0..size` that share half of their keys, with nested maps as values.
+fn maps<Map, Inner>(size: u32) -> (Map, Map)
+where
+    Map: FromIterator<(u32, Inner)>,
+    Inner: FromIterator<(u8, bool)>,
+{
+    let a = (0..size)
+        .map(|key| (key, Inner::from_iter([(0, true), (1, true)])))
+        .collect();
+    let b = (size / 2..size + size / 2)
+        .map(|key| (key, Inner::from_iter([(1, true), (2, true)])))
+        .collect();
+
+    (a, b)
+}
+
+macro_rules! bench_map {
+    ($name:ident, $map:ident) => {
+        fn $name(c: &mut Criterion) {
+            let mut group = c.benchmark_group(stringify!($map));
+
+            for size in SIZES {
+                if size >= 100_000 {
+                    group.sample_size(10);
+                }
+
+                let (a, b) = maps::<$map<u32, $map<u8, bool>>, $map<u8, bool>>(size);
+
+                group.bench_with_input(BenchmarkId::new("union", size), &size, |bench, _| {
+                    bench.iter_batched(
+                        || a.clone(),
+                        |mut a| {
+                            a.union_assign(black_box(&b));
+                            a
+                        },
+                        BatchSize::LargeInput,
+                    )
+                });
+
+                group.bench_with_input(BenchmarkId::new("difference", size), &size, |bench, _| {
+                    bench.iter_batched(
+                        || a.clone(),
+                        |mut a| {
+                            a.difference_assign(black_box(&b));
+                            a
+                        },
+                        BatchSize::LargeInput,
+                    )
+                });
+
+                group.bench_with_input(
+                    BenchmarkId::new("intersection", size),
+                    &size,
+                    |bench, _| {
+                        bench.iter_batched(
+                            || a.clone(),
+                            |mut a| {
+                                a.intersection_assign(black_box(&b));
+                                a
+                            },
+                            BatchSize::LargeInput,
+                        )
+                    },
+                );
+
+                group.bench_with_input(
+                    BenchmarkId::new("disjunctive_union", size),
+                    &size,
+                    |bench, _| {
+                        bench.iter_batched(
+                            || a.clone(),
+                            |mut a| {
+                                a.disjunctive_union_assign(black_box(&b));
+                                a
+                            },
+                            BatchSize::LargeInput,
+                        )
+                    },
+                );
+
+                group.bench_with_input(BenchmarkId::new("subset_of", size), &size, |bench, _| {
+                    bench.iter(|| a.subset_of(black_box(&a)))
+                });
+            }
+
+            group.finish();
+        }
+    };
+}
+
+bench_map!(hashmap, HashMap);
+bench_map!(btreemap, BTreeMap);
+
+criterion_group!(benches, hashmap, btreemap);
+criterion_main!(benches);
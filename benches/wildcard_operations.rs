@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::hint::black_box;
+
+use criterion::{BatchSize, BenchmarkId, Criterion, criterion_group, criterion_main};
+use finit::collections::WildcardHashMap;
+use finit::comparisons::SubsetOf;
+use finit::operations::identity::{
+    disjunctive_union_using_difference_and_union, intersection_using_double_difference,
+};
+use finit::operations::{
+    DifferenceAssign, DisjunctiveUnionAssign, IntersectionAssign, UnionAssign,
+};
+
+const SIZES: [u32; 3] = [100, 1_000, 10_000];
+
+/// A map granting everything, with exceptions for every `step`th key and extra values for the keys in between.
+fn wildcard_map(size: u32, step: u32) -> WildcardHashMap<u32, HashMap<u32, bool>> {
+    let mut map = WildcardHashMap::new(HashMap::from([(0, true), (1, true)]));
+
+    let exceptions: HashMap<u32, HashMap<u32, bool>> = (0..size)
+        .filter(|key| key % step == 0)
+        .map(|key| (key, HashMap::from([(0, true)])))
+        .collect();
+    let rest: HashMap<u32, HashMap<u32, bool>> = (0..size)
+        .filter(|key| key % step != 0)
+        .map(|key| (key, HashMap::from([(2, true)])))
+        .collect();
+
+    map.difference_assign(&exceptions);
+    map.union_assign(&rest);
+
+    map
+}
+
+/// A map without a wildcard value, giving values to only a few keys, like the permissions required for an action.
+fn sparse_map(size: u32) -> WildcardHashMap<u32, HashMap<u32, bool>> {
+    let mut map = WildcardHashMap::new(HashMap::new());
+
+    let rest: HashMap<u32, HashMap<u32, bool>> = (0..size)
+        .step_by(size as usize / 10)
+        .map(|key| (key, HashMap::from([(0, true)])))
+        .collect();
+
+    map.union_assign(&rest);
+
+    map
+}
+
+fn intersection(c: &mut Criterion) {
+    let mut group = c.benchmark_group("wildcard_intersection");
+
+    for size in SIZES {
+        let a = wildcard_map(size, 2);
+
+        for (name, b) in [
+            ("dense", wildcard_map(size, 3)),
+            ("sparse", sparse_map(size)),
+        ] {
+            group.bench_with_input(
+                BenchmarkId::new(format!("direct/{name}"), size),
+                &size,
+                |bench, _| {
+                    bench.iter(|| {
+                        let mut a = a.clone();
+                        a.intersection_assign(black_box(&b));
+                        a
+                    })
+                },
+            );
+
+            group.bench_with_input(
+                BenchmarkId::new(format!("identity/{name}"), size),
+                &size,
+                |bench, _| {
+                    bench.iter(|| intersection_using_double_difference(a.clone(), black_box(&b)))
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+fn disjunctive_union(c: &mut Criterion) {
+    let mut group = c.benchmark_group("wildcard_disjunctive_union");
+
+    for size in SIZES {
+        let a = wildcard_map(size, 2);
+        let b = wildcard_map(size, 3);
+
+        group.bench_with_input(BenchmarkId::new("direct", size), &size, |bench, _| {
+            bench.iter(|| {
+                let mut a = a.clone();
+                a.disjunctive_union_assign(black_box(&b));
+                a
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("identity", size), &size, |bench, _| {
+            bench.iter(|| {
+                disjunctive_union_using_difference_and_union(a.clone(), black_box(b.clone()))
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn heavy_exceptions(c: &mut Criterion) {
+    let mut group = c.benchmark_group("wildcard_heavy_exceptions");
+
+    for size in SIZES {
+        // Every key of `a` has an exception.
+        let a = wildcard_map(size, 1);
+        let b = wildcard_map(size, 2);
+
+        group.bench_with_input(BenchmarkId::new("union", size), &size, |bench, _| {
+            bench.iter_batched(
+                || a.clone(),
+                |mut a| {
+                    a.union_assign(black_box(&b));
+                    a
+                },
+                BatchSize::LargeInput,
+            )
+        });
+
+        group.bench_with_input(BenchmarkId::new("difference", size), &size, |bench, _| {
+            bench.iter_batched(
+                || a.clone(),
+                |mut a| {
+                    a.difference_assign(black_box(&b));
+                    a
+                },
+                BatchSize::LargeInput,
+            )
+        });
+
+        group.bench_with_input(BenchmarkId::new("intersection", size), &size, |bench, _| {
+            bench.iter_batched(
+                || a.clone(),
+                |mut a| {
+                    a.intersection_assign(black_box(&b));
+                    a
+                },
+                BatchSize::LargeInput,
+            )
+        });
+
+        group.bench_with_input(
+            BenchmarkId::new("disjunctive_union", size),
+            &size,
+            |bench, _| {
+                bench.iter_batched(
+                    || a.clone(),
+                    |mut a| {
+                        a.disjunctive_union_assign(black_box(&b));
+                        a
+                    },
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+
+        group.bench_with_input(BenchmarkId::new("subset_of", size), &size, |bench, _| {
+            bench.iter(|| a.subset_of(black_box(&b)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, intersection, disjunctive_union, heavy_exceptions);
+criterion_main!(benches);
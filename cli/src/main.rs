@@ -0,0 +1,288 @@
+//! `finit` answers questions about permission sets and policies without writing Rust, printing JSON.
+//!
+//! A set is read from a file, which is either:
+//! - a policy document (`.toml`, `.yaml`, `.yml` or `.json`) when `--role` is given, see [`finit::policy`],
+//! - a JSON serialized [`NodeSet`] (`.json`),
+//! - or a list of permission nodes, one per line, where nodes starting with `-` are denied and everything is applied in order.
+
+mod node_set;
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use finit::Set;
+use finit::operations::{DisjunctiveUnionAssign, IntersectionAssign, UnionAssign};
+use finit::policy::Policy;
+use finit::requirements::Require;
+use serde::Serialize;
+
+use node_set::{NodeSet, Op, difference_ops};
+
+#[derive(Parser)]
+#[command(name = "finit", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Checks whether a set contains every node, exiting with 1 if any is missing.
+    Check {
+        set: PathBuf,
+        #[arg(required = true)]
+        nodes: Vec<String>,
+        /// The role to use from a policy document.
+        #[arg(long)]
+        role: Option<String>,
+    },
+    /// Lists what was added, removed and changed between two versions of a set.
+    Diff {
+        old: PathBuf,
+        new: PathBuf,
+        /// The role to use from both policy documents.
+        #[arg(long)]
+        role: Option<String>,
+    },
+    /// Prints the union of the sets.
+    Union {
+        #[arg(required = true)]
+        sets: Vec<PathBuf>,
+        /// The role to use from every policy document.
+        #[arg(long)]
+        role: Option<String>,
+    },
+    /// Prints the shortest node list of a set, or every role of a policy document.
+    Normalize {
+        set: PathBuf,
+        /// The role to use from a policy document.
+        #[arg(long)]
+        role: Option<String>,
+    },
+    /// Explains which grants, denies and inherited roles decide whether a role has a node.
+    Explain {
+        policy: PathBuf,
+        #[arg(long)]
+        role: String,
+        node: String,
+    },
+}
+
+/// The ops of a set, as printed by most commands.
+#[derive(Serialize)]
+struct Nodes {
+    nodes: Vec<Op>,
+}
+
+#[derive(Serialize)]
+struct Check {
+    node: String,
+    granted: bool,
+}
+
+#[derive(Serialize)]
+struct Diff {
+    /// `new - old`
+    added: Vec<Op>,
+    /// `old - new`
+    removed: Vec<Op>,
+    /// `old ⊖ new`
+    changed: Vec<Op>,
+}
+
+#[derive(Serialize)]
+struct Explanation {
+    role: String,
+    granted: bool,
+    /// The grants of the role that contain part of the node.
+    granted_by: Vec<String>,
+    /// The denies of the role that contain part of the node.
+    denied_by: Vec<String>,
+    inherits: Vec<Explanation>,
+}
+
+fn node(node: &str) -> Result<NodeSet, String> {
+    NodeSet::from_node_str(node).map_err(|error| format!("invalid node `{node}`: {error}"))
+}
+
+fn load_policy(path: &Path) -> Result<Policy<NodeSet>, String> {
+    Policy::load(path).map_err(|errors| errors.to_string())
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|error| format!("{}: {error}", path.display()))
+}
+
+/// Whether the file is a policy document. TOML and YAML files always are, while JSON files are policies if they have `roles`, which a serialized [`NodeSet`] never has.
+fn is_policy(path: &Path) -> Result<bool, String> {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("toml" | "yaml" | "yml") => Ok(true),
+        Some("json") => serde_json::from_str::<serde_json::Value>(&read(path)?)
+            .map(|value| value.get("roles").is_some())
+            .map_err(|error| format!("{}: {error}", path.display())),
+        _ => Ok(false),
+    }
+}
+
+fn load(path: &Path, role: Option<&str>) -> Result<NodeSet, String> {
+    if let Some(role) = role {
+        return load_policy(path)?
+            .role(role)
+            .cloned()
+            .ok_or_else(|| format!("{}: unknown role `{role}`", path.display()));
+    }
+
+    if is_policy(path)? {
+        return Err(format!(
+            "{}: is a policy document, pick a role with `--role`",
+            path.display()
+        ));
+    }
+
+    let text = read(path)?;
+
+    if path
+        .extension()
+        .is_some_and(|extension| extension == "json")
+    {
+        return serde_json::from_str(&text).map_err(|error| format!("{}: {error}", path.display()));
+    }
+
+    NodeSet::from_ops(&Op::parse_list(&text))
+        .map_err(|error| format!("{}: {error}", path.display()))
+}
+
+fn explain(policy: &Policy<NodeSet>, role: &str, node: &NodeSet) -> Result<Explanation, String> {
+    let definition = policy
+        .definition(role)
+        .ok_or_else(|| format!("unknown role `{role}`"))?;
+    let contains_part = |entry: &String| -> Result<bool, String> {
+        let mut entry_set = self::node(entry)?;
+        entry_set.intersection_assign(node);
+        Ok(!entry_set.is_empty())
+    };
+
+    Ok(Explanation {
+        role: role.to_string(),
+        granted: policy
+            .role(role)
+            .is_some_and(|set| set.require(node.clone()).is_ok()),
+        granted_by: definition
+            .grant
+            .iter()
+            .filter_map(|entry| {
+                contains_part(entry)
+                    .map(|part| part.then(|| entry.clone()))
+                    .transpose()
+            })
+            .collect::<Result<_, _>>()?,
+        denied_by: definition
+            .deny
+            .iter()
+            .filter_map(|entry| {
+                contains_part(entry)
+                    .map(|part| part.then(|| entry.clone()))
+                    .transpose()
+            })
+            .collect::<Result<_, _>>()?,
+        inherits: definition
+            .inherits
+            .iter()
+            .map(|inherited| explain(policy, inherited, node))
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+fn print(value: &impl Serialize) {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(value).expect("Output is always serializable.")
+    );
+}
+
+fn run(command: Command) -> Result<bool, String> {
+    match command {
+        Command::Check { set, nodes, role } => {
+            let set = load(&set, role.as_deref())?;
+
+            let checks = nodes
+                .into_iter()
+                .map(|node| {
+                    let granted = set.require(self::node(&node)?).is_ok();
+                    Ok(Check { node, granted })
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+
+            print(&checks);
+            Ok(checks.iter().all(|check| check.granted))
+        }
+        Command::Diff { old, new, role } => {
+            let old = load(&old, role.as_deref())?;
+            let new = load(&new, role.as_deref())?;
+
+            let mut changed = old.clone();
+            changed.disjunctive_union_assign(&new);
+
+            print(&Diff {
+                added: difference_ops(&new, &old),
+                removed: difference_ops(&old, &new),
+                changed: changed.to_ops(),
+            });
+            Ok(true)
+        }
+        Command::Union { sets, role } => {
+            let mut union = NodeSet::empty();
+
+            for set in sets {
+                union.union_assign(&load(&set, role.as_deref())?);
+            }
+
+            print(&Nodes {
+                nodes: union.to_ops(),
+            });
+            Ok(true)
+        }
+        Command::Normalize { set, role: None } if is_policy(&set)? => {
+            let roles = load_policy(&set)?
+                .into_roles()
+                .into_iter()
+                .map(|(role, set)| {
+                    (
+                        role,
+                        Nodes {
+                            nodes: set.to_ops(),
+                        },
+                    )
+                })
+                .collect::<std::collections::BTreeMap<_, _>>();
+
+            print(&roles);
+            Ok(true)
+        }
+        Command::Normalize { set, role } => {
+            print(&Nodes {
+                nodes: load(&set, role.as_deref())?.to_ops(),
+            });
+            Ok(true)
+        }
+        Command::Explain { policy, role, node } => {
+            let explanation = explain(&load_policy(&policy)?, &role, &self::node(&node)?)?;
+
+            let granted = explanation.granted;
+            print(&explanation);
+            Ok(granted)
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse().command) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,365 @@
+//! [`NodeSet`] is a set of permission nodes of any shape, for working with sets without their Rust type.
+
+use std::fmt::Display;
+
+use finit::Set;
+use finit::collections::WildcardBTreeMap;
+use finit::operations::{
+    Difference, DifferenceAssign, DisjunctiveUnionAssign, IntersectionAssign, UnionAssign,
+};
+use finit::policy::{FromNode, NodeError};
+
+type Children = WildcardBTreeMap<String, NodeSet>;
+
+/// A set of permission nodes, where every node is either a flag or has children keyed by its next segment.
+///
+/// The node `clans.*.kick` is the `kick` flag of every child of `clans`. A set is written as a list of [`Op`]s, which are applied in order.
+#[derive(Clone, PartialEq, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct NodeSet {
+    #[serde(skip_serializing_if = "Set::is_empty")]
+    pub flag: bool,
+    /// The children are boxed and missing when empty, since the empty wildcard map of a recursive type would otherwise contain itself.
+    #[serde(with = "flat_children", skip_serializing_if = "Option::is_none")]
+    children: Option<Box<Children>>,
+}
+
+mod flat_children {
+    use finit::representation::flat;
+    use serde::{Deserializer, Serializer};
+
+    use super::Children;
+
+    pub fn serialize<S: Serializer>(
+        children: &Option<Box<Children>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match children {
+            Some(children) => flat::serialize(&**children, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Box<Children>>, D::Error> {
+        flat::deserialize(deserializer).map(|children: Children| {
+            (!finit::Set::is_empty(&children)).then(|| Box::new(children))
+        })
+    }
+}
+
+impl Set for NodeSet {
+    type Empty = Self;
+
+    fn is_empty(&self) -> bool {
+        !self.flag && self.children.is_none()
+    }
+
+    fn empty() -> Self {
+        NodeSet {
+            flag: false,
+            children: None,
+        }
+    }
+}
+
+impl NodeSet {
+    fn children_mut(&mut self) -> &mut Children {
+        self.children
+            .get_or_insert_with(|| Box::new(Children::empty()))
+    }
+
+    /// Removes the children if they became empty, so that equal sets compare equal.
+    fn prune(&mut self) {
+        if self
+            .children
+            .as_ref()
+            .is_some_and(|children| children.is_empty())
+        {
+            self.children = None;
+        }
+    }
+}
+
+impl UnionAssign<&NodeSet> for NodeSet {
+    fn union_assign(&mut self, rhs: &NodeSet) {
+        self.flag.union_assign(&rhs.flag);
+
+        if let Some(rhs_children) = &rhs.children {
+            self.children_mut().union_assign(&**rhs_children);
+        }
+
+        self.prune();
+    }
+}
+
+impl DifferenceAssign<&NodeSet> for NodeSet {
+    fn difference_assign(&mut self, rhs: &NodeSet) {
+        self.flag.difference_assign(&rhs.flag);
+
+        if let (Some(children), Some(rhs_children)) = (&mut self.children, &rhs.children) {
+            children.difference_assign(&**rhs_children);
+        }
+
+        self.prune();
+    }
+}
+
+impl IntersectionAssign<&NodeSet> for NodeSet {
+    fn intersection_assign(&mut self, rhs: &NodeSet) {
+        self.flag.intersection_assign(&rhs.flag);
+
+        match (&mut self.children, &rhs.children) {
+            (Some(children), Some(rhs_children)) => children.intersection_assign(&**rhs_children),
+            (children, None) => *children = None,
+            (None, Some(_)) => {}
+        }
+
+        self.prune();
+    }
+}
+
+impl DisjunctiveUnionAssign<&NodeSet> for NodeSet {
+    fn disjunctive_union_assign(&mut self, rhs: &NodeSet) {
+        self.flag.disjunctive_union_assign(&rhs.flag);
+
+        if let Some(rhs_children) = &rhs.children {
+            self.children_mut()
+                .disjunctive_union_assign(&**rhs_children);
+        }
+
+        self.prune();
+    }
+}
+
+impl Difference<&NodeSet> for NodeSet {
+    type Output = Self;
+
+    fn difference(mut self, rhs: &NodeSet) -> Self {
+        self.difference_assign(rhs);
+        self
+    }
+}
+
+impl FromNode for NodeSet {
+    fn from_node(segments: &[&str]) -> Result<Self, NodeError> {
+        if segments.is_empty() {
+            return Ok(NodeSet {
+                flag: true,
+                ..NodeSet::empty()
+            });
+        }
+
+        Ok(NodeSet {
+            children: Some(Box::new(FromNode::from_node(segments)?)),
+            ..NodeSet::empty()
+        })
+    }
+}
+
+impl NodeSet {
+    /// The set containing `node`, whose segments are separated by `.`. The empty node is the flag of the root.
+    pub fn from_node_str(node: &str) -> Result<Self, NodeError> {
+        if node.is_empty() {
+            return Self::from_node(&[]);
+        }
+
+        Self::from_node(&node.split('.').collect::<Vec<_>>())
+    }
+
+    /// Applies `ops` in order to the empty set.
+    pub fn from_ops<'a>(ops: impl IntoIterator<Item = &'a Op>) -> Result<Self, NodeError> {
+        let mut set = NodeSet::empty();
+
+        for op in ops {
+            match op {
+                Op::Grant(node) => set.union_assign(&Self::from_node_str(node)?),
+                Op::Deny(node) => set.difference_assign(&Self::from_node_str(node)?),
+            }
+        }
+
+        Ok(set)
+    }
+
+    /// A list of ops that builds this set with [`NodeSet::from_ops`].
+    pub fn to_ops(&self) -> Vec<Op> {
+        let mut ops = Vec::new();
+        self.write_ops(&[], &mut ops);
+        ops
+    }
+
+    /// True if the ops of this set are all grants.
+    fn has_exceptions(&self) -> bool {
+        self.children.as_ref().is_some_and(|children| {
+            !children.wildcard_exceptions().is_empty()
+                || children.wildcard_value().has_exceptions()
+                || children.rest_list().values().any(NodeSet::has_exceptions)
+        })
+    }
+
+    fn write_ops(&self, prefix: &[&str], ops: &mut Vec<Op>) {
+        let node = |segments: &[&str], rest: &str| {
+            let mut node = segments.join(".");
+            if !node.is_empty() && !rest.is_empty() {
+                node.push('.');
+            }
+            node + rest
+        };
+
+        if self.flag {
+            ops.push(Op::Grant(prefix.join(".")));
+        }
+
+        let Some(children) = &self.children else {
+            return;
+        };
+
+        let wildcard_value = children.wildcard_value();
+        let wildcard_ops = wildcard_value.to_ops();
+        ops.extend(
+            wildcard_ops
+                .iter()
+                .map(|op| op.map(|rest| node(&[prefix, &["*"]].concat(), rest))),
+        );
+
+        let mut keys: Vec<&String> = children
+            .wildcard_exceptions()
+            .keys()
+            .chain(children.rest_list().keys())
+            .collect();
+        keys.sort();
+        keys.dedup();
+
+        for key in keys {
+            let key_prefix = [prefix, &[key.as_str()]].concat();
+            let exception = children.wildcard_exceptions().get(key);
+            let rest_value = children.rest_list().get(key);
+
+            if exception.is_some_and(NodeSet::has_exceptions)
+                || rest_value.is_some_and(NodeSet::has_exceptions)
+            {
+                // Start over from the empty set, since denying or adding sets with exceptions of their own is not a list of ops.
+                ops.extend(wildcard_ops.iter().filter_map(|op| match op {
+                    Op::Grant(rest) => Some(Op::Deny(node(&key_prefix, rest))),
+                    Op::Deny(_) => None,
+                }));
+                children.value_of(key).write_ops(&key_prefix, ops);
+                continue;
+            }
+
+            if let Some(exception) = exception {
+                ops.extend(
+                    exception
+                        .to_ops()
+                        .iter()
+                        .map(|op| Op::Deny(node(&key_prefix, op.node()))),
+                );
+            }
+
+            if let Some(rest_value) = rest_value {
+                rest_value.write_ops(&key_prefix, ops);
+            }
+        }
+    }
+}
+
+/// A step in building a [`NodeSet`], written as the node for grants and as the node prefixed with `-` for denies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Op {
+    Grant(String),
+    Deny(String),
+}
+
+impl Op {
+    pub fn node(&self) -> &str {
+        match self {
+            Op::Grant(node) | Op::Deny(node) => node,
+        }
+    }
+
+    fn map(&self, f: impl FnOnce(&str) -> String) -> Op {
+        match self {
+            Op::Grant(node) => Op::Grant(f(node)),
+            Op::Deny(node) => Op::Deny(f(node)),
+        }
+    }
+
+    /// Parses a list of ops with one op per line, skipping empty lines and lines starting with `#`.
+    pub fn parse_list(text: &str) -> Vec<Op> {
+        text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| match line.strip_prefix('-') {
+                Some(node) => Op::Deny(node.to_string()),
+                None => Op::Grant(line.to_string()),
+            })
+            .collect()
+    }
+}
+
+impl Display for Op {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Op::Grant(node) => write!(f, "{node}"),
+            Op::Deny(node) => write!(f, "-{node}"),
+        }
+    }
+}
+
+impl serde::Serialize for Op {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// The ops of `a - b`, so that diffs can be computed without cloning at the call site.
+pub fn difference_ops(a: &NodeSet, b: &NodeSet) -> Vec<Op> {
+    a.clone().difference(b).to_ops()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(list: &str) -> NodeSet {
+        NodeSet::from_ops(&Op::parse_list(list)).unwrap()
+    }
+
+    #[test]
+    fn round_trip_test() {
+        for list in [
+            "chat\nclans.*.kick\nclans.*.ban\n-clans.secret.kick",
+            "clans.*.*.kick\n-clans.*.admin.kick\nclans.home.admin.kick",
+            "a.*.b.*\n-a.*.b.c\n-a.x.b.d",
+            "",
+        ] {
+            let set = set(list);
+            assert_eq!(NodeSet::from_ops(&set.to_ops()).unwrap(), set, "{list}");
+        }
+    }
+
+    #[test]
+    fn to_ops_test() {
+        assert_eq!(
+            set("clans.*.kick\nclans.*.ban\n-clans.secret.kick\n# comment\nchat")
+                .to_ops()
+                .iter()
+                .map(Op::to_string)
+                .collect::<Vec<_>>(),
+            ["chat", "clans.*.ban", "clans.*.kick", "-clans.secret.kick"]
+        );
+    }
+
+    #[test]
+    fn json_test() {
+        let set = set("clans.*.kick\n-clans.secret.kick");
+        let json = serde_json::to_string(&set).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"children":{"clans":{"children":{"*":{"children":{"kick":{"flag":true}}},"-secret":{"children":{"kick":{"flag":true}}}}}}}"#
+        );
+        assert_eq!(serde_json::from_str::<NodeSet>(&json).unwrap(), set);
+    }
+}
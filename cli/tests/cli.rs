@@ -0,0 +1,143 @@
+use std::process::Command;
+
+use serde_json::{Value, json};
+
+/// Runs the `finit` binary from the fixtures directory, returning its exit code and its output parsed as JSON.
+fn finit(args: &[&str]) -> (i32, Value) {
+    let output = Command::new(env!("CARGO_BIN_EXE_finit"))
+        .args(args)
+        .current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures"))
+        .output()
+        .unwrap();
+
+    let stdout = if output.stdout.is_empty() {
+        Value::Null
+    } else {
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
+
+    (output.status.code().unwrap(), stdout)
+}
+
+#[test]
+fn check_test() {
+    assert_eq!(
+        finit(&[
+            "check",
+            "policy.toml",
+            "--role",
+            "moderator",
+            "clans.public.kick",
+            "chat"
+        ]),
+        (
+            0,
+            json!([
+                { "node": "clans.public.kick", "granted": true },
+                { "node": "chat", "granted": true },
+            ])
+        )
+    );
+    assert_eq!(
+        finit(&[
+            "check",
+            "policy.yaml",
+            "--role",
+            "moderator",
+            "clans.secret.kick"
+        ]),
+        (
+            1,
+            json!([{ "node": "clans.secret.kick", "granted": false }])
+        )
+    );
+    assert_eq!(
+        finit(&["check", "new.txt", "clans.public.kick"]),
+        (0, json!([{ "node": "clans.public.kick", "granted": true }]))
+    );
+}
+
+#[test]
+fn error_test() {
+    assert_eq!(
+        finit(&["check", "policy.toml", "--role", "nobody", "chat"]),
+        (2, Value::Null)
+    );
+    assert_eq!(finit(&["check", "policy.toml", "chat"]), (2, Value::Null));
+    assert_eq!(
+        finit(&["explain", "policy.toml", "--role", "nobody", "chat"]),
+        (2, Value::Null)
+    );
+    assert_eq!(finit(&["check", "missing.txt", "chat"]), (2, Value::Null));
+}
+
+#[test]
+fn diff_test() {
+    assert_eq!(
+        finit(&["diff", "old.txt", "new.txt"]),
+        (
+            0,
+            json!({
+                "added": ["clans.*.kick", "-clans.secret.kick"],
+                "removed": ["clans.*.invite"],
+                "changed": ["clans.*.invite", "clans.*.kick", "-clans.secret.kick"],
+            })
+        )
+    );
+}
+
+#[test]
+fn normalize_test() {
+    let roles = json!({
+        "member": { "nodes": ["chat", "clans.*.invite"] },
+        "moderator": { "nodes": ["chat", "clans.*.invite", "clans.*.kick", "-clans.secret.kick"] },
+    });
+
+    assert_eq!(finit(&["normalize", "policy.toml"]), (0, roles.clone()));
+    assert_eq!(finit(&["normalize", "policy.json"]), (0, roles.clone()));
+    assert_eq!(finit(&["normalize", "policy.yaml"]), (0, roles));
+    assert_eq!(
+        finit(&["normalize", "policy.json", "--role", "member"]),
+        (0, json!({ "nodes": ["chat", "clans.*.invite"] }))
+    );
+}
+
+#[test]
+fn explain_test() {
+    assert_eq!(
+        finit(&[
+            "explain",
+            "policy.toml",
+            "--role",
+            "moderator",
+            "clans.secret.kick"
+        ]),
+        (
+            1,
+            json!({
+                "role": "moderator",
+                "granted": false,
+                "granted_by": ["clans.*.kick"],
+                "denied_by": ["clans.secret.kick"],
+                "inherits": [{
+                    "role": "member",
+                    "granted": false,
+                    "granted_by": [],
+                    "denied_by": [],
+                    "inherits": [],
+                }],
+            })
+        )
+    );
+    assert_eq!(
+        finit(&[
+            "explain",
+            "policy.json",
+            "--role",
+            "moderator",
+            "clans.public.invite"
+        ])
+        .0,
+        0
+    );
+}
@@ -0,0 +1,138 @@
+extern crate proc_macro;
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, parse_quote};
+
+/// A field of a struct, split into whether it is a `bool` (which gets packed into the bitfield) or not.
+struct CodecField {
+    member: syn::Member,
+    binding: syn::Ident,
+    is_bool: bool,
+}
+
+fn codec_fields(fields: &syn::Fields) -> Option<Vec<CodecField>> {
+    let fields: Vec<CodecField> = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| CodecField {
+            member: match &field.ident {
+                Some(ident) => syn::Member::Named(ident.clone()),
+                None => syn::Member::Unnamed(i.into()),
+            },
+            binding: format_ident!("field_{}", i),
+            is_bool: matches!(&field.ty, syn::Type::Path(path) if path.qself.is_none() && path.path.is_ident("bool")),
+        })
+        .collect();
+
+    (!fields.is_empty()).then_some(fields)
+}
+
+pub fn encode_derive(input: TokenStream) -> TokenStream {
+    let input = proc_macro2::TokenStream::from(input);
+
+    let input: DeriveInput = syn::parse2(input).unwrap();
+
+    let crate_name: syn::Path = parse_quote!(::finit);
+    let struct_name = &input.ident;
+
+    let Data::Struct(struct_data) = &input.data else {
+        unimplemented!("Currently, there is only support for structs.");
+    };
+
+    let Some(fields) = codec_fields(&struct_data.fields) else {
+        return quote! {
+          compile_error!("Unit structs can't be a set.")
+        }
+        .into();
+    };
+
+    let bool_members: Vec<&syn::Member> = fields
+        .iter()
+        .filter(|field| field.is_bool)
+        .map(|field| &field.member)
+        .collect();
+
+    let encode_bools = (!bool_members.is_empty()).then(|| {
+        quote! {
+            encoder.write_bools(&[#(self.#bool_members),*]);
+        }
+    });
+
+    let encode_rest = fields
+        .iter()
+        .filter(|field| !field.is_bool)
+        .map(|field| {
+            let member = &field.member;
+            quote! {
+                #crate_name::codec::Encode::encode(&self.#member, encoder);
+            }
+        });
+
+    quote! {
+        impl #crate_name::codec::Encode for #struct_name {
+            fn encode(&self, encoder: &mut #crate_name::codec::Encoder) {
+                #encode_bools
+                #(#encode_rest)*
+            }
+        }
+    }
+    .into()
+}
+
+pub fn decode_derive(input: TokenStream) -> TokenStream {
+    let input = proc_macro2::TokenStream::from(input);
+
+    let input: DeriveInput = syn::parse2(input).unwrap();
+
+    let crate_name: syn::Path = parse_quote!(::finit);
+    let struct_name = &input.ident;
+
+    let Data::Struct(struct_data) = &input.data else {
+        unimplemented!("Currently, there is only support for structs.");
+    };
+
+    let Some(fields) = codec_fields(&struct_data.fields) else {
+        return quote! {
+          compile_error!("Unit structs can't be a set.")
+        }
+        .into();
+    };
+
+    let bool_bindings: Vec<&syn::Ident> = fields
+        .iter()
+        .filter(|field| field.is_bool)
+        .map(|field| &field.binding)
+        .collect();
+    let bool_count = bool_bindings.len();
+
+    let decode_bools = (!bool_bindings.is_empty()).then(|| {
+        quote! {
+            let [#(#bool_bindings),*] = decoder.read_bools::<#bool_count>()?;
+        }
+    });
+
+    // Fields are decoded in declaration order, since struct expressions are evaluated in the order they are written.
+    let field_values = fields.iter().map(|field| {
+        let member = &field.member;
+        let binding = &field.binding;
+
+        if field.is_bool {
+            quote! { #member: #binding }
+        } else {
+            quote! { #member: #crate_name::codec::Decode::decode(decoder)? }
+        }
+    });
+
+    quote! {
+        impl #crate_name::codec::Decode for #struct_name {
+            fn decode(decoder: &mut #crate_name::codec::Decoder<'_>) -> Result<Self, #crate_name::codec::DecodeError> {
+                #decode_bools
+
+                Ok(Self {
+                    #(#field_values,)*
+                })
+            }
+        }
+    }
+    .into()
+}
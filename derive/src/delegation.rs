@@ -0,0 +1,126 @@
+extern crate proc_macro;
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, parse_quote};
+
+/// Checks for `#[finit(grantable)]`, which marks a field whose type also derives `Grantable`.
+fn is_grantable(field: &syn::Field) -> syn::Result<bool> {
+    let Some(attr) = field.attrs.iter().find(|attr| attr.path.is_ident("finit")) else {
+        return Ok(false);
+    };
+
+    let key: syn::Ident = attr.parse_args()?;
+    if key != "grantable" {
+        return Err(syn::Error::new(key.span(), "expected `grantable`"));
+    }
+
+    Ok(true)
+}
+
+/// The type of a field in the generated struct, which is the generated struct of the field type for `#[finit(grantable)]` fields.
+fn grantable_type(crate_name: &syn::Path, field: &syn::Field) -> syn::Result<syn::Type> {
+    if !is_grantable(field)? {
+        let ty = &field.ty;
+        return Ok(parse_quote!(#crate_name::delegation::Grantable<#ty>));
+    }
+
+    let syn::Type::Path(mut path) = field.ty.clone() else {
+        return Err(syn::Error::new_spanned(
+            &field.ty,
+            "`#[finit(grantable)]` fields must be a path to a struct deriving `Grantable`",
+        ));
+    };
+
+    let segment = path
+        .path
+        .segments
+        .last_mut()
+        .expect("Paths have at least one segment.");
+    segment.ident = format_ident!("Grantable{}", segment.ident);
+
+    Ok(syn::Type::Path(path))
+}
+
+pub fn grantable_derive(input: TokenStream) -> TokenStream {
+    let input = proc_macro2::TokenStream::from(input);
+
+    let input: DeriveInput = syn::parse2(input).unwrap();
+
+    let crate_name: syn::Path = parse_quote!(::finit);
+    let struct_name = &input.ident;
+    let grantable_name = format_ident!("Grantable{}", struct_name);
+    let vis = &input.vis;
+
+    let Data::Struct(struct_data) = &input.data else {
+        unimplemented!("Currently, there is only support for structs.");
+    };
+
+    let members: Vec<syn::Member> = match &struct_data.fields {
+        syn::Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(|field| syn::Member::Named(field.ident.clone().expect("Struct is named.")))
+            .collect(),
+        syn::Fields::Unnamed(fields) => (0..fields.unnamed.len())
+            .map(|i| syn::Member::Unnamed(i.into()))
+            .collect(),
+        syn::Fields::Unit => {
+            return quote! {
+              compile_error!("Unit structs can't be a set.")
+            }
+            .into();
+        }
+    };
+
+    let types = match struct_data
+        .fields
+        .iter()
+        .map(|field| grantable_type(&crate_name, field))
+        .collect::<syn::Result<Vec<_>>>()
+    {
+        Ok(types) => types,
+        Err(error) => return error.to_compile_error().into(),
+    };
+
+    let field_vis = struct_data.fields.iter().map(|field| &field.vis);
+
+    let body = match &struct_data.fields {
+        syn::Fields::Named(_) => {
+            let field_names = &members;
+            quote! { { #(#field_vis #field_names: #types,)* } }
+        }
+        _ => quote! { ( #(#field_vis #types,)* ); },
+    };
+
+    let doc = format!(
+        "[`{struct_name}`] with both the permissions that are held and the permissions that may be granted to others, generated by the `Grantable` derive."
+    );
+
+    quote! {
+        #[doc = #doc]
+        #vis struct #grantable_name #body
+
+        impl #crate_name::delegation::GrantableLayers for #grantable_name {
+            type Layer = #struct_name;
+
+            fn from_layers(has: #struct_name, may_grant: #struct_name) -> Self {
+                Self {
+                    #(#members: #crate_name::delegation::GrantableLayers::from_layers(has.#members, may_grant.#members),)*
+                }
+            }
+
+            fn has(&self) -> #struct_name {
+                #struct_name {
+                    #(#members: #crate_name::delegation::GrantableLayers::has(&self.#members),)*
+                }
+            }
+
+            fn may_grant(&self) -> #struct_name {
+                #struct_name {
+                    #(#members: #crate_name::delegation::GrantableLayers::may_grant(&self.#members),)*
+                }
+            }
+        }
+    }
+    .into()
+}
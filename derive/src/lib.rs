@@ -3,8 +3,13 @@ use proc_macro::TokenStream;
 use quote::{format_ident, quote};
 use syn::{Data, DeriveInput, parse_quote};
 
+mod codec;
 mod comparisons;
+mod delegation;
 mod operations;
+mod policy;
+mod representation;
+mod schema;
 
 #[proc_macro_derive(Set)]
 pub fn set_derive(input: TokenStream) -> TokenStream {
@@ -157,6 +162,62 @@ pub fn disjunctive_union_derive(input: TokenStream) -> TokenStream {
     operations::operation_derive(input, &trait_path, &fn_name)
 }
 
+#[proc_macro_derive(TryUnionAssign, attributes(finit))]
+pub fn try_union_assign_derive(input: TokenStream) -> TokenStream {
+    let trait_path: syn::Path = parse_quote!(::finit::operations::TryUnionAssign);
+    let fn_name = format_ident!("try_union_assign");
+    operations::try_operation_assign_derive(input, &trait_path, &fn_name)
+}
+
+#[proc_macro_derive(TryDifferenceAssign, attributes(finit))]
+pub fn try_difference_assign_derive(input: TokenStream) -> TokenStream {
+    let trait_path: syn::Path = parse_quote!(::finit::operations::TryDifferenceAssign);
+    let fn_name = format_ident!("try_difference_assign");
+    operations::try_operation_assign_derive(input, &trait_path, &fn_name)
+}
+
+#[proc_macro_derive(TryIntersectionAssign, attributes(finit))]
+pub fn try_intersection_assign_derive(input: TokenStream) -> TokenStream {
+    let trait_path: syn::Path = parse_quote!(::finit::operations::TryIntersectionAssign);
+    let fn_name = format_ident!("try_intersection_assign");
+    operations::try_operation_assign_derive(input, &trait_path, &fn_name)
+}
+
+#[proc_macro_derive(TryDisjunctiveUnionAssign, attributes(finit))]
+pub fn try_disjunctive_union_assign_derive(input: TokenStream) -> TokenStream {
+    let trait_path: syn::Path = parse_quote!(::finit::operations::TryDisjunctiveUnionAssign);
+    let fn_name = format_ident!("try_disjunctive_union_assign");
+    operations::try_operation_assign_derive(input, &trait_path, &fn_name)
+}
+
+#[proc_macro_derive(TryUnion, attributes(finit))]
+pub fn try_union_derive(input: TokenStream) -> TokenStream {
+    let trait_path: syn::Path = parse_quote!(::finit::operations::TryUnion);
+    let fn_name = format_ident!("try_union");
+    operations::try_operation_derive(input, &trait_path, &fn_name)
+}
+
+#[proc_macro_derive(TryDifference, attributes(finit))]
+pub fn try_difference_derive(input: TokenStream) -> TokenStream {
+    let trait_path: syn::Path = parse_quote!(::finit::operations::TryDifference);
+    let fn_name = format_ident!("try_difference");
+    operations::try_operation_derive(input, &trait_path, &fn_name)
+}
+
+#[proc_macro_derive(TryIntersection, attributes(finit))]
+pub fn try_intersection_derive(input: TokenStream) -> TokenStream {
+    let trait_path: syn::Path = parse_quote!(::finit::operations::TryIntersection);
+    let fn_name = format_ident!("try_intersection");
+    operations::try_operation_derive(input, &trait_path, &fn_name)
+}
+
+#[proc_macro_derive(TryDisjunctiveUnion, attributes(finit))]
+pub fn try_disjunctive_union_derive(input: TokenStream) -> TokenStream {
+    let trait_path: syn::Path = parse_quote!(::finit::operations::TryDisjunctiveUnion);
+    let fn_name = format_ident!("try_disjunctive_union");
+    operations::try_operation_derive(input, &trait_path, &fn_name)
+}
+
 #[proc_macro_derive(SetEq)]
 pub fn set_eq_derive(input: TokenStream) -> TokenStream {
     comparisons::set_eq_derive(input)
@@ -166,3 +227,33 @@ pub fn set_eq_derive(input: TokenStream) -> TokenStream {
 pub fn subset_of_derive(input: TokenStream) -> TokenStream {
     comparisons::subset_of_derive(input)
 }
+
+#[proc_macro_derive(Encode)]
+pub fn encode_derive(input: TokenStream) -> TokenStream {
+    codec::encode_derive(input)
+}
+
+#[proc_macro_derive(Decode)]
+pub fn decode_derive(input: TokenStream) -> TokenStream {
+    codec::decode_derive(input)
+}
+
+#[proc_macro_derive(PermissionSchema)]
+pub fn permission_schema_derive(input: TokenStream) -> TokenStream {
+    schema::permission_schema_derive(input)
+}
+
+#[proc_macro_derive(FromNode)]
+pub fn from_node_derive(input: TokenStream) -> TokenStream {
+    policy::from_node_derive(input)
+}
+
+#[proc_macro_derive(Grantable, attributes(finit))]
+pub fn grantable_derive(input: TokenStream) -> TokenStream {
+    delegation::grantable_derive(input)
+}
+
+#[proc_macro_attribute]
+pub fn represent(attr: TokenStream, item: TokenStream) -> TokenStream {
+    representation::represent(attr, item)
+}
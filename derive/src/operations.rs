@@ -1,7 +1,8 @@
 extern crate proc_macro;
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{Data, DeriveInput};
+use syn::parse::ParseStream;
+use syn::{Data, DeriveInput, Token};
 
 pub fn operation_assign_derive(
     input: TokenStream,
@@ -131,3 +132,141 @@ pub fn operation_derive(
     }
     .into()
 }
+
+/// Reads the error type from `#[finit(error = E)]`, which the fallible derives need to name their `FieldError`.
+fn error_type(input: &DeriveInput) -> syn::Result<syn::Type> {
+    if let Some(attr) = input.attrs.iter().find(|attr| attr.path.is_ident("finit")) {
+        return attr.parse_args_with(|input: ParseStream| {
+            let key: syn::Ident = input.parse()?;
+            if key != "error" {
+                return Err(syn::Error::new(key.span(), "expected `error = ...`"));
+            }
+            input.parse::<Token![=]>()?;
+            input.parse()
+        });
+    }
+
+    Err(syn::Error::new(
+        input.ident.span(),
+        "fallible operations need the error type of the fields, set it with `#[finit(error = ...)]`",
+    ))
+}
+
+/// The field names used in the error path, and the members used to access the fields.
+fn fields(fields: &syn::Fields) -> Vec<(String, syn::Member)> {
+    match fields {
+        syn::Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(|field| {
+                let field_name = field.ident.clone().expect("Struct is named.");
+                (field_name.to_string(), syn::Member::Named(field_name))
+            })
+            .collect(),
+        syn::Fields::Unnamed(fields) => (0..fields.unnamed.len())
+            .map(|i| (i.to_string(), syn::Member::Unnamed(i.into())))
+            .collect(),
+        syn::Fields::Unit => Vec::new(),
+    }
+}
+
+pub fn try_operation_assign_derive(
+    input: TokenStream,
+    trait_path: &syn::Path,
+    fn_name: &syn::Ident,
+) -> TokenStream {
+    let input = proc_macro2::TokenStream::from(input);
+
+    let input: DeriveInput = syn::parse2(input).unwrap();
+
+    let struct_name = &input.ident;
+
+    let Data::Struct(struct_data) = &input.data else {
+        unimplemented!("Currently, there is only support for structs.");
+    };
+
+    if let syn::Fields::Unit = struct_data.fields {
+        return quote! {
+          compile_error!("Unit structs can't be a set.")
+        }
+        .into();
+    }
+
+    let error_type = match error_type(&input) {
+        Ok(error_type) => error_type,
+        Err(error) => return error.to_compile_error().into(),
+    };
+
+    let function_body = fields(&struct_data.fields)
+        .into_iter()
+        .map(|(field_name, member)| {
+            quote! {
+                #trait_path::#fn_name(&mut self.#member, &rhs.#member).map_err(|error| {
+                    ::finit::operations::IntoFieldError::into_field_error(error, #field_name)
+                })?;
+            }
+        });
+
+    quote! {
+        impl #trait_path<&#struct_name> for #struct_name {
+            type Error = ::finit::operations::FieldError<#error_type>;
+
+            fn #fn_name(&mut self, rhs: &#struct_name) -> ::core::result::Result<(), Self::Error> {
+                #(#function_body)*
+
+                ::core::result::Result::Ok(())
+            }
+        }
+    }
+    .into()
+}
+
+pub fn try_operation_derive(
+    input: TokenStream,
+    trait_path: &syn::Path,
+    fn_name: &syn::Ident,
+) -> TokenStream {
+    let input = proc_macro2::TokenStream::from(input);
+
+    let input: DeriveInput = syn::parse2(input).unwrap();
+
+    let struct_name = &input.ident;
+
+    let Data::Struct(struct_data) = &input.data else {
+        unimplemented!("Currently, there is only support for structs.");
+    };
+
+    if let syn::Fields::Unit = struct_data.fields {
+        return quote! {
+          compile_error!("Unit structs can't be a set.")
+        }
+        .into();
+    }
+
+    let error_type = match error_type(&input) {
+        Ok(error_type) => error_type,
+        Err(error) => return error.to_compile_error().into(),
+    };
+
+    let fields = fields(&struct_data.fields).into_iter().map(|(field_name, member)| {
+        quote! {
+            #member: #trait_path::#fn_name(self.#member, &rhs.#member).map_err(|error| {
+                ::finit::operations::IntoFieldError::into_field_error(error, #field_name)
+            })?
+        }
+    });
+
+    quote! {
+        impl #trait_path<&#struct_name> for #struct_name {
+            type Output = Self;
+            type Error = ::finit::operations::FieldError<#error_type>;
+
+            fn #fn_name(self, rhs: &#struct_name) -> ::core::result::Result<Self::Output, Self::Error> {
+                ::core::result::Result::Ok(#struct_name {
+                    #(#fields,)*
+                })
+            }
+        }
+    }
+    .into()
+}
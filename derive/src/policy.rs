@@ -0,0 +1,75 @@
+extern crate proc_macro;
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, parse_quote};
+
+pub fn from_node_derive(input: TokenStream) -> TokenStream {
+    let input = proc_macro2::TokenStream::from(input);
+
+    let input: DeriveInput = syn::parse2(input).unwrap();
+
+    let crate_name: syn::Path = parse_quote!(::finit);
+    let struct_name = &input.ident;
+
+    let Data::Struct(struct_data) = &input.data else {
+        unimplemented!("Currently, there is only support for structs.");
+    };
+
+    let arms: Vec<proc_macro2::TokenStream> = match &struct_data.fields {
+        syn::Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(|field| {
+                let field_name = field.ident.as_ref().expect("Struct is named.");
+                let segment = field_name.to_string();
+                quote! {
+                    #segment => #struct_name {
+                        #field_name: #crate_name::policy::FromNode::from_node(rest)?,
+                        ..<#struct_name as #crate_name::Set>::empty()
+                    },
+                }
+            })
+            .collect(),
+        syn::Fields::Unnamed(fields) => fields
+            .unnamed
+            .iter()
+            .enumerate()
+            .map(|(i, _field)| {
+                let member = syn::Index::from(i);
+                let segment = i.to_string();
+                quote! {
+                    #segment => #struct_name {
+                        #member: #crate_name::policy::FromNode::from_node(rest)?,
+                        ..<#struct_name as #crate_name::Set>::empty()
+                    },
+                }
+            })
+            .collect(),
+        syn::Fields::Unit => {
+            return quote! {
+              compile_error!("Unit structs can't be a set.")
+            }
+            .into();
+        }
+    };
+
+    quote! {
+        impl #crate_name::policy::FromNode for #struct_name {
+            fn from_node(segments: &[&str]) -> ::core::result::Result<Self, #crate_name::policy::NodeError> {
+                let ::core::option::Option::Some((segment, rest)) = segments.split_first() else {
+                    return ::core::result::Result::Err(#crate_name::policy::NodeError::Incomplete);
+                };
+
+                ::core::result::Result::Ok(match *segment {
+                    #(#arms)*
+                    _ => {
+                        return ::core::result::Result::Err(#crate_name::policy::NodeError::UnknownField(
+                            ::std::string::ToString::to_string(segment),
+                        ));
+                    }
+                })
+            }
+        }
+    }
+    .into()
+}
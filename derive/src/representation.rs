@@ -0,0 +1,79 @@
+extern crate proc_macro;
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Lit, Meta, NestedMeta, parse_quote};
+
+/// The representations that can be picked with `#[finit(representation = "...")]`.
+enum Representation {
+    Default,
+    Flat,
+}
+
+/// Reads `#[finit(representation = "...")]` from a field attribute, returning `None` for other attributes.
+fn representation(attr: &syn::Attribute) -> syn::Result<Option<Representation>> {
+    if !attr.path.is_ident("finit") {
+        return Ok(None);
+    }
+
+    let Ok(Meta::List(list)) = attr.parse_meta() else {
+        return Ok(None);
+    };
+
+    let Some(NestedMeta::Meta(Meta::NameValue(name_value))) = list.nested.first() else {
+        return Ok(None);
+    };
+
+    if !name_value.path.is_ident("representation") {
+        return Ok(None);
+    }
+
+    let Lit::Str(value) = &name_value.lit else {
+        return Err(syn::Error::new_spanned(
+            &name_value.lit,
+            "expected a string, `\"default\"` or `\"flat\"`",
+        ));
+    };
+
+    match value.value().as_str() {
+        "default" => Ok(Some(Representation::Default)),
+        "flat" => Ok(Some(Representation::Flat)),
+        _ => Err(syn::Error::new_spanned(
+            value,
+            "unknown representation, expected `\"default\"` or `\"flat\"`",
+        )),
+    }
+}
+
+pub fn represent(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return quote! {
+            compile_error!("`represent` takes no arguments, pick the representation on the fields with `#[finit(representation = \"...\")]`.");
+        }
+        .into();
+    }
+
+    let mut input: DeriveInput = syn::parse(item).unwrap();
+
+    let Data::Struct(struct_data) = &mut input.data else {
+        unimplemented!("Currently, there is only support for structs.");
+    };
+
+    for field in struct_data.fields.iter_mut() {
+        let mut attrs = Vec::with_capacity(field.attrs.len());
+
+        for attr in field.attrs.drain(..) {
+            match representation(&attr) {
+                Ok(None) => attrs.push(attr),
+                Ok(Some(Representation::Default)) => {}
+                Ok(Some(Representation::Flat)) => {
+                    attrs.push(parse_quote!(#[serde(with = "::finit::representation::flat")]))
+                }
+                Err(error) => return error.to_compile_error().into(),
+            }
+        }
+
+        field.attrs = attrs;
+    }
+
+    quote!(#input).into()
+}
@@ -0,0 +1,68 @@
+extern crate proc_macro;
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, parse_quote};
+
+pub fn permission_schema_derive(input: TokenStream) -> TokenStream {
+    let input = proc_macro2::TokenStream::from(input);
+
+    let input: DeriveInput = syn::parse2(input).unwrap();
+
+    let crate_name: syn::Path = parse_quote!(::finit);
+    let struct_name = &input.ident;
+    let struct_name_str = struct_name.to_string();
+
+    let Data::Struct(struct_data) = &input.data else {
+        unimplemented!("Currently, there is only support for structs.");
+    };
+
+    let fields: Vec<proc_macro2::TokenStream> = match &struct_data.fields {
+        syn::Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(|field| {
+                let field_name = field.ident.as_ref().expect("Struct is named.").to_string();
+                let field_type = &field.ty;
+                quote! {
+                    #crate_name::schema::Field {
+                        name: #field_name,
+                        schema: <#field_type as #crate_name::schema::PermissionSchema>::schema(),
+                    }
+                }
+            })
+            .collect(),
+        syn::Fields::Unnamed(fields) => fields
+            .unnamed
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let field_name = i.to_string();
+                let field_type = &field.ty;
+                quote! {
+                    #crate_name::schema::Field {
+                        name: #field_name,
+                        schema: <#field_type as #crate_name::schema::PermissionSchema>::schema(),
+                    }
+                }
+            })
+            .collect(),
+        syn::Fields::Unit => {
+            return quote! {
+              compile_error!("Unit structs can't be a set.")
+            }
+            .into();
+        }
+    };
+
+    quote! {
+        impl #crate_name::schema::PermissionSchema for #struct_name {
+            fn schema() -> #crate_name::schema::Schema {
+                #crate_name::schema::Schema::Struct {
+                    name: #struct_name_str,
+                    fields: ::core::convert::From::from([#(#fields),*]),
+                }
+            }
+        }
+    }
+    .into()
+}
@@ -6,6 +6,7 @@ use finit::operations::{
     Difference, DifferenceAssign, DisjunctiveUnion, DisjunctiveUnionAssign, Intersection,
     IntersectionAssign, Union, UnionAssign,
 };
+use finit::require;
 use maplit::hashmap;
 
 #[derive(
@@ -73,22 +74,6 @@ pub struct UserPerms {
     account_access: bool,
 }
 
-impl UserPerms {
-    fn is_owner_of_clan(&self, clan_name: String) -> bool {
-        let comparer = UserPerms {
-            clans: hashmap! {
-                clan_name => ClanPerms {
-                    owner: true,
-                    ..<ClanPerms as Set>::empty()
-                }
-            },
-            ..<UserPerms as Set>::empty()
-        };
-
-        comparer.subset_of(self)
-    }
-}
-
 fn main() {
     use finit::Set;
 
@@ -108,7 +93,16 @@ fn main() {
         },
     };
 
-    assert!(user_perms.is_owner_of_clan("redwood".to_string()));
+    assert!(require!(user_perms, clans["redwood".to_string()].owner => true).is_ok());
 
     println!("User is owner of redwood clan");
+
+    let missing = require!(
+        user_perms,
+        clans["redwood".to_string()].ban => true,
+        theming.can_have_dark_mode => true,
+    )
+    .unwrap_err();
+
+    println!("{missing}");
 }
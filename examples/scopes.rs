@@ -0,0 +1,66 @@
+use finit::Set;
+use finit::collections::{Scope, Scoped};
+use finit::comparisons::{SetEq, SubsetOf};
+use finit::operations::{
+    Difference, DifferenceAssign, DisjunctiveUnion, DisjunctiveUnionAssign, Intersection,
+    IntersectionAssign, Union, UnionAssign,
+};
+
+#[derive(
+    Debug,
+    Clone,
+    Set,
+    UnionAssign,
+    DifferenceAssign,
+    IntersectionAssign,
+    DisjunctiveUnionAssign,
+    Union,
+    Difference,
+    Intersection,
+    DisjunctiveUnion,
+    SetEq,
+    SubsetOf,
+    PartialEq,
+)]
+pub struct ProjectPerms {
+    read: bool,
+    write: bool,
+    deploy: bool,
+}
+
+fn main() {
+    let read = ProjectPerms {
+        read: true,
+        ..ProjectPerms::empty()
+    };
+    let write = ProjectPerms {
+        write: true,
+        ..ProjectPerms::empty()
+    };
+    let deploy = ProjectPerms {
+        deploy: true,
+        ..ProjectPerms::empty()
+    };
+
+    // A scope is the organization, then the project.
+    let mut developer = Scoped::granted(&[Scope::Id("acme")], read.clone());
+    developer.grant(&[Scope::Id("acme"), Scope::Every], write.clone());
+    developer.grant(&[Scope::Id("acme"), Scope::Id("website")], deploy);
+    developer.revoke(&[Scope::Id("acme"), Scope::Id("billing")], write);
+
+    let website = developer.at(&["acme", "website"]);
+    assert!(website.read && website.write && website.deploy);
+
+    assert_eq!(developer.at(&["acme", "billing"]), read);
+    assert!(developer.at(&["globex"]).is_empty());
+
+    println!("Developer can deploy the website, but not write to billing");
+
+    let mut auditor = Scoped::granted(&[Scope::Id("acme"), Scope::Every], read.clone());
+    auditor.revoke(&[Scope::Id("acme"), Scope::Id("billing")], read);
+
+    assert!(auditor.subset_of(&developer));
+    assert!(!developer.subset_of(&auditor));
+
+    println!("Every permission of the auditor is also held by the developer");
+}
@@ -0,0 +1,251 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+
+use super::{Decode, DecodeError, Decoder, Encode, Encoder};
+
+macro_rules! impl_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl Encode for $t {
+                fn encode(&self, encoder: &mut Encoder) {
+                    encoder.write_varint(*self as u64);
+                }
+            }
+
+            impl Decode for $t {
+                fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+                    <$t>::try_from(decoder.read_varint()?).map_err(|_| DecodeError::IntegerOverflow)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_signed {
+    ($($t:ty),*) => {
+        $(
+            impl Encode for $t {
+                fn encode(&self, encoder: &mut Encoder) {
+                    let value = *self as i64;
+
+                    encoder.write_varint(((value << 1) ^ (value >> 63)) as u64);
+                }
+            }
+
+            impl Decode for $t {
+                fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+                    let value = decoder.read_varint()?;
+                    let value = ((value >> 1) as i64) ^ -((value & 1) as i64);
+
+                    <$t>::try_from(value).map_err(|_| DecodeError::IntegerOverflow)
+                }
+            }
+        )*
+    };
+}
+
+impl_unsigned!(u8, u16, u32, u64, usize);
+impl_signed!(i8, i16, i32, i64, isize);
+
+impl Encode for char {
+    fn encode(&self, encoder: &mut Encoder) {
+        (*self as u32).encode(encoder);
+    }
+}
+
+impl Decode for char {
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+        char::from_u32(u32::decode(decoder)?).ok_or(DecodeError::InvalidUtf8)
+    }
+}
+
+impl Encode for str {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write_len(self.len());
+        encoder.write_bytes(self.as_bytes());
+    }
+}
+
+impl Encode for String {
+    fn encode(&self, encoder: &mut Encoder) {
+        self.as_str().encode(encoder);
+    }
+}
+
+impl Decode for String {
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+        let len = decoder.read_len()?;
+        let bytes = decoder.read_bytes(len)?;
+
+        core::str::from_utf8(bytes)
+            .map(String::from)
+            .map_err(|_| DecodeError::InvalidUtf8)
+    }
+}
+
+impl Encode for () {
+    fn encode(&self, _encoder: &mut Encoder) {}
+}
+
+impl Decode for () {
+    fn decode(_decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+        Ok(())
+    }
+}
+
+impl Encode for bool {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write_u8(*self as u8);
+    }
+}
+
+impl Decode for bool {
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+        match decoder.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            tag => Err(DecodeError::InvalidTag(tag)),
+        }
+    }
+}
+
+impl<Value: Encode> Encode for Option<Value> {
+    fn encode(&self, encoder: &mut Encoder) {
+        match self {
+            None => encoder.write_u8(0),
+            Some(value) => {
+                encoder.write_u8(1);
+                value.encode(encoder);
+            }
+        }
+    }
+}
+
+impl<Value: Decode> Decode for Option<Value> {
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+        match decoder.read_u8()? {
+            0 => Ok(None),
+            1 => Ok(Some(Value::decode(decoder)?)),
+            tag => Err(DecodeError::InvalidTag(tag)),
+        }
+    }
+}
+
+impl<Value: Encode + ?Sized> Encode for Box<Value> {
+    fn encode(&self, encoder: &mut Encoder) {
+        self.as_ref().encode(encoder);
+    }
+}
+
+impl<Value: Decode> Decode for Box<Value> {
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+        Value::decode(decoder).map(Box::new)
+    }
+}
+
+impl<const N: usize, Value: Encode> Encode for [Value; N] {
+    fn encode(&self, encoder: &mut Encoder) {
+        for value in self {
+            value.encode(encoder);
+        }
+    }
+}
+
+impl<const N: usize, Value: Decode> Decode for [Value; N] {
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+        let mut values = alloc::vec::Vec::with_capacity(N);
+
+        for _ in 0..N {
+            values.push(Value::decode(decoder)?);
+        }
+
+        Ok(values
+            .try_into()
+            .unwrap_or_else(|_| unreachable!("Exactly N values were decoded.")))
+    }
+}
+
+macro_rules! impl_tuples {
+    ($first:ident $(, $rest:ident)*) => {
+        impl<$first: Encode $(, $rest: Encode)*> Encode for ($first, $($rest,)*) {
+            fn encode(&self, encoder: &mut Encoder) {
+                #[allow(non_snake_case)]
+                let ($first, $($rest,)*) = self;
+
+                $first.encode(encoder);
+                $($rest.encode(encoder);)*
+            }
+        }
+
+        impl<$first: Decode $(, $rest: Decode)*> Decode for ($first, $($rest,)*) {
+            fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+                Ok(($first::decode(decoder)?, $($rest::decode(decoder)?,)*))
+            }
+        }
+
+        impl_tuples!($($rest),*);
+    };
+    () => {};
+}
+
+impl_tuples!(
+    T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15, T16
+);
+
+mod map_impl {
+    use super::*;
+    use crate::Set;
+    use alloc::collections::BTreeMap;
+    #[cfg(any(feature = "std", feature = "hashbrown"))]
+    use core::hash::Hash;
+    #[cfg(feature = "std")]
+    use std::collections::HashMap;
+
+    /// Implements [`Encode`] and [`Decode`] for a map type, rejecting duplicate keys and empty values when decoding.
+    macro_rules! impl_map_codec {
+        ($map:path, $with_capacity:expr, Key: ($($bounds:tt)*) $(, Value: $($value_bounds:tt)*)?) => {
+            impl<Key: Encode + $($bounds)*, Value: Encode> Encode for $map {
+                fn encode(&self, encoder: &mut Encoder) {
+                    encoder.write_len(self.len());
+
+                    for (key, value) in self.iter() {
+                        key.encode(encoder);
+                        value.encode(encoder);
+                    }
+                }
+            }
+
+            impl<Key: Decode + $($bounds)*, Value: Decode + Set $(+ $($value_bounds)*)?> Decode for $map {
+                fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+                    let len = decoder.read_len()?;
+                    let mut map = $with_capacity(decoder.capacity_hint(len));
+
+                    for _ in 0..len {
+                        let key = Key::decode(decoder)?;
+                        let value = Value::decode(decoder)?;
+
+                        if value.is_empty() {
+                            return Err(DecodeError::EmptyValue);
+                        }
+
+                        if map.insert(key, value).is_some() {
+                            return Err(DecodeError::DuplicateKey);
+                        }
+                    }
+
+                    Ok(map)
+                }
+            }
+        };
+    }
+
+    impl_map_codec!(BTreeMap<Key, Value>, |_| BTreeMap::new(), Key: (Ord));
+    #[cfg(feature = "std")]
+    impl_map_codec!(HashMap<Key, Value>, HashMap::with_capacity, Key: (Hash + Eq));
+    #[cfg(feature = "hashbrown")]
+    impl_map_codec!(::hashbrown::HashMap<Key, Value>, ::hashbrown::HashMap::with_capacity, Key: (Hash + Eq));
+    #[cfg(feature = "im")]
+    impl_map_codec!(::im::HashMap<Key, Value>, |_| ::im::HashMap::new(), Key: (Hash + Eq + Clone), Value: Clone);
+    #[cfg(feature = "im")]
+    impl_map_codec!(::im::OrdMap<Key, Value>, |_| ::im::OrdMap::new(), Key: (Ord + Clone), Value: Clone);
+}
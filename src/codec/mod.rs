@@ -0,0 +1,373 @@
+//! This module contains a compact, versioned binary format for sets, built around the [`Encode`] and [`Decode`] traits.
+//!
+//! The format is designed for caching large amounts of resolved sets:
+//! - Integers (and lengths) are written as LEB128 varints, signed integers are zigzag encoded first.
+//! - Strings are written as a varint length followed by their UTF-8 bytes.
+//! - Maps are written as a varint length followed by their key-value pairs.
+//! - Derived structs pack all of their `bool` fields into a bitfield, followed by the rest of the fields in declaration order.
+//! - Wildcard maps are written as a byte of section flags, followed by the wildcard, exception and rest sections that are not empty.
+//!
+//! Use [`to_bytes`] and [`from_bytes`] to encode a value with a leading format version byte.
+//! Decoding checks the invariants that the operations of this crate uphold, such as maps never containing empty values or duplicate keys and wildcard maps being canonical, and rejects any data breaking them. Decoding a `symbol::Symbol` interns its name, while a `symbol::StrictSymbol` is only decoded if its name was interned before.
+
+use alloc::vec::Vec;
+use core::fmt::Display;
+
+#[cfg(feature = "derive")]
+pub use finit_derive::{Decode, Encode};
+
+mod impls;
+
+/// The section flags of wildcard maps, written as a single byte before the sections that are present.
+pub(crate) const WILDCARD_SECTION: u8 = 1 << 0;
+pub(crate) const EXCEPTIONS_SECTION: u8 = 1 << 1;
+pub(crate) const REST_SECTION: u8 = 1 << 2;
+
+/// The version of the binary format written by [`to_bytes`]. [`from_bytes`] rejects data with any other version.
+pub const FORMAT_VERSION: u8 = 1;
+
+/// Encodes a value, prefixed by the [`FORMAT_VERSION`].
+pub fn to_bytes<T: Encode + ?Sized>(value: &T) -> Vec<u8> {
+    let mut encoder = Encoder::new();
+
+    encoder.write_u8(FORMAT_VERSION);
+    value.encode(&mut encoder);
+
+    encoder.into_bytes()
+}
+
+/// Decodes a value encoded by [`to_bytes`], checking the format version and that no bytes are left over.
+pub fn from_bytes<T: Decode>(bytes: &[u8]) -> Result<T, DecodeError> {
+    let mut decoder = Decoder::new(bytes);
+
+    let version = decoder.read_u8()?;
+    if version != FORMAT_VERSION {
+        return Err(DecodeError::UnsupportedVersion(version));
+    }
+
+    let value = T::decode(&mut decoder)?;
+
+    if !decoder.is_empty() {
+        return Err(DecodeError::TrailingBytes);
+    }
+
+    Ok(value)
+}
+
+/// [`Encode`] writes a value to an [`Encoder`].
+pub trait Encode {
+    fn encode(&self, encoder: &mut Encoder);
+}
+
+/// [`Decode`] reads a value from a [`Decoder`], checking any invariants of the type.
+pub trait Decode: Sized {
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError>;
+}
+
+/// The errors that can occur while decoding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The data ended before the value was fully decoded.
+    UnexpectedEnd,
+    /// There were bytes left after the value was decoded.
+    TrailingBytes,
+    /// The format version is not supported by this version of the crate.
+    UnsupportedVersion(u8),
+    /// A varint was longer than its type allows.
+    VarintOverflow,
+    /// A decoded number does not fit in the type it is decoded into.
+    IntegerOverflow,
+    /// A byte that should be a `bool` or tag had an unknown value.
+    InvalidTag(u8),
+    /// The unused bits of a bitfield were set.
+    InvalidPadding,
+    /// A string was not valid UTF-8, or a char was not a valid unicode scalar value.
+    InvalidUtf8,
+    /// A map contained the same key more than once.
+    DuplicateKey,
+    /// A map or section contained an empty value, which the set operations never produce.
+    EmptyValue,
+    /// A wildcard map had exceptions that are not part of its wildcard value, or rest list values overlapping it, which the set operations never produce.
+    NotCanonical,
+    /// A `symbol::StrictSymbol` was not interned before decoding, since untrusted data may not grow the interner.
+    UnknownSymbol,
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DecodeError::UnexpectedEnd => write!(f, "unexpected end of data"),
+            DecodeError::TrailingBytes => write!(f, "trailing bytes after value"),
+            DecodeError::UnsupportedVersion(version) => {
+                write!(f, "unsupported format version {version}")
+            }
+            DecodeError::VarintOverflow => write!(f, "varint is too long"),
+            DecodeError::IntegerOverflow => write!(f, "integer does not fit in its type"),
+            DecodeError::InvalidTag(tag) => write!(f, "invalid tag {tag}"),
+            DecodeError::InvalidPadding => write!(f, "unused bits of bitfield are set"),
+            DecodeError::InvalidUtf8 => write!(f, "invalid utf-8"),
+            DecodeError::DuplicateKey => write!(f, "duplicate key in map"),
+            DecodeError::EmptyValue => write!(f, "empty value in map"),
+            DecodeError::NotCanonical => write!(f, "wildcard map is not canonical"),
+            DecodeError::UnknownSymbol => write!(f, "symbol is not interned"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+/// [`Encoder`] is a growable buffer that values are encoded into.
+#[derive(Debug, Clone, Default)]
+pub struct Encoder {
+    bytes: Vec<u8>,
+}
+
+impl Encoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    /// Writes an unsigned LEB128 varint.
+    pub fn write_varint(&mut self, mut value: u64) {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+
+            if value == 0 {
+                self.write_u8(byte);
+                return;
+            }
+
+            self.write_u8(byte | 0x80);
+        }
+    }
+
+    /// Writes the length of a collection as a varint.
+    pub fn write_len(&mut self, len: usize) {
+        self.write_varint(len as u64);
+    }
+
+    /// Writes a list of bools as a bitfield, least significant bit first.
+    pub fn write_bools(&mut self, bools: &[bool]) {
+        for chunk in bools.chunks(8) {
+            let byte = chunk
+                .iter()
+                .enumerate()
+                .fold(0u8, |byte, (i, value)| byte | ((*value as u8) << i));
+
+            self.write_u8(byte);
+        }
+    }
+}
+
+/// [`Decoder`] reads values from a byte slice.
+#[derive(Debug, Clone)]
+pub struct Decoder<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    /// Returns true if all bytes have been read.
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, DecodeError> {
+        let (first, rest) = self.bytes.split_first().ok_or(DecodeError::UnexpectedEnd)?;
+        self.bytes = rest;
+
+        Ok(*first)
+    }
+
+    pub fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        if self.bytes.len() < len {
+            return Err(DecodeError::UnexpectedEnd);
+        }
+
+        let (bytes, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+
+        Ok(bytes)
+    }
+
+    /// Reads an unsigned LEB128 varint.
+    pub fn read_varint(&mut self) -> Result<u64, DecodeError> {
+        let mut value: u64 = 0;
+
+        for shift in (0..64).step_by(7) {
+            let byte = self.read_u8()?;
+            let bits = (byte & 0x7f) as u64;
+
+            if shift == 63 && bits > 1 {
+                return Err(DecodeError::VarintOverflow);
+            }
+
+            value |= bits << shift;
+
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Err(DecodeError::VarintOverflow)
+    }
+
+    /// Reads the length of a collection written by [`Encoder::write_len`].
+    pub fn read_len(&mut self) -> Result<usize, DecodeError> {
+        usize::try_from(self.read_varint()?).map_err(|_| DecodeError::IntegerOverflow)
+    }
+
+    /// Returns the capacity to preallocate for a collection of `len` elements, bounded by the remaining bytes so corrupt lengths can't cause huge allocations.
+    pub fn capacity_hint(&self, len: usize) -> usize {
+        len.min(self.bytes.len())
+    }
+
+    /// Reads `N` bools written by [`Encoder::write_bools`].
+    pub fn read_bools<const N: usize>(&mut self) -> Result<[bool; N], DecodeError> {
+        let mut bools = [false; N];
+
+        for chunk in bools.chunks_mut(8) {
+            let byte = self.read_u8()?;
+
+            if chunk.len() < 8 && byte >> chunk.len() != 0 {
+                return Err(DecodeError::InvalidPadding);
+            }
+
+            for (i, value) in chunk.iter_mut().enumerate() {
+                *value = byte & (1 << i) != 0;
+            }
+        }
+
+        Ok(bools)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::fmt::Debug;
+
+    use rstest::*;
+
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[rstest]
+    #[case::zero(0, &[0])]
+    #[case::one_byte(127, &[0x7f])]
+    #[case::two_bytes(128, &[0x80, 0x01])]
+    #[case::max(u64::MAX, &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01])]
+    fn varint_tests(#[case] value: u64, #[case] bytes: &[u8]) {
+        let mut encoder = Encoder::new();
+        encoder.write_varint(value);
+        assert_eq!(encoder.into_bytes(), bytes);
+
+        assert_eq!(Decoder::new(bytes).read_varint(), Ok(value));
+    }
+
+    #[rstest]
+    #[case::too_long(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02])]
+    #[case::never_ending(&[0xff; 11])]
+    fn varint_overflow_tests(#[case] bytes: &[u8]) {
+        assert_eq!(
+            Decoder::new(bytes).read_varint(),
+            Err(DecodeError::VarintOverflow)
+        );
+    }
+
+    #[test]
+    fn bools_test() {
+        let bools = [true, false, true, true, false, false, false, false, true];
+
+        let mut encoder = Encoder::new();
+        encoder.write_bools(&bools);
+        let bytes = encoder.into_bytes();
+        assert_eq!(bytes, [0b0000_1101, 0b0000_0001]);
+
+        assert_eq!(Decoder::new(&bytes).read_bools::<9>(), Ok(bools));
+        assert_eq!(
+            Decoder::new(&[0, 0b10]).read_bools::<9>(),
+            Err(DecodeError::InvalidPadding)
+        );
+    }
+
+    #[rstest]
+    #[case::bool(true)]
+    #[case::option(Some(true))]
+    #[case::none(Option::<bool>::None)]
+    #[case::array([true, false, true])]
+    #[case::tuple((true, false, Some(true)))]
+    #[case::boxed(alloc::boxed::Box::new(true))]
+    fn round_trip_tests<T: Encode + Decode + PartialEq + Debug>(#[case] value: T) {
+        assert_eq!(from_bytes::<T>(&to_bytes(&value)), Ok(value));
+    }
+
+    #[cfg(feature = "std")]
+    #[rstest]
+    #[case::hashmap(maplit::hashmap! {
+        1u32 => true,
+        300 => true,
+    })]
+    #[case::nested_hashmap(maplit::hashmap! {
+        "a".to_string() => maplit::hashmap! { -1i64 => true },
+        "b".to_string() => maplit::hashmap! { i64::MIN => true, i64::MAX => true },
+    })]
+    #[case::btreemap(maplit::btreemap! {
+        'a' => maplit::btreemap! { 0u8 => true },
+    })]
+    fn map_round_trip_tests<T: Encode + Decode + PartialEq + Debug>(#[case] value: T) {
+        assert_eq!(from_bytes::<T>(&to_bytes(&value)), Ok(value));
+    }
+
+    #[test]
+    fn header_tests() {
+        assert_eq!(to_bytes(&true), [FORMAT_VERSION, 1]);
+        assert_eq!(
+            from_bytes::<bool>(&[FORMAT_VERSION + 1, 1]),
+            Err(DecodeError::UnsupportedVersion(FORMAT_VERSION + 1))
+        );
+        assert_eq!(
+            from_bytes::<bool>(&[FORMAT_VERSION, 1, 0]),
+            Err(DecodeError::TrailingBytes)
+        );
+        assert_eq!(
+            from_bytes::<bool>(&[FORMAT_VERSION]),
+            Err(DecodeError::UnexpectedEnd)
+        );
+        assert_eq!(
+            from_bytes::<bool>(&[FORMAT_VERSION, 2]),
+            Err(DecodeError::InvalidTag(2))
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[rstest]
+    // Map of length 2 with key 1 twice.
+    #[case::duplicate_key(&[FORMAT_VERSION, 2, 1, 1, 1, 1], DecodeError::DuplicateKey)]
+    // Map of length 1 with an empty value.
+    #[case::empty_value(&[FORMAT_VERSION, 1, 1, 0], DecodeError::EmptyValue)]
+    // Map claiming a huge length.
+    #[case::huge_length(&[FORMAT_VERSION, 0xff, 0xff, 0xff, 0xff, 0x0f], DecodeError::UnexpectedEnd)]
+    fn invalid_map_tests(#[case] bytes: &[u8], #[case] error: DecodeError) {
+        assert_eq!(
+            from_bytes::<std::collections::HashMap<u32, bool>>(bytes),
+            Err(error)
+        );
+    }
+}
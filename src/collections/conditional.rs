@@ -0,0 +1,366 @@
+use crate::Set;
+use crate::comparisons::SubsetOf;
+use crate::operations::{DifferenceAssign, UnionAssign};
+use alloc::vec::Vec;
+
+/// [`Condition`] is implemented by the conditions of a [`Conditional`] set, which are checked against a user-defined context when the set is evaluated.
+pub trait Condition<Context: ?Sized> {
+    fn holds(&self, context: &Context) -> bool;
+}
+
+/// A set of grants that only apply when their condition holds, together with grants that always apply.
+///
+/// Conditions are opaque, so operations only combine the grants of equal conditions:
+/// - The union of two sets has the grants of both, and the grants of equal conditions are merged.
+/// - The difference of two sets removes every grant of the right hand side, conditional or not, from every grant. Since it is unknown when the conditions of the right hand side hold, this under-approximates: the result never grants anything the exact difference would not, but `{kick} − {kick if C}` is empty instead of `{kick if not C}`.
+///
+/// ```
+/// # use std::collections::HashSet;
+/// # use finit::collections::{Condition, Conditional};
+/// #[derive(Clone, PartialEq)]
+/// enum When {
+///     BusinessHours,
+/// }
+///
+/// impl Condition<u8> for When {
+///     fn holds(&self, hour: &u8) -> bool {
+///         (9..17).contains(hour)
+///     }
+/// }
+///
+/// let mut perms = Conditional::new(HashSet::from(["chat"]));
+/// perms.grant_if(When::BusinessHours, HashSet::from(["refund"]));
+///
+/// assert_eq!(perms.evaluate(&10), HashSet::from(["chat", "refund"]));
+/// assert_eq!(perms.evaluate(&20), HashSet::from(["chat"]));
+/// ```
+///
+/// With serde, it is a map with the `unconditional` grants and the `conditional` list of conditions and grants. Deserializing adds the conditional grants one by one like [`Conditional::grant_if`], so the grants of repeated conditions are merged and empty grants are dropped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        from = "ConditionalParts<S, C>",
+        bound(
+            deserialize = "S: Set<Empty = S> + Clone + serde::Deserialize<'de>, C: PartialEq + Clone + serde::Deserialize<'de>, for<'a> S: UnionAssign<&'a S> + DifferenceAssign<&'a S>"
+        )
+    )
+)]
+pub struct Conditional<S, C> {
+    /// The grants that always apply.
+    unconditional: S,
+    /// The grants of every condition, without what is granted unconditionally. Every condition is listed at most once and no grant is empty.
+    conditional: Vec<(C, S)>,
+}
+
+impl<S, C> Conditional<S, C> {
+    pub fn new(unconditional: S) -> Self {
+        Self {
+            unconditional,
+            conditional: Vec::new(),
+        }
+    }
+
+    /// The grants that always apply.
+    pub fn unconditional(&self) -> &S {
+        &self.unconditional
+    }
+
+    /// The grants that only apply when their condition holds, without what is granted unconditionally.
+    pub fn conditional(&self) -> &[(C, S)] {
+        &self.conditional
+    }
+
+    /// The grants of `condition`, without what is granted unconditionally.
+    pub fn grants_of(&self, condition: &C) -> Option<&S>
+    where
+        C: PartialEq,
+    {
+        self.conditional
+            .iter()
+            .find(|(other, _)| other == condition)
+            .map(|(_, set)| set)
+    }
+}
+
+impl<S: Set<Empty = S> + Clone, C> Conditional<S, C>
+where
+    for<'a> S: UnionAssign<&'a S>,
+{
+    /// The set that is granted in `context`, which is the unconditional grants together with the grants of every condition that holds.
+    pub fn evaluate<Context: ?Sized>(&self, context: &Context) -> S
+    where
+        C: Condition<Context>,
+    {
+        let mut set = self.unconditional.clone();
+
+        for (_, part) in self
+            .conditional
+            .iter()
+            .filter(|(condition, _)| condition.holds(context))
+        {
+            set.union_assign(part);
+        }
+
+        set
+    }
+}
+
+impl<S: Set<Empty = S> + Clone, C: PartialEq + Clone> Conditional<S, C>
+where
+    for<'a> S: UnionAssign<&'a S> + DifferenceAssign<&'a S>,
+{
+    /// Grants `set` when `condition` holds.
+    pub fn grant_if(&mut self, condition: C, set: S) {
+        self.union_assign(&Conditional {
+            unconditional: S::empty(),
+            conditional: alloc::vec![(condition, set)],
+        });
+    }
+
+    /// Removes the unconditional grants from the conditional ones, and drops conditions without grants.
+    fn normalize(&mut self) {
+        let unconditional = &self.unconditional;
+
+        self.conditional.retain_mut(|(_, part)| {
+            part.difference_assign(unconditional);
+            !part.is_empty()
+        });
+    }
+}
+
+/// The fields of [`Conditional`] as they are deserialized, before they are normalized.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct ConditionalParts<S, C> {
+    unconditional: S,
+    conditional: Vec<(C, S)>,
+}
+
+#[cfg(feature = "serde")]
+impl<S: Set<Empty = S> + Clone, C: PartialEq + Clone> From<ConditionalParts<S, C>>
+    for Conditional<S, C>
+where
+    for<'a> S: UnionAssign<&'a S> + DifferenceAssign<&'a S>,
+{
+    fn from(parts: ConditionalParts<S, C>) -> Self {
+        let mut conditional = Conditional::new(parts.unconditional);
+        for (condition, set) in parts.conditional {
+            conditional.grant_if(condition, set);
+        }
+        conditional
+    }
+}
+
+impl<S: Set<Empty = S>, C> Set for Conditional<S, C> {
+    type Empty = Self;
+
+    fn is_empty(&self) -> bool {
+        self.unconditional.is_empty() && self.conditional.is_empty()
+    }
+
+    fn empty() -> Self {
+        Self::new(S::empty())
+    }
+}
+
+impl<S: Set<Empty = S>, C> Default for Conditional<S, C> {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<S: Set<Empty = S> + Clone, C: PartialEq + Clone> UnionAssign<&Conditional<S, C>>
+    for Conditional<S, C>
+where
+    for<'a> S: UnionAssign<&'a S> + DifferenceAssign<&'a S>,
+{
+    fn union_assign(&mut self, rhs: &Conditional<S, C>) {
+        self.unconditional.union_assign(&rhs.unconditional);
+
+        for (condition, rhs_part) in &rhs.conditional {
+            match self
+                .conditional
+                .iter_mut()
+                .find(|(other, _)| other == condition)
+            {
+                Some((_, part)) => part.union_assign(rhs_part),
+                None => self.conditional.push((condition.clone(), rhs_part.clone())),
+            }
+        }
+
+        self.normalize();
+    }
+}
+
+impl<S: Set<Empty = S> + Clone, C: PartialEq + Clone> DifferenceAssign<&Conditional<S, C>>
+    for Conditional<S, C>
+where
+    for<'a> S: UnionAssign<&'a S> + DifferenceAssign<&'a S>,
+{
+    fn difference_assign(&mut self, rhs: &Conditional<S, C>) {
+        // Any condition of rhs may hold, so everything it could grant is removed.
+        let mut revoked = rhs.unconditional.clone();
+        for (_, rhs_part) in &rhs.conditional {
+            revoked.union_assign(rhs_part);
+        }
+
+        self.unconditional.difference_assign(&revoked);
+
+        for (_, part) in &mut self.conditional {
+            part.difference_assign(&revoked);
+        }
+
+        self.normalize();
+    }
+}
+
+/// Checks that `self` is a subset of `rhs` in every context.
+///
+/// Since conditions are opaque, this is conservative: conditional grants of `self` must be granted unconditionally or under the same condition by `rhs`, even if another condition of `rhs` would always hold with it.
+impl<S: Set<Empty = S> + Clone + SubsetOf, C: PartialEq> SubsetOf for Conditional<S, C>
+where
+    for<'a> S: UnionAssign<&'a S>,
+{
+    fn subset_of(&self, rhs: &Conditional<S, C>) -> bool {
+        self.unconditional.subset_of(&rhs.unconditional)
+            && self
+                .conditional
+                .iter()
+                .all(|(condition, part)| match rhs.grants_of(condition) {
+                    Some(rhs_part) => {
+                        let mut granted = rhs.unconditional.clone();
+                        granted.union_assign(rhs_part);
+                        part.subset_of(&granted)
+                    }
+                    None => part.subset_of(&rhs.unconditional),
+                })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use rstest::*;
+
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+    enum When {
+        OwnsChannel,
+        BusinessHours,
+    }
+
+    struct Request {
+        owns_channel: bool,
+        hour: u8,
+    }
+
+    impl Condition<Request> for When {
+        fn holds(&self, request: &Request) -> bool {
+            match self {
+                When::OwnsChannel => request.owns_channel,
+                When::BusinessHours => (9..17).contains(&request.hour),
+            }
+        }
+    }
+
+    type Perms = Conditional<HashSet<&'static str>, When>;
+
+    fn perms<const N: usize>(
+        unconditional: &[&'static str],
+        conditional: [(When, &[&'static str]); N],
+    ) -> Perms {
+        let mut perms = Conditional::new(unconditional.iter().copied().collect());
+        for (condition, part) in conditional {
+            perms.grant_if(condition, part.iter().copied().collect());
+        }
+        perms
+    }
+
+    #[rstest]
+    #[case(Request { owns_channel: true, hour: 10 }, &["chat", "kick", "refund"])]
+    #[case(Request { owns_channel: true, hour: 20 }, &["chat", "kick"])]
+    #[case(Request { owns_channel: false, hour: 20 }, &["chat"])]
+    fn evaluate_tests(#[case] request: Request, #[case] expected: &[&str]) {
+        let perms = perms(
+            &["chat"],
+            [
+                (When::OwnsChannel, &["kick"]),
+                (When::BusinessHours, &["refund"]),
+            ],
+        );
+
+        assert_eq!(
+            perms.evaluate(&request),
+            expected.iter().copied().collect::<HashSet<_>>()
+        );
+    }
+
+    #[rstest]
+    #[case(perms(&[], [(When::OwnsChannel, &["kick"])]), perms(&[], [(When::OwnsChannel, &["ban"])]), perms(&[], [(When::OwnsChannel, &["kick", "ban"])]))]
+    #[case(perms(&[], [(When::OwnsChannel, &["kick"])]), perms(&["kick"], []), perms(&["kick"], []))]
+    #[case(perms(&["chat"], []), perms(&[], [(When::BusinessHours, &["chat", "refund"])]), perms(&["chat"], [(When::BusinessHours, &["refund"])]))]
+    fn union_tests(#[case] mut a: Perms, #[case] b: Perms, #[case] result: Perms) {
+        a.union_assign(&b);
+        assert_eq!(a, result);
+    }
+
+    #[rstest]
+    #[case(perms(&[], [(When::OwnsChannel, &["kick", "ban"])]), perms(&[], [(When::OwnsChannel, &["ban"])]), perms(&[], [(When::OwnsChannel, &["kick"])]))]
+    #[case(perms(&[], [(When::OwnsChannel, &["kick"])]), perms(&["kick"], []), perms(&[], []))]
+    #[case(perms(&["kick", "chat"], []), perms(&[], [(When::OwnsChannel, &["kick"])]), perms(&["chat"], []))]
+    #[case(perms(&[], [(When::OwnsChannel, &["kick"])]), perms(&[], [(When::BusinessHours, &["kick"])]), perms(&[], []))]
+    #[case(perms(&["chat"], [(When::OwnsChannel, &["kick", "ban"])]), perms(&[], [(When::BusinessHours, &["kick"])]), perms(&["chat"], [(When::OwnsChannel, &["ban"])]))]
+    fn difference_tests(#[case] mut a: Perms, #[case] b: Perms, #[case] result: Perms) {
+        let original = a.clone();
+        a.difference_assign(&b);
+        assert_eq!(a, result);
+
+        // The difference never grants what the exact difference in some context would not.
+        for owns_channel in [false, true] {
+            for hour in [10, 20] {
+                let request = Request { owns_channel, hour };
+                let mut exact = original.evaluate(&request);
+                exact.difference_assign(&b.evaluate(&request));
+
+                assert!(a.evaluate(&request).is_subset(&exact));
+            }
+        }
+    }
+
+    #[rstest]
+    #[case(perms(&[], [(When::OwnsChannel, &["kick"])]), perms(&["kick"], []), true)]
+    #[case(perms(&[], [(When::OwnsChannel, &["kick"])]), perms(&[], [(When::OwnsChannel, &["kick"])]), true)]
+    #[case(perms(&["kick"], []), perms(&[], [(When::OwnsChannel, &["kick"])]), false)]
+    #[case(perms(&[], [(When::OwnsChannel, &["kick"])]), perms(&[], [(When::BusinessHours, &["kick"])]), false)]
+    fn subset_of_tests(#[case] a: Perms, #[case] b: Perms, #[case] expected: bool) {
+        assert_eq!(a.subset_of(&b), expected);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_test() {
+        let perms: Perms = serde_json::from_str(
+            r#"{
+                "unconditional": ["chat"],
+                "conditional": [
+                    ["BusinessHours", ["a"]],
+                    ["BusinessHours", ["b", "chat"]],
+                    ["OwnsChannel", []],
+                    ["OwnsChannel", ["chat"]]
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            perms,
+            self::perms(&["chat"], [(When::BusinessHours, &["a", "b"])])
+        );
+    }
+}
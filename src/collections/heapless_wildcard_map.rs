@@ -0,0 +1,321 @@
+use crate::Set;
+use crate::collections::CapacityError;
+use crate::comparisons::SubsetOf;
+use crate::operations::{
+    DifferenceAssign, DisjunctiveUnionAssign, IntersectionAssign, TryDifference,
+    TryDifferenceAssign, TryDisjunctiveUnion, TryDisjunctiveUnionAssign, TryIntersection,
+    TryIntersectionAssign, TryUnion, TryUnionAssign, UnionAssign,
+};
+use heapless::LinearMap;
+
+/// A fixed-capacity variant of [`super::WildcardBTreeMap`] using [`heapless::LinearMap`], which needs no allocator.
+///
+/// The exceptions and the rest list can each hold at most `N` keys. Since any operation can add keys to them, all operations are fallible and return [`CapacityError`] without changing the map if the result does not fit.
+///
+/// Values are combined with their infallible operations, so they should be sets like `bool`, arrays or tuples. Heapless maps as values are not supported, since a nested union could run out of capacity after other keys were already changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaplessWildcardMap<Key: Eq + Clone, Value: Set<Empty = Value>, const N: usize> {
+    /// The value that every key has, unless excepted.
+    wildcard_value: Value,
+    /// The parts of the wildcard value that specific keys do not have.
+    wildcard_exceptions: LinearMap<Key, Value, N>,
+    /// The values that specific keys have in addition to the wildcard value.
+    rest_list: LinearMap<Key, Value, N>,
+}
+
+impl<Key: Eq + Clone, Value: Set<Empty = Value>, const N: usize>
+    HeaplessWildcardMap<Key, Value, N>
+{
+    pub fn new(wildcard_value: Value) -> Self {
+        Self {
+            wildcard_value,
+            wildcard_exceptions: LinearMap::new(),
+            rest_list: LinearMap::new(),
+        }
+    }
+
+    /// The value that every key has, unless excepted.
+    pub fn wildcard_value(&self) -> &Value {
+        &self.wildcard_value
+    }
+
+    /// The parts of the wildcard value that specific keys do not have.
+    pub fn wildcard_exceptions(&self) -> &LinearMap<Key, Value, N> {
+        &self.wildcard_exceptions
+    }
+
+    /// The values that specific keys have in addition to the wildcard value.
+    pub fn rest_list(&self) -> &LinearMap<Key, Value, N> {
+        &self.rest_list
+    }
+}
+
+impl<Key: Eq + Clone, Value: Set<Empty = Value> + Clone, const N: usize>
+    HeaplessWildcardMap<Key, Value, N>
+where
+    for<'a> Value: DifferenceAssign<&'a Value> + UnionAssign<&'a Value>,
+{
+    /// The value that `key` has, which is the wildcard value without the key's exception, together with the key's rest list value.
+    pub fn value_of(&self, key: &Key) -> Value {
+        let mut value = self.wildcard_value.clone();
+
+        if let Some(exception) = self.wildcard_exceptions.get(key) {
+            value.difference_assign(exception);
+        }
+
+        if let Some(rest_value) = self.rest_list.get(key) {
+            value.union_assign(rest_value);
+        }
+
+        value
+    }
+
+    /// Applies `operation` to the value of every key, building the result next to the current map so that it is left unchanged if the result does not fit.
+    fn try_combine_assign<OtherValue, const M: usize>(
+        &mut self,
+        rhs: &HeaplessWildcardMap<Key, OtherValue, M>,
+        operation: impl Fn(&mut Value, &OtherValue),
+    ) -> Result<(), CapacityError>
+    where
+        OtherValue: Set<Empty = OtherValue> + Clone,
+        for<'a> OtherValue: DifferenceAssign<&'a OtherValue> + UnionAssign<&'a OtherValue>,
+    {
+        let mut wildcard_value = self.wildcard_value.clone();
+        operation(&mut wildcard_value, &rhs.wildcard_value);
+
+        let mut wildcard_exceptions = LinearMap::new();
+        let mut rest_list = LinearMap::new();
+
+        let keys = self
+            .wildcard_exceptions
+            .keys()
+            .chain(self.rest_list.keys())
+            .chain(rhs.wildcard_exceptions.keys())
+            .chain(rhs.rest_list.keys());
+
+        for key in keys {
+            if wildcard_exceptions.contains_key(key) || rest_list.contains_key(key) {
+                continue;
+            }
+
+            let mut value = self.value_of(key);
+            operation(&mut value, &rhs.value_of(key));
+
+            let mut exception = wildcard_value.clone();
+            exception.difference_assign(&value);
+            if !exception.is_empty() {
+                wildcard_exceptions
+                    .insert(key.clone(), exception)
+                    .map_err(|_| CapacityError)?;
+            }
+
+            value.difference_assign(&wildcard_value);
+            if !value.is_empty() {
+                rest_list
+                    .insert(key.clone(), value)
+                    .map_err(|_| CapacityError)?;
+            }
+        }
+
+        self.wildcard_value = wildcard_value;
+        self.wildcard_exceptions = wildcard_exceptions;
+        self.rest_list = rest_list;
+
+        Ok(())
+    }
+}
+
+impl<Key: Eq + Clone, Value: Set<Empty = Value>, const N: usize> Set
+    for HeaplessWildcardMap<Key, Value, N>
+{
+    type Empty = Self;
+
+    fn is_empty(&self) -> bool {
+        self.rest_list.is_empty() && self.wildcard_value.is_empty()
+    }
+
+    fn empty() -> Self::Empty {
+        Self::new(Value::empty())
+    }
+}
+
+impl<Key: Eq + Clone, Value: Set<Empty = Value>, const N: usize> Default
+    for HeaplessWildcardMap<Key, Value, N>
+{
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+macro_rules! impl_try_operation {
+    ($try_trait:ident, $try_func:ident, $try_non_assign_trait:ident, $try_non_assign_func:ident, $trait:ident, $func:ident) => {
+        impl<Key, Value, OtherValue, const N: usize, const M: usize>
+            $try_trait<&HeaplessWildcardMap<Key, OtherValue, M>>
+            for HeaplessWildcardMap<Key, Value, N>
+        where
+            Key: Eq + Clone,
+            Value: Set<Empty = Value> + Clone,
+            for<'a> Value:
+                DifferenceAssign<&'a Value> + UnionAssign<&'a Value> + $trait<&'a OtherValue>,
+            OtherValue: Set<Empty = OtherValue> + Clone,
+            for<'a> OtherValue: DifferenceAssign<&'a OtherValue> + UnionAssign<&'a OtherValue>,
+        {
+            type Error = CapacityError;
+
+            fn $try_func(
+                &mut self,
+                rhs: &HeaplessWildcardMap<Key, OtherValue, M>,
+            ) -> Result<(), CapacityError> {
+                self.try_combine_assign(rhs, |value, other_value| value.$func(other_value))
+            }
+        }
+
+        impl<'a, Key, Value, OtherValue, const N: usize, const M: usize>
+            $try_non_assign_trait<&'a HeaplessWildcardMap<Key, OtherValue, M>>
+            for HeaplessWildcardMap<Key, Value, N>
+        where
+            Key: Eq + Clone,
+            Value: Set<Empty = Value>,
+            Self: $try_trait<&'a HeaplessWildcardMap<Key, OtherValue, M>, Error = CapacityError>,
+            OtherValue: Set<Empty = OtherValue>,
+        {
+            type Output = Self;
+            type Error = CapacityError;
+
+            fn $try_non_assign_func(
+                mut self,
+                rhs: &'a HeaplessWildcardMap<Key, OtherValue, M>,
+            ) -> Result<Self, CapacityError> {
+                self.$try_func(rhs)?;
+
+                Ok(self)
+            }
+        }
+    };
+}
+
+impl_try_operation!(
+    TryUnionAssign,
+    try_union_assign,
+    TryUnion,
+    try_union,
+    UnionAssign,
+    union_assign
+);
+impl_try_operation!(
+    TryDifferenceAssign,
+    try_difference_assign,
+    TryDifference,
+    try_difference,
+    DifferenceAssign,
+    difference_assign
+);
+impl_try_operation!(
+    TryIntersectionAssign,
+    try_intersection_assign,
+    TryIntersection,
+    try_intersection,
+    IntersectionAssign,
+    intersection_assign
+);
+impl_try_operation!(
+    TryDisjunctiveUnionAssign,
+    try_disjunctive_union_assign,
+    TryDisjunctiveUnion,
+    try_disjunctive_union,
+    DisjunctiveUnionAssign,
+    disjunctive_union_assign
+);
+
+impl<Key, Value, OtherValue, const N: usize, const M: usize>
+    SubsetOf<HeaplessWildcardMap<Key, OtherValue, M>> for HeaplessWildcardMap<Key, Value, N>
+where
+    Key: Eq + Clone,
+    Value: Set<Empty = Value> + Clone + SubsetOf<OtherValue>,
+    for<'a> Value: DifferenceAssign<&'a Value> + UnionAssign<&'a Value>,
+    OtherValue: Set<Empty = OtherValue> + Clone,
+    for<'a> OtherValue: DifferenceAssign<&'a OtherValue> + UnionAssign<&'a OtherValue>,
+{
+    fn subset_of(&self, rhs: &HeaplessWildcardMap<Key, OtherValue, M>) -> bool {
+        self.wildcard_value.subset_of(&rhs.wildcard_value)
+            && self
+                .wildcard_exceptions
+                .keys()
+                .chain(self.rest_list.keys())
+                .chain(rhs.wildcard_exceptions.keys())
+                .chain(rhs.rest_list.keys())
+                .all(|key| self.value_of(key).subset_of(&rhs.value_of(key)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    #[allow(unused_imports)]
+    use super::*;
+
+    type Map = HeaplessWildcardMap<u8, bool, 2>;
+
+    /// A map from its parts, like the one the operations would build.
+    fn map(wildcard_value: bool, wildcard_exceptions: &[u8], rest_list: &[u8]) -> Map {
+        HeaplessWildcardMap {
+            wildcard_value,
+            wildcard_exceptions: wildcard_exceptions.iter().map(|key| (*key, true)).collect(),
+            rest_list: rest_list.iter().map(|key| (*key, true)).collect(),
+        }
+    }
+
+    #[rstest]
+    #[case(map(false, &[], &[0]), map(false, &[], &[1]), map(false, &[], &[0, 1]))]
+    #[case(map(true, &[0], &[]), map(false, &[], &[0]), map(true, &[], &[]))]
+    #[case(map(true, &[0, 1], &[]), map(true, &[1], &[]), map(true, &[1], &[]))]
+    fn union_tests(#[case] mut a: Map, #[case] b: Map, #[case] result: Map) {
+        a.try_union_assign(&b).unwrap();
+        assert_eq!(a, result);
+    }
+
+    #[rstest]
+    #[case(map(true, &[], &[]), map(false, &[], &[0]), map(true, &[0], &[]))]
+    #[case(map(false, &[], &[0, 1]), map(true, &[0], &[]), map(false, &[], &[0]))]
+    fn difference_tests(#[case] mut a: Map, #[case] b: Map, #[case] result: Map) {
+        a.try_difference_assign(&b).unwrap();
+        assert_eq!(a, result);
+    }
+
+    #[rstest]
+    #[case(map(true, &[0], &[]), map(false, &[], &[0, 1]), map(false, &[], &[1]))]
+    #[case(map(true, &[0], &[]), map(true, &[1], &[]), map(true, &[0, 1], &[]))]
+    fn intersection_tests(#[case] mut a: Map, #[case] b: Map, #[case] result: Map) {
+        a.try_intersection_assign(&b).unwrap();
+        assert_eq!(a, result);
+    }
+
+    #[rstest]
+    #[case(map(true, &[], &[]), map(false, &[], &[0]), map(true, &[0], &[]))]
+    #[case(map(true, &[0], &[]), map(true, &[], &[]), map(false, &[], &[0]))]
+    fn disjunctive_union_tests(#[case] mut a: Map, #[case] b: Map, #[case] result: Map) {
+        a.try_disjunctive_union_assign(&b).unwrap();
+        assert_eq!(a, result);
+    }
+
+    #[test]
+    fn capacity_test() {
+        let mut a = map(true, &[0, 1], &[]);
+
+        assert_eq!(
+            a.try_difference_assign(&map(false, &[], &[2])),
+            Err(CapacityError)
+        );
+        assert_eq!(a, map(true, &[0, 1], &[]));
+    }
+
+    #[rstest]
+    #[case(map(true, &[0], &[]), map(true, &[], &[]), true)]
+    #[case(map(true, &[], &[]), map(true, &[0], &[]), false)]
+    #[case(map(false, &[], &[0]), map(true, &[1], &[]), true)]
+    #[case(map(false, &[], &[1]), map(true, &[1], &[]), false)]
+    fn subset_of_tests(#[case] a: Map, #[case] b: Map, #[case] expected: bool) {
+        assert_eq!(a.subset_of(&b), expected);
+    }
+}
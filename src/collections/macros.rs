@@ -0,0 +1,895 @@
+//! The implementation shared by the wildcard maps backed by std, alloc and im maps, which only differ in the map, the bound it needs on its keys and the box holding the wildcard value.
+
+/// Defines a wildcard map backed by `$map`, whose keys need `$key` (`Hash` or `Ord`), with the wildcard value held in `$wildcard`.
+///
+/// `$value_bounds` are extra bounds that `$map` needs on its values.
+/// The serde literals name the functions for the defaults and `skip_serializing_if` of the internal maps and the wildcard value.
+macro_rules! wildcard_map {
+    (
+        $(#[$meta:meta])*
+        $name:ident,
+        map: $map:ident,
+        key: $key:ident,
+        wildcard: $wildcard:ident,
+        remove_empty_keys: $remove_empty_keys:path,
+        serde: ($map_default:literal, $map_skip:literal, $wildcard_default:literal, $wildcard_skip:literal),
+        Value: ($($value_bounds:tt)*)
+    ) => {
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        $(#[$meta])*
+        pub struct $name<Key: $key + Eq + Clone, Value: Set<Empty = Value> $($value_bounds)*> {
+            #[cfg_attr(
+                feature = "serde",
+                serde(default = $map_default, skip_serializing_if = $map_skip)
+            )]
+            wildcard_exceptions: $map<Key, Value>,
+            #[cfg_attr(
+                feature = "serde",
+                serde(default = $wildcard_default, skip_serializing_if = $wildcard_skip)
+            )]
+            wildcard_value: $wildcard<Value>,
+            #[cfg_attr(
+                feature = "serde",
+                serde(default = $map_default, skip_serializing_if = $map_skip)
+            )]
+            rest_list: $map<Key, Value>,
+        }
+
+        impl<Key: $key + Eq + Clone, Value: Set<Empty = Value> $($value_bounds)*> $name<Key, Value> {
+            pub fn new(wildcard_value: Value) -> Self {
+                Self {
+                    wildcard_exceptions: $map::empty(),
+                    wildcard_value: $wildcard::new(wildcard_value),
+                    rest_list: $map::empty(),
+                }
+            }
+
+            /// The value that every key has, unless it is removed by an exception.
+            pub fn wildcard_value(&self) -> &Value {
+                &self.wildcard_value
+            }
+
+            /// The values that are removed from the wildcard value for specific keys.
+            pub fn wildcard_exceptions(&self) -> &$map<Key, Value> {
+                &self.wildcard_exceptions
+            }
+
+            /// The values that specific keys have in addition to the wildcard value.
+            pub fn rest_list(&self) -> &$map<Key, Value> {
+                &self.rest_list
+            }
+
+            /// Creates a map from its parts, without checking that they are consistent with each other.
+            #[cfg(feature = "serde")]
+            pub(crate) fn from_parts(
+                wildcard_value: Value,
+                wildcard_exceptions: $map<Key, Value>,
+                rest_list: $map<Key, Value>,
+            ) -> Self {
+                Self {
+                    wildcard_exceptions,
+                    wildcard_value: $wildcard::new(wildcard_value),
+                    rest_list,
+                }
+            }
+        }
+
+        impl<Key: $key + Eq + Clone, Value: Set<Empty = Value> $($value_bounds)* + Clone> $name<Key, Value>
+        where
+            for<'a> Value: DifferenceAssign<&'a Value>,
+        {
+            /// Applies `operation` to a copy of the wildcard value, only replacing the wildcard value if it changes so an unchanged one stays shared.
+            fn update_wildcard_value(&mut self, operation: impl FnOnce(&mut Value)) {
+                let mut wildcard_value = self.wildcard_value.deref().clone();
+                operation(&mut wildcard_value);
+
+                if !Self::same_value(&wildcard_value, &self.wildcard_value) {
+                    self.wildcard_value = $wildcard::new(wildcard_value);
+                }
+            }
+
+            /// Whether both values contain the same elements, which is checked with differences so `Value` does not need [`PartialEq`].
+            fn same_value(value: &Value, other_value: &Value) -> bool {
+                let mut added = value.clone();
+                added.difference_assign(other_value);
+                let mut removed = other_value.clone();
+                removed.difference_assign(value);
+
+                added.is_empty() && removed.is_empty()
+            }
+
+            /// Stores `value` for `key`, removing the key if it is empty and leaving the map untouched if the key already has that value.
+            fn store(map: &mut $map<Key, Value>, key: Key, value: Value) {
+                if value.is_empty() {
+                    if map.contains_key(&key) {
+                        map.remove(&key);
+                    }
+
+                    return;
+                }
+
+                if !map
+                    .get(&key)
+                    .is_some_and(|old_value| Self::same_value(&value, old_value))
+                {
+                    map.insert(key, value);
+                }
+            }
+        }
+
+        impl<Key: $key + Eq + Clone, Value: Set<Empty = Value> $($value_bounds)* + Clone> $name<Key, Value>
+        where
+            for<'a> Value: DifferenceAssign<&'a Value> + UnionAssign<&'a Value>,
+        {
+            /// The value that `key` has, which is the wildcard value without the key's exception, together with the key's rest list value.
+            pub fn value_of(&self, key: &Key) -> Value {
+                self.with_value_of(key, Value::clone)
+            }
+
+            /// Calls `f` with the value that `key` has, only cloning the wildcard value if the key has an exception or rest list value.
+            fn with_value_of<R>(&self, key: &Key, f: impl FnOnce(&Value) -> R) -> R {
+                match (self.wildcard_exceptions.get(key), self.rest_list.get(key)) {
+                    (None, None) => f(&self.wildcard_value),
+                    (exception, rest_value) => {
+                        let mut value = self.wildcard_value.deref().clone();
+
+                        if let Some(exception) = exception {
+                            value.difference_assign(exception);
+                        }
+
+                        if let Some(rest_value) = rest_value {
+                            value.union_assign(rest_value);
+                        }
+
+                        f(&value)
+                    }
+                }
+            }
+
+            /// Applies `operation` to the wildcard value and to the value of every key mentioned by either map.
+            ///
+            /// The result is stored in its canonical form, where every exception is a subset of the wildcard value and every rest list value is disjoint from it.
+            /// Entries are updated in place and only written if they change, so the parts of the internal maps that stay the same keep being shared.
+            /// If `only_rhs_keys` is true, keys not mentioned by rhs are dropped, which the caller must only request when `operation` makes them empty.
+            fn combine_assign<OtherValue>(
+                &mut self,
+                rhs: &$name<Key, OtherValue>,
+                only_rhs_keys: bool,
+                operation: impl Fn(&mut Value, &OtherValue),
+            ) where
+                OtherValue: Set<Empty = OtherValue> $($value_bounds)* + Clone,
+                for<'a> OtherValue: DifferenceAssign<&'a OtherValue> + UnionAssign<&'a OtherValue>,
+            {
+                let rhs_mentions = |key: &Key| {
+                    rhs.wildcard_exceptions.contains_key(key) || rhs.rest_list.contains_key(key)
+                };
+
+                let mut keys: Vec<Key> = rhs
+                    .wildcard_exceptions
+                    .keys()
+                    .chain(
+                        rhs.rest_list
+                            .keys()
+                            .filter(|key| !rhs.wildcard_exceptions.contains_key(key)),
+                    )
+                    .cloned()
+                    .collect();
+
+                if only_rhs_keys {
+                    self.wildcard_exceptions.retain(|key, _| rhs_mentions(key));
+                    self.rest_list.retain(|key, _| rhs_mentions(key));
+                } else {
+                    keys.extend(
+                        self.wildcard_exceptions
+                            .keys()
+                            .chain(
+                                self.rest_list
+                                    .keys()
+                                    .filter(|key| !self.wildcard_exceptions.contains_key(key)),
+                            )
+                            .filter(|key| !rhs_mentions(key))
+                            .cloned(),
+                    );
+                }
+
+                let old_wildcard_value = self.wildcard_value.deref().clone();
+                self.update_wildcard_value(|wildcard_value| operation(wildcard_value, &rhs.wildcard_value));
+
+                for key in keys {
+                    // Every key is visited once, so the entries of the key still hold its old value.
+                    let mut value = old_wildcard_value.clone();
+
+                    if let Some(exception) = self.wildcard_exceptions.get(&key) {
+                        value.difference_assign(exception);
+                    }
+
+                    if let Some(rest_value) = self.rest_list.get(&key) {
+                        value.union_assign(rest_value);
+                    }
+
+                    rhs.with_value_of(&key, |other_value| operation(&mut value, other_value));
+
+                    let mut exception = self.wildcard_value.deref().clone();
+                    exception.difference_assign(&value);
+                    value.difference_assign(self.wildcard_value.deref());
+
+                    Self::store(&mut self.wildcard_exceptions, key.clone(), exception);
+                    Self::store(&mut self.rest_list, key, value);
+                }
+            }
+        }
+
+        impl<Key: $key + Eq + Clone, Value: Set<Empty = Value> $($value_bounds)*> Set for $name<Key, Value> {
+            type Empty = Self;
+
+            fn is_empty(&self) -> bool {
+                self.rest_list.is_empty() && self.wildcard_value.is_empty()
+            }
+
+            fn empty() -> Self::Empty {
+                $name {
+                    wildcard_exceptions: $map::empty(),
+                    wildcard_value: $wildcard::new(Value::empty()),
+                    rest_list: $map::empty(),
+                }
+            }
+        }
+
+        impl<Key: $key + Eq + Clone, Value: Set<Empty = Value> $($value_bounds)*> Default for $name<Key, Value> {
+            fn default() -> Self {
+                Self::empty()
+            }
+        }
+
+        impl<Key: $key + Eq + Clone, Value: Set<Empty = Value> $($value_bounds)*> crate::requirements::SetEntry
+            for $name<Key, Value>
+        {
+            type Key = Key;
+            type Value = Value;
+
+            /// The rest list value of `key`, which is the whole value of the key as long as the wildcard value is empty, such as in the required sets built by [`crate::require!`].
+            fn set_entry(&mut self, key: Key) -> &mut Value {
+                self.rest_list.entry(key).or_insert_with(Value::empty)
+            }
+        }
+
+        impl<Key: $key + Eq + Clone, Value: Set<Empty = Value> $($value_bounds)*> From<$map<Key, Value>>
+            for $name<Key, Value>
+        {
+            fn from(rest_list: $map<Key, Value>) -> Self {
+                $name {
+                    rest_list,
+                    ..Default::default()
+                }
+            }
+        }
+
+        impl<Key: $key + Eq + Clone, Value: Set<Empty = Value> $($value_bounds)* + Clone> Extend<(Key, Value)>
+            for $name<Key, Value>
+        where
+            for<'a> Value: DifferenceAssign<&'a Value> + UnionAssign<&'a Value>,
+        {
+            /// Adds the values to their keys, like [`UnionAssign`] with a map. Values of the same key are combined.
+            fn extend<I: IntoIterator<Item = (Key, Value)>>(&mut self, iter: I) {
+                let mut rhs: $map<Key, Value> = $map::new();
+                for (key, value) in iter {
+                    rhs.entry(key)
+                        .or_insert_with(Value::empty)
+                        .union_assign(&value);
+                }
+
+                self.union_assign(&rhs);
+            }
+        }
+
+        impl<Key: $key + Eq + Clone, Value: Set<Empty = Value> $($value_bounds)* + Clone> Extend<$name<Key, Value>>
+            for $name<Key, Value>
+        where
+            for<'a> Value: DifferenceAssign<&'a Value> + UnionAssign<&'a Value>,
+        {
+            /// Adds the sets, like [`UnionAssign`].
+            fn extend<I: IntoIterator<Item = $name<Key, Value>>>(&mut self, iter: I) {
+                for set in iter {
+                    self.union_assign(&set);
+                }
+            }
+        }
+
+        impl<Key: $key + Eq + Clone, Value: Set<Empty = Value> $($value_bounds)* + Clone> FromIterator<(Key, Value)>
+            for $name<Key, Value>
+        where
+            for<'a> Value: DifferenceAssign<&'a Value> + UnionAssign<&'a Value>,
+        {
+            fn from_iter<I: IntoIterator<Item = (Key, Value)>>(iter: I) -> Self {
+                let mut set = Self::empty();
+                set.extend(iter);
+                set
+            }
+        }
+
+        impl<Key: $key + Eq + Clone, Value: Set<Empty = Value> $($value_bounds)* + Clone>
+            FromIterator<$name<Key, Value>> for $name<Key, Value>
+        where
+            for<'a> Value: DifferenceAssign<&'a Value> + UnionAssign<&'a Value>,
+        {
+            /// The union of all sets.
+            fn from_iter<I: IntoIterator<Item = $name<Key, Value>>>(iter: I) -> Self {
+                let mut set = Self::empty();
+                set.extend(iter);
+                set
+            }
+        }
+
+        // WildcardList A <-> List B
+        impl<Key: $key + Eq + Clone, Value: Set<Empty = Value> $($value_bounds)*, OtherValue: Clone>
+            UnionAssign<&$map<Key, OtherValue>> for $name<Key, Value>
+        where
+            for<'a> Value: DifferenceAssign<&'a Value>
+                + DifferenceAssign<&'a OtherValue>
+                + UnionAssign<&'a OtherValue>,
+            for<'a> OtherValue: DifferenceAssign<&'a Value>,
+        {
+            fn union_assign(&mut self, rhs: &$map<Key, OtherValue>) {
+                for (key, value) in rhs.iter() {
+                    //For each key, remove the value from the exceptions for this key, so the part covered by the wildcard is no longer excepted.
+                    //For the rest (that is not covered by the wildcard), add it to the rest list.
+                    let mut rest = value.clone();
+
+                    rest.difference_assign(&self.wildcard_value);
+
+                    let mut remove: bool = false;
+                    if let Some(val) = self.wildcard_exceptions.get_mut(key) {
+                        val.difference_assign(value);
+
+                        remove = val.is_empty();
+                    };
+
+                    if remove {
+                        self.wildcard_exceptions.remove(key);
+                    };
+
+                    if !rest.is_empty() {
+                        self.rest_list
+                            .entry(key.clone())
+                            .or_insert_with(Value::empty)
+                            .union_assign(&rest);
+                    }
+                }
+            }
+        }
+
+        impl<Key: $key + Eq + Clone, Value: Set<Empty = Value> $($value_bounds)*, OtherValue: Clone>
+            Union<&$map<Key, OtherValue>> for $name<Key, Value>
+        where
+            for<'a> Value: DifferenceAssign<&'a Value>
+                + DifferenceAssign<&'a OtherValue>
+                + UnionAssign<&'a OtherValue>,
+            for<'a> OtherValue: DifferenceAssign<&'a Value>,
+        {
+            type Output = Self;
+
+            fn union(mut self, rhs: &$map<Key, OtherValue>) -> Self::Output {
+                self.union_assign(rhs);
+                self
+            }
+        }
+
+        impl<Key: $key + Eq + Clone, Value: Set<Empty = Value> $($value_bounds)*, OtherValue: Clone>
+            Union<$map<Key, OtherValue>> for $name<Key, Value>
+        where
+            for<'a> Self: Union<&'a $map<Key, OtherValue>, Output = Self>,
+        {
+            type Output = Self;
+
+            fn union(self, rhs: $map<Key, OtherValue>) -> Self::Output {
+                self.union(&rhs)
+            }
+        }
+
+        impl<Key: $key + Eq + Clone, Value: Set<Empty = Value> $($value_bounds)* + Clone, OtherValue: Set<Empty = OtherValue> $($value_bounds)*>
+            DifferenceAssign<&$map<Key, OtherValue>> for $name<Key, Value>
+        where
+            for<'a> Value: DifferenceAssign<&'a OtherValue>
+                + IntersectionAssign<&'a OtherValue>
+                + UnionAssign<&'a Value>,
+        {
+            fn difference_assign(&mut self, rhs: &$map<Key, OtherValue>) {
+                self.rest_list.difference_assign(rhs);
+
+                for (key, value) in rhs.iter() {
+                    let mut covered = self.wildcard_value.deref().clone();
+                    covered.intersection_assign(value);
+
+                    // Whatever intersection exists between the wildcard and the value of a key should be inserted as an exception on that key.
+                    if !covered.is_empty() {
+                        self.wildcard_exceptions
+                            .entry(key.clone())
+                            .or_insert_with(Value::empty)
+                            .union_assign(&covered);
+                    }
+                }
+            }
+        }
+
+        impl<Key: $key + Eq + Clone, Value: Set<Empty = Value> $($value_bounds)* + Clone, OtherValue: Set<Empty = OtherValue> $($value_bounds)*>
+            Difference<&$map<Key, OtherValue>> for $name<Key, Value>
+        where
+            for<'a> Value: DifferenceAssign<&'a OtherValue>
+                + IntersectionAssign<&'a OtherValue>
+                + UnionAssign<&'a Value>,
+        {
+            type Output = Self;
+
+            fn difference(mut self, rhs: &$map<Key, OtherValue>) -> Self::Output {
+                self.difference_assign(rhs);
+                self
+            }
+        }
+
+        // WildcardList A <-> WildcardList B
+        impl<Key, Value, OtherValue> UnionAssign<&$name<Key, OtherValue>>
+            for $name<Key, Value>
+        where
+            Key: $key + Eq + Clone,
+            Value: Set<Empty = Value> $($value_bounds)* + Clone,
+            for<'a> Value: DifferenceAssign<&'a Value>
+                + DifferenceAssign<&'a OtherValue>
+                + UnionAssign<&'a OtherValue>,
+            OtherValue: Set<Empty = OtherValue> $($value_bounds)* + Clone,
+            for<'a> OtherValue: DifferenceAssign<&'a Value> + DifferenceAssign<&'a OtherValue>,
+            for<'a> $map<Key, Value>: UnionAssign<&'a $map<Key, OtherValue>>,
+        {
+            fn union_assign(&mut self, rhs: &$name<Key, OtherValue>) {
+                let mut cleaned_rhs_wildcard_exceptions = rhs.wildcard_exceptions.clone();
+
+                /// This function removes covered exceptions from a wildcard value (and it's associated exceptions).
+                fn remove_covered_values<Key, Value, OtherValue>(
+                    exceptions: &mut $map<Key, OtherValue>,
+                    wildcard_value: &Value,
+                    wildcard_exceptions: &$map<Key, Value>,
+                ) where
+                    Key: $key + Eq + Clone,
+                    Value: Set<Empty = Value> $($value_bounds)* + Clone,
+                    OtherValue: Set $($value_bounds)*,
+                    for<'a> Value: DifferenceAssign<&'a Value>, // + DifferenceAssign<&'a OtherValue>,
+                    for<'a> OtherValue: DifferenceAssign<&'a Value>, // + DifferenceAssign<&'a OtherValue>,
+                {
+                    for (key, other_exception) in exceptions.iter_mut() {
+                        if let Some(exception) = wildcard_exceptions.get(key) {
+                            let mut wildcard_value = wildcard_value.clone();
+                            wildcard_value.difference_assign(exception);
+                            other_exception.difference_assign(&wildcard_value);
+                        } else {
+                            other_exception.difference_assign(wildcard_value);
+                        };
+                    }
+
+                    $remove_empty_keys(exceptions);
+                }
+
+                // Remove exceptions in rhs covered by selfs wildcard.
+                remove_covered_values(
+                    &mut cleaned_rhs_wildcard_exceptions,
+                    self.wildcard_value.as_ref(),
+                    &self.wildcard_exceptions,
+                );
+                cleaned_rhs_wildcard_exceptions.difference_assign(&self.rest_list);
+
+                // Remove exceptions in self covered by rhs' wildcard.
+                remove_covered_values(
+                    &mut self.wildcard_exceptions,
+                    rhs.wildcard_value.as_ref(),
+                    &rhs.wildcard_exceptions,
+                );
+                self.wildcard_exceptions.difference_assign(&rhs.rest_list);
+
+                // Merge the exception lists and the wildcards.
+                self.wildcard_exceptions
+                    .union_assign(&cleaned_rhs_wildcard_exceptions);
+                self.update_wildcard_value(|wildcard_value| {
+                    wildcard_value.union_assign(rhs.wildcard_value.deref())
+                });
+
+                // Merge rest lists.
+                self.rest_list.union_assign(&rhs.rest_list);
+
+                // Remove values in rest list covered by new wildcard.
+                remove_covered_values(
+                    &mut self.rest_list,
+                    self.wildcard_value.as_ref(),
+                    &self.wildcard_exceptions,
+                );
+            }
+        }
+
+        impl<Key, Value, OtherValue> Union<&$name<Key, OtherValue>>
+            for $name<Key, Value>
+        where
+            Key: $key + Eq + Clone,
+            Value: Set<Empty = Value> $($value_bounds)* + Clone,
+            for<'a> Value: DifferenceAssign<&'a Value>
+                + DifferenceAssign<&'a OtherValue>
+                + UnionAssign<&'a OtherValue>,
+            OtherValue: Set<Empty = OtherValue> $($value_bounds)* + Clone,
+            for<'a> OtherValue: DifferenceAssign<&'a Value> + DifferenceAssign<&'a OtherValue>,
+            for<'a> $map<Key, Value>: UnionAssign<&'a $map<Key, OtherValue>>,
+        {
+            type Output = Self;
+
+            fn union(mut self, rhs: &$name<Key, OtherValue>) -> Self::Output {
+                self.union_assign(rhs);
+                self
+            }
+        }
+
+        impl<Key, Value, OtherValue> Union<$name<Key, OtherValue>> for $name<Key, Value>
+        where
+            Key: $key + Eq + Clone,
+            Value: Set<Empty = Value> $($value_bounds)* + Clone,
+            OtherValue: Set<Empty = OtherValue> $($value_bounds)* + Clone,
+            for<'a> Self: Union<&'a $name<Key, OtherValue>, Output = Self>,
+        {
+            type Output = Self;
+
+            fn union(self, rhs: $name<Key, OtherValue>) -> Self::Output {
+                self.union(&rhs)
+            }
+        }
+
+        impl<Key, Value, OtherValue> DifferenceAssign<&$name<Key, OtherValue>>
+            for $name<Key, Value>
+        where
+            Key: $key + Eq + Clone,
+            Value: Set<Empty = Value> $($value_bounds)* + Clone,
+            for<'a> Value: DifferenceAssign<&'a Value>
+                + DifferenceAssign<&'a OtherValue>
+                + IntersectionAssign<&'a OtherValue>
+                + UnionAssign<&'a OtherValue>
+                + UnionAssign<&'a Value>,
+            OtherValue: Set<Empty = OtherValue> $($value_bounds)* + Clone,
+            for<'a> OtherValue: IntersectionAssign<&'a Value>
+                + DifferenceAssign<&'a OtherValue>
+                + UnionAssign<&'a OtherValue>,
+        {
+            fn difference_assign(&mut self, rhs: &$name<Key, OtherValue>) {
+                //If exception exists for X key, that value should not be removed for that key.
+                //That means, if there is an intersection between that exception and the wildcard value, it should be added to the rest list.
+                for (key, other_exception) in rhs.wildcard_exceptions.iter() {
+                    let mut value = self.wildcard_value.deref().clone();
+
+                    if let Some(exception) = self.wildcard_exceptions.get(key) {
+                        value.difference_assign(exception);
+                    }
+
+                    value.intersection_assign(other_exception);
+
+                    if value.is_empty() {
+                        continue;
+                    }
+
+                    if let Some(rest_value) = self.rest_list.get_mut(key) {
+                        rest_value.union_assign(&value);
+                    } else {
+                        self.rest_list.insert(key.clone(), value);
+                    }
+                }
+                //Remove rhs wildcard from self wildcard.
+                self.update_wildcard_value(|wildcard_value| {
+                    wildcard_value.difference_assign(rhs.wildcard_value.deref())
+                });
+
+                // If any rest list items in rhs intersect with the self wildcard, add them to the exceptions.
+                // Subtract any rest list items in self with rhs.
+
+                for (key, value) in rhs.rest_list.iter() {
+                    let mut value = value.clone();
+
+                    value.intersection_assign(&self.wildcard_value);
+
+                    if value.is_empty() {
+                        continue;
+                    }
+
+                    self.wildcard_exceptions
+                        .entry(key.clone())
+                        .or_insert_with(Value::empty)
+                        .union_assign(&value);
+                }
+
+                // Subtract the values of rhs from the rest list, including those given by its wildcard.
+                for (key, rest_value) in self.rest_list.iter_mut() {
+                    rest_value.difference_assign(&rhs.value_of(key));
+                }
+
+                $remove_empty_keys(&mut self.rest_list);
+            }
+        }
+
+        impl<Key, Value, OtherValue> Difference<&$name<Key, OtherValue>>
+            for $name<Key, Value>
+        where
+            Key: $key + Eq + Clone,
+            Value: Set<Empty = Value> $($value_bounds)* + Clone,
+            OtherValue: Set<Empty = OtherValue> $($value_bounds)* + Clone,
+            for<'a> Self: DifferenceAssign<&'a $name<Key, OtherValue>>,
+        {
+            type Output = Self;
+
+            fn difference(mut self, rhs: &$name<Key, OtherValue>) -> Self::Output {
+                self.difference_assign(rhs);
+                self
+            }
+        }
+
+        impl<Key, Value, OtherValue> Difference<$name<Key, OtherValue>>
+            for $name<Key, Value>
+        where
+            Key: $key + Eq + Clone,
+            Value: Set<Empty = Value> $($value_bounds)* + Clone,
+            OtherValue: Set<Empty = OtherValue> $($value_bounds)* + Clone,
+            for<'a> Self: Difference<&'a $name<Key, OtherValue>, Output = Self>,
+        {
+            type Output = Self;
+
+            fn difference(self, rhs: $name<Key, OtherValue>) -> Self::Output {
+                self.difference(&rhs)
+            }
+        }
+
+        impl<Key, Value, OtherValue> IntersectionAssign<&$name<Key, OtherValue>>
+            for $name<Key, Value>
+        where
+            Key: $key + Eq + Clone,
+            Value: Set<Empty = Value> $($value_bounds)* + Clone,
+            for<'a> Value:
+                DifferenceAssign<&'a Value> + UnionAssign<&'a Value> + IntersectionAssign<&'a OtherValue>,
+            OtherValue: Set<Empty = OtherValue> $($value_bounds)* + Clone,
+            for<'a> OtherValue: DifferenceAssign<&'a OtherValue> + UnionAssign<&'a OtherValue>,
+        {
+            fn intersection_assign(&mut self, rhs: &$name<Key, OtherValue>) {
+                // Keys not mentioned by rhs have its wildcard value, so an empty wildcard value removes them.
+                let only_rhs_keys = rhs.wildcard_value.is_empty();
+
+                self.combine_assign(rhs, only_rhs_keys, |value, other_value| {
+                    value.intersection_assign(other_value)
+                });
+            }
+        }
+
+        impl<Key, Value, OtherValue> IntersectionAssign<$name<Key, OtherValue>>
+            for $name<Key, Value>
+        where
+            Key: $key + Eq + Clone,
+            Value: Set<Empty = Value> $($value_bounds)* + Clone,
+            OtherValue: Set<Empty = OtherValue> $($value_bounds)* + Clone,
+            for<'a> Self: IntersectionAssign<&'a $name<Key, OtherValue>>,
+        {
+            fn intersection_assign(&mut self, rhs: $name<Key, OtherValue>) {
+                self.intersection_assign(&rhs);
+            }
+        }
+
+        impl<Key, Value, OtherValue> DisjunctiveUnionAssign<&$name<Key, OtherValue>>
+            for $name<Key, Value>
+        where
+            Key: $key + Eq + Clone,
+            Value: Set<Empty = Value> $($value_bounds)* + Clone,
+            for<'a> Value: DifferenceAssign<&'a Value>
+                + UnionAssign<&'a Value>
+                + DisjunctiveUnionAssign<&'a OtherValue>,
+            OtherValue: Set<Empty = OtherValue> $($value_bounds)* + Clone,
+            for<'a> OtherValue: DifferenceAssign<&'a OtherValue> + UnionAssign<&'a OtherValue>,
+        {
+            fn disjunctive_union_assign(&mut self, rhs: &$name<Key, OtherValue>) {
+                self.combine_assign(rhs, false, |value, other_value| {
+                    value.disjunctive_union_assign(other_value)
+                });
+            }
+        }
+
+        impl<Key, Value, OtherValue> DisjunctiveUnionAssign<$name<Key, OtherValue>>
+            for $name<Key, Value>
+        where
+            Key: $key + Eq + Clone,
+            Value: Set<Empty = Value> $($value_bounds)* + Clone,
+            OtherValue: Set<Empty = OtherValue> $($value_bounds)* + Clone,
+            for<'a> Self: DisjunctiveUnionAssign<&'a $name<Key, OtherValue>>,
+        {
+            fn disjunctive_union_assign(&mut self, rhs: $name<Key, OtherValue>) {
+                self.disjunctive_union_assign(&rhs);
+            }
+        }
+
+        impl<
+            Key: $key + Eq + Clone,
+            Value: Set<Empty = Value> $($value_bounds)* + SetEq<OtherValue>,
+            OtherValue: Set<Empty = OtherValue> $($value_bounds)*,
+        > SetEq<$name<Key, OtherValue>> for $name<Key, Value>
+        {
+            fn set_eq(&self, rhs: &$name<Key, OtherValue>) -> bool {
+                self.wildcard_value.set_eq(rhs.wildcard_value.as_ref())
+                    && self.wildcard_exceptions.set_eq(&rhs.wildcard_exceptions)
+                    && self.rest_list.set_eq(&rhs.rest_list)
+            }
+        }
+
+        impl<Key: $key + Eq + Clone, Value: Set<Empty = Value> $($value_bounds)* + SetEq<OtherValue>, OtherValue: Set $($value_bounds)*>
+            SetEq<$map<Key, OtherValue>> for $name<Key, Value>
+        {
+            fn set_eq(&self, rhs: &$map<Key, OtherValue>) -> bool {
+                self.wildcard_value.is_empty() && self.rest_list.set_eq(rhs)
+            }
+        }
+
+        impl<Key: $key + Eq + Clone, Value: Set<Empty = Value> $($value_bounds)* + Clone> $name<Key, Value> {
+            /// Returns true if the value of `key` is a subset of the value of `key` in `rhs`.
+            pub(crate) fn key_subset_of<OtherValue: Set<Empty = OtherValue> $($value_bounds)*>(
+                &self,
+                rhs: &$name<Key, OtherValue>,
+                key: &Key,
+            ) -> bool
+            where
+                for<'a> Value: DifferenceAssign<&'a Value>
+                    + DifferenceAssign<&'a OtherValue>
+                    + UnionAssign<&'a Value>
+                    + IntersectionAssign<&'a OtherValue>,
+            {
+                let mut a = self.wildcard_value.deref().clone();
+                if let Some(exc) = self.wildcard_exceptions.get(key) {
+                    a.difference_assign(exc);
+                }
+                if let Some(rest) = self.rest_list.get(key) {
+                    a.union_assign(rest);
+                }
+
+                if let Some(rhs_rest) = rhs.rest_list.get(key) {
+                    a.difference_assign(rhs_rest);
+                }
+
+                let mut a_minus_wb = a.clone();
+                a_minus_wb.difference_assign(rhs.wildcard_value.as_ref());
+                if !a_minus_wb.is_empty() {
+                    return false;
+                }
+
+                if let Some(rhs_exc) = rhs.wildcard_exceptions.get(key) {
+                    a.intersection_assign(rhs_exc);
+                    if !a.is_empty() {
+                        return false;
+                    }
+                }
+
+                true
+            }
+        }
+
+        impl<
+            Key: $key + Eq + Clone,
+            Value: Set<Empty = Value> $($value_bounds)* + SubsetOf<OtherValue>,
+            OtherValue: Set<Empty = OtherValue> $($value_bounds)*,
+        > SubsetOf<$name<Key, OtherValue>> for $name<Key, Value>
+        where
+            for<'a> Value: DifferenceAssign<&'a Value>
+                + DifferenceAssign<&'a OtherValue>
+                + UnionAssign<&'a Value>
+                + IntersectionAssign<&'a OtherValue>
+                + Clone,
+        {
+            fn subset_of(&self, rhs: &$name<Key, OtherValue>) -> bool {
+                // The wildcard itself must be a subset
+                if !self.wildcard_value.subset_of(rhs.wildcard_value.as_ref()) {
+                    return false;
+                }
+
+                // Every key is checked once, skipping rest list keys that are also exceptions of rhs.
+                rhs.wildcard_exceptions
+                    .keys()
+                    .chain(
+                        self.rest_list
+                            .keys()
+                            .filter(|key| !rhs.wildcard_exceptions.contains_key(key)),
+                    )
+                    .all(|key| self.key_subset_of(rhs, key))
+            }
+        }
+
+        impl<Key: $key + Eq + Clone, Value: Set<Empty = Value> $($value_bounds)* + SubsetOf<OtherValue>, OtherValue: Set $($value_bounds)*>
+            SubsetOf<$map<Key, OtherValue>> for $name<Key, Value>
+        {
+            fn subset_of(&self, rhs: &$map<Key, OtherValue>) -> bool {
+                if !self.wildcard_value.is_empty() {
+                    return false;
+                }
+
+                self.rest_list.subset_of(rhs)
+            }
+        }
+
+        #[cfg(feature = "codec")]
+        const _: () = {
+            use crate::codec::{
+                Decode, DecodeError, Decoder, EXCEPTIONS_SECTION, Encode, Encoder, REST_SECTION,
+                WILDCARD_SECTION,
+            };
+
+            impl<Key: $key + Eq + Clone + Encode, Value: Set<Empty = Value> $($value_bounds)* + Encode> Encode
+                for $name<Key, Value>
+            {
+                fn encode(&self, encoder: &mut Encoder) {
+                    let mut sections = 0;
+                    if !self.wildcard_value.is_empty() {
+                        sections |= WILDCARD_SECTION;
+                    }
+                    if !self.wildcard_exceptions.is_empty() {
+                        sections |= EXCEPTIONS_SECTION;
+                    }
+                    if !self.rest_list.is_empty() {
+                        sections |= REST_SECTION;
+                    }
+
+                    encoder.write_u8(sections);
+
+                    if sections & WILDCARD_SECTION != 0 {
+                        self.wildcard_value.encode(encoder);
+                    }
+                    if sections & EXCEPTIONS_SECTION != 0 {
+                        self.wildcard_exceptions.encode(encoder);
+                    }
+                    if sections & REST_SECTION != 0 {
+                        self.rest_list.encode(encoder);
+                    }
+                }
+            }
+
+            impl<Key: $key + Eq + Clone + Decode, Value: Set<Empty = Value> $($value_bounds)* + Clone + Decode> Decode
+                for $name<Key, Value>
+            where
+                for<'a> Value: DifferenceAssign<&'a Value> + IntersectionAssign<&'a Value>,
+            {
+                fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+                    let sections = decoder.read_u8()?;
+                    if sections & !(WILDCARD_SECTION | EXCEPTIONS_SECTION | REST_SECTION) != 0 {
+                        return Err(DecodeError::InvalidTag(sections));
+                    }
+
+                    /// Decodes a section if it is present, rejecting present sections that are empty.
+                    fn decode_section<T: Set<Empty = T> + Decode>(
+                        decoder: &mut Decoder<'_>,
+                        present: bool,
+                    ) -> Result<T, DecodeError> {
+                        if !present {
+                            return Ok(T::empty());
+                        }
+
+                        let value = T::decode(decoder)?;
+                        if value.is_empty() {
+                            return Err(DecodeError::EmptyValue);
+                        }
+
+                        Ok(value)
+                    }
+
+                    let map: Self = $name {
+                        wildcard_value: $wildcard::new(decode_section(
+                            decoder,
+                            sections & WILDCARD_SECTION != 0,
+                        )?),
+                        wildcard_exceptions: decode_section(decoder, sections & EXCEPTIONS_SECTION != 0)?,
+                        rest_list: decode_section(decoder, sections & REST_SECTION != 0)?,
+                    };
+
+                    if !crate::collections::is_canonical(
+                        map.wildcard_value.deref(),
+                        map.wildcard_exceptions.values(),
+                        map.rest_list.values(),
+                    ) {
+                        return Err(DecodeError::NotCanonical);
+                    }
+
+                    Ok(map)
+                }
+            }
+        };
+    };
+}
@@ -1,11 +1,86 @@
 //! This module contains various collection types that can be used with the traits defined in this crate, such as [`WildcardHashMap`] and [`WildcardBTreeMap`].
 
+#[cfg(feature = "alloc")]
+#[macro_use]
+mod macros;
+
 #[cfg(feature = "std")]
 mod wildcard_hashmap;
 #[cfg(feature = "std")]
 pub use wildcard_hashmap::WildcardHashMap;
 
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
 mod wildcard_btreemap;
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
 pub use wildcard_btreemap::WildcardBTreeMap;
+
+#[cfg(feature = "alloc")]
+mod conditional;
+#[cfg(feature = "alloc")]
+pub use conditional::{Condition, Conditional};
+
+#[cfg(feature = "alloc")]
+mod scoped;
+#[cfg(feature = "alloc")]
+pub use scoped::{Scope, Scoped};
+
+#[cfg(feature = "alloc")]
+mod timed;
+#[cfg(feature = "alloc")]
+pub use timed::Timed;
+
+#[cfg(feature = "im")]
+mod wildcard_im_hashmap;
+#[cfg(feature = "im")]
+pub use wildcard_im_hashmap::WildcardImHashMap;
+
+/// Whether the parts of a wildcard map are in the canonical form the operations keep them in, which is checked when they are read from outside.
+///
+/// Every exception has to be part of the wildcard value, so there are no exceptions next to an empty wildcard value, and every rest list value has to be disjoint from it.
+#[cfg(any(feature = "codec", all(feature = "serde", feature = "alloc")))]
+pub(crate) fn is_canonical<'a, Value>(
+    wildcard_value: &Value,
+    mut wildcard_exceptions: impl Iterator<Item = &'a Value>,
+    mut rest_values: impl Iterator<Item = &'a Value>,
+) -> bool
+where
+    Value: crate::Set<Empty = Value> + Clone + 'a,
+    for<'b> Value: crate::operations::DifferenceAssign<&'b Value>
+        + crate::operations::IntersectionAssign<&'b Value>,
+{
+    let exceptions_inside = wildcard_exceptions.all(|exception| {
+        let mut outside = exception.clone();
+        outside.difference_assign(wildcard_value);
+        outside.is_empty()
+    });
+
+    exceptions_inside
+        && rest_values.all(|rest_value| {
+            let mut overlap = rest_value.clone();
+            overlap.intersection_assign(wildcard_value);
+            overlap.is_empty()
+        })
+}
+
+#[cfg(feature = "heapless")]
+mod heapless_wildcard_map;
+#[cfg(feature = "heapless")]
+pub use heapless_wildcard_map::HeaplessWildcardMap;
+
+/// [`CapacityError`] is returned by the fallible operations of fixed-capacity collections when the result does not fit.
+#[cfg(feature = "heapless")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapacityError;
+
+#[cfg(feature = "heapless")]
+impl core::fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "the result does not fit in the capacity of the collection"
+        )
+    }
+}
+
+#[cfg(all(feature = "heapless", feature = "std"))]
+impl std::error::Error for CapacityError {}
@@ -0,0 +1,385 @@
+use crate::Set;
+use crate::collections::WildcardBTreeMap;
+use crate::comparisons::SubsetOf;
+use crate::operations::{
+    DifferenceAssign, DisjunctiveUnionAssign, IntersectionAssign, UnionAssign,
+};
+use alloc::{boxed::Box, collections::BTreeMap};
+
+type Children<ScopeId, S> = WildcardBTreeMap<ScopeId, Scoped<ScopeId, S>>;
+
+/// A segment of the scope path given to [`Scoped::granted`], which is either a single child scope or every child scope.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Scope<ScopeId> {
+    Id(ScopeId),
+    Every,
+}
+
+/// A set for every scope of a hierarchy, such as global, organization, project and resource scopes, where a scope is the path of ids from the root.
+///
+/// A scope has its own set and the sets of its child scopes. Child scopes that are not listed have the set of their parent, so grants at a parent scope flow into every scope below it, unless a child scope lists a different set. The child scopes are stored in a [`WildcardBTreeMap`], so "every project" is its wildcard value and operations on child scopes work like those of any other wildcard map.
+///
+/// Operations are applied to the sets of every scope, so the set of any scope in the result is the operation applied to its sets in both operands.
+///
+/// ```
+/// # use std::collections::HashSet;
+/// # use finit::collections::{Scope, Scoped};
+/// let mut perms = Scoped::granted(&[Scope::Id("acme")], HashSet::from(["read"]));
+/// perms.grant(&[Scope::Id("acme"), Scope::Every], HashSet::from(["write"]));
+/// perms.revoke(&[Scope::Id("acme"), Scope::Id("billing")], HashSet::from(["write"]));
+///
+/// assert_eq!(perms.at(&[]), HashSet::new());
+/// assert_eq!(perms.at(&["acme"]), HashSet::from(["read"]));
+/// assert_eq!(perms.at(&["acme", "website", "logo"]), HashSet::from(["read", "write"]));
+/// assert_eq!(perms.at(&["acme", "billing"]), HashSet::from(["read"]));
+/// ```
+///
+/// With serde, it is a map with the optional `grants` and `children` of the root scope. Deserializing normalizes the child scopes like operations do, so a decoded set compares equal to the same set built with operations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(bound(serialize = "ScopeId: serde::Serialize, S: serde::Serialize"))
+)]
+pub struct Scoped<ScopeId: Ord + Clone, S: Set<Empty = S>> {
+    #[cfg_attr(
+        feature = "serde",
+        serde(default = "S::empty", skip_serializing_if = "Set::is_empty")
+    )]
+    grants: S,
+    /// The child scopes are boxed and missing when every child scope has the set of this scope, since the empty wildcard map of a recursive type would otherwise contain itself.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    children: Option<Box<Children<ScopeId, S>>>,
+}
+
+impl<ScopeId: Ord + Clone, S: Set<Empty = S>> Scoped<ScopeId, S> {
+    /// Grants `grants` in every scope.
+    pub fn new(grants: S) -> Self {
+        Self {
+            grants,
+            children: None,
+        }
+    }
+
+    /// The set of this scope, which is also the set of every child scope that is not listed.
+    pub fn grants(&self) -> &S {
+        &self.grants
+    }
+
+    /// The child scopes, or [`None`] if every child scope has the set of this scope.
+    pub fn children(&self) -> Option<&Children<ScopeId, S>> {
+        self.children.as_deref()
+    }
+}
+
+impl<ScopeId: Ord + Clone, S: Set<Empty = S> + Clone> Scoped<ScopeId, S> {
+    /// Grants `set` in `scope` and every scope below it.
+    pub fn granted(scope: &[Scope<ScopeId>], set: S) -> Self {
+        let Some((segment, rest)) = scope.split_first() else {
+            return Self::new(set);
+        };
+
+        if set.is_empty() {
+            return Self::empty();
+        }
+
+        let child = Self::granted(rest, set);
+        let children = match segment {
+            Scope::Id(id) => Children::from(BTreeMap::from([(id.clone(), child)])),
+            Scope::Every => Children::new(child),
+        };
+
+        Self {
+            grants: S::empty(),
+            children: Some(Box::new(children)),
+        }
+    }
+
+    /// The child scopes, where every child scope that is not listed has the set of this scope.
+    fn listed_children(&self) -> Children<ScopeId, S> {
+        match &self.children {
+            Some(children) => children.as_ref().clone(),
+            None => Children::new(Self::new(self.grants.clone())),
+        }
+    }
+}
+
+impl<ScopeId: Ord + Clone, S: Set<Empty = S> + Clone + PartialEq> Scoped<ScopeId, S> {
+    /// Applies `operation` to the sets of this scope and `children_operation` to the child scopes, listing them first if either side lists them.
+    fn combine_assign(
+        &mut self,
+        rhs: &Scoped<ScopeId, S>,
+        operation: impl Fn(&mut S, &S),
+        children_operation: impl Fn(&mut Children<ScopeId, S>, &Children<ScopeId, S>),
+    ) {
+        if self.children.is_some() || rhs.children.is_some() {
+            let mut children = self.listed_children();
+            children_operation(&mut children, &rhs.listed_children());
+            self.children = Some(Box::new(children));
+        }
+
+        operation(&mut self.grants, &rhs.grants);
+        self.normalize();
+    }
+
+    /// Removes the child scopes if they all have the set of this scope, so that equal sets compare equal.
+    fn normalize(&mut self) {
+        if self.children.as_ref().is_some_and(|children| {
+            let wildcard_value = children.wildcard_value();
+
+            // Exceptions only remove from the wildcard value, so they do nothing if it is empty.
+            (children.wildcard_exceptions().is_empty() || wildcard_value.is_empty())
+                && children.rest_list().is_empty()
+                && wildcard_value.children.is_none()
+                && wildcard_value.grants == self.grants
+        }) {
+            self.children = None;
+        }
+    }
+}
+
+impl<ScopeId: Ord + Clone, S: Set<Empty = S> + Clone + PartialEq> Scoped<ScopeId, S>
+where
+    for<'a> S: UnionAssign<&'a S> + DifferenceAssign<&'a S> + IntersectionAssign<&'a S>,
+{
+    /// The set of `scope`.
+    pub fn at(&self, scope: &[ScopeId]) -> S {
+        match (scope.split_first(), &self.children) {
+            (Some((id, rest)), Some(children)) => children.value_of(id).at(rest),
+            _ => self.grants.clone(),
+        }
+    }
+
+    /// Grants `set` in `scope` and every scope below it.
+    pub fn grant(&mut self, scope: &[Scope<ScopeId>], set: S) {
+        self.union_assign(&Self::granted(scope, set));
+    }
+
+    /// Revokes `set` in `scope` and every scope below it.
+    pub fn revoke(&mut self, scope: &[Scope<ScopeId>], set: S) {
+        self.difference_assign(&Self::granted(scope, set));
+    }
+}
+
+impl<ScopeId: Ord + Clone, S: Set<Empty = S>> Set for Scoped<ScopeId, S> {
+    type Empty = Self;
+
+    fn is_empty(&self) -> bool {
+        self.grants.is_empty() && self.children.is_none()
+    }
+
+    fn empty() -> Self {
+        Self::new(S::empty())
+    }
+}
+
+impl<ScopeId: Ord + Clone, S: Set<Empty = S>> Default for Scoped<ScopeId, S> {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+/// The fields of [`Scoped`] as they are deserialized, before they are normalized.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(bound(
+    deserialize = "S: serde::Deserialize<'de>, Children<ScopeId, S>: serde::Deserialize<'de>"
+))]
+struct ScopedParts<ScopeId: Ord + Clone, S: Set<Empty = S>> {
+    #[serde(default = "S::empty")]
+    grants: S,
+    #[serde(default)]
+    children: Option<Box<Children<ScopeId, S>>>,
+}
+
+#[cfg(feature = "serde")]
+impl<'de, ScopeId, S> serde::Deserialize<'de> for Scoped<ScopeId, S>
+where
+    ScopeId: Ord + Clone + serde::Deserialize<'de>,
+    S: Set<Empty = S> + Clone + PartialEq + serde::Deserialize<'de>,
+    for<'a> S: UnionAssign<&'a S> + DifferenceAssign<&'a S> + IntersectionAssign<&'a S>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let ScopedParts { grants, children } = ScopedParts::deserialize(deserializer)?;
+
+        // Rebuilding the child scopes with operations drops listed child scopes that change nothing.
+        let children = children.map(|children| {
+            let mut listed = Children::new(children.wildcard_value().clone());
+            listed.difference_assign(children.wildcard_exceptions());
+            listed.union_assign(children.rest_list());
+            Box::new(listed)
+        });
+
+        let mut scoped = Self { grants, children };
+        scoped.normalize();
+
+        Ok(scoped)
+    }
+}
+
+macro_rules! impl_scoped_operation {
+    ($trait:ident, $func:ident $(, $extra:ident)*) => {
+        impl<ScopeId: Ord + Clone, S: Set<Empty = S> + Clone + PartialEq> $trait<&Scoped<ScopeId, S>>
+            for Scoped<ScopeId, S>
+        where
+            for<'a> S: UnionAssign<&'a S>
+                + DifferenceAssign<&'a S>
+                + IntersectionAssign<&'a S>
+                $(+ $extra<&'a S>)*,
+        {
+            fn $func(&mut self, rhs: &Scoped<ScopeId, S>) {
+                self.combine_assign(
+                    rhs,
+                    |set, rhs_set| set.$func(rhs_set),
+                    |children, rhs_children| children.$func(rhs_children),
+                );
+            }
+        }
+    };
+}
+
+impl_scoped_operation!(UnionAssign, union_assign);
+impl_scoped_operation!(DifferenceAssign, difference_assign);
+impl_scoped_operation!(IntersectionAssign, intersection_assign);
+impl_scoped_operation!(
+    DisjunctiveUnionAssign,
+    disjunctive_union_assign,
+    DisjunctiveUnionAssign
+);
+
+impl<ScopeId: Ord + Clone, S: Set<Empty = S> + Clone + PartialEq + SubsetOf> SubsetOf
+    for Scoped<ScopeId, S>
+where
+    for<'a> S: UnionAssign<&'a S> + DifferenceAssign<&'a S> + IntersectionAssign<&'a S>,
+{
+    fn subset_of(&self, rhs: &Scoped<ScopeId, S>) -> bool {
+        self.grants.subset_of(&rhs.grants)
+            && (self.children.is_none() && rhs.children.is_none()
+                || self.listed_children().subset_of(&rhs.listed_children()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use rstest::*;
+
+    #[allow(unused_imports)]
+    use super::*;
+
+    type Perms = Scoped<&'static str, HashSet<&'static str>>;
+
+    fn set(values: &[&'static str]) -> HashSet<&'static str> {
+        values.iter().copied().collect()
+    }
+
+    fn granted(scope: &[Scope<&'static str>], values: &[&'static str]) -> Perms {
+        Scoped::granted(scope, set(values))
+    }
+
+    fn perms() -> Perms {
+        let mut perms = Scoped::new(set(&["login"]));
+        perms.grant(&[Scope::Id("acme")], set(&["read"]));
+        perms.grant(&[Scope::Id("acme"), Scope::Every], set(&["write"]));
+        perms.revoke(&[Scope::Id("acme"), Scope::Id("billing")], set(&["write"]));
+        perms
+    }
+
+    #[rstest]
+    #[case(&[], &["login"])]
+    #[case(&["globex"], &["login"])]
+    #[case(&["acme"], &["login", "read"])]
+    #[case(&["acme", "website"], &["login", "read", "write"])]
+    #[case(&["acme", "website", "logo"], &["login", "read", "write"])]
+    #[case(&["acme", "billing", "invoices"], &["login", "read"])]
+    fn at_tests(#[case] scope: &[&'static str], #[case] expected: &[&'static str]) {
+        assert_eq!(perms().at(scope), set(expected));
+    }
+
+    #[rstest]
+    #[case(granted(&[], &["read"]), granted(&[Scope::Id("acme")], &["read"]), &["acme"], &[])]
+    #[case(granted(&[], &["read"]), granted(&[Scope::Id("acme")], &["read"]), &["globex"], &["read"])]
+    #[case(granted(&[], &["read"]), granted(&[Scope::Id("acme")], &["read"]), &[], &["read"])]
+    #[case(granted(&[Scope::Every], &["read"]), granted(&[Scope::Every, Scope::Id("billing")], &["read"]), &["acme", "billing"], &[])]
+    fn difference_tests(
+        #[case] mut a: Perms,
+        #[case] b: Perms,
+        #[case] scope: &[&'static str],
+        #[case] expected: &[&'static str],
+    ) {
+        a.difference_assign(&b);
+        assert_eq!(a.at(scope), set(expected));
+    }
+
+    #[rstest]
+    #[case(granted(&[], &["read", "write"]), granted(&[Scope::Id("acme")], &["write"]), &[], &[])]
+    #[case(granted(&[], &["read", "write"]), granted(&[Scope::Id("acme")], &["write"]), &["acme", "website"], &["write"])]
+    #[case(granted(&[Scope::Every], &["read"]), granted(&[Scope::Id("acme")], &["read"]), &["globex"], &[])]
+    fn intersection_tests(
+        #[case] mut a: Perms,
+        #[case] b: Perms,
+        #[case] scope: &[&'static str],
+        #[case] expected: &[&'static str],
+    ) {
+        a.intersection_assign(&b);
+        assert_eq!(a.at(scope), set(expected));
+    }
+
+    #[test]
+    fn normalize_test() {
+        let mut perms = perms();
+        perms.revoke(&[], set(&["login", "read", "write"]));
+        assert!(perms.is_empty());
+
+        let mut perms = granted(&[Scope::Id("acme")], &["read"]);
+        perms.union_assign(&granted(&[], &["read"]));
+        assert_eq!(perms, granted(&[], &["read"]));
+    }
+
+    #[rstest]
+    #[case(granted(&[Scope::Id("acme")], &["read"]), granted(&[], &["read"]), true)]
+    #[case(granted(&[], &["read"]), granted(&[Scope::Id("acme")], &["read"]), false)]
+    #[case(granted(&[Scope::Id("acme"), Scope::Id("website")], &["read"]), granted(&[Scope::Id("acme"), Scope::Every], &["read"]), true)]
+    #[case(granted(&[Scope::Id("acme")], &["read"]), granted(&[Scope::Id("acme"), Scope::Every], &["read"]), false)]
+    fn subset_of_tests(#[case] a: Perms, #[case] b: Perms, #[case] expected: bool) {
+        assert_eq!(a.subset_of(&b), expected);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_test() {
+        let perms: Scoped<String, bool> = Scoped::granted(&[Scope::Id("acme".to_string())], true);
+        let json = serde_json::to_string(&perms).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"children":{"rest_list":{"acme":{"grants":true}}}}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<Scoped<String, bool>>(&json).unwrap(),
+            perms
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[rstest]
+    #[case(r#"{"children":{}}"#, Scoped::empty())]
+    #[case(
+        r#"{"grants":true,"children":{"wildcard_value":{"grants":true}}}"#,
+        Scoped::new(true)
+    )]
+    #[case(
+        r#"{"children":{"rest_list":{"acme":{"children":{}}}}}"#,
+        Scoped::empty()
+    )]
+    fn deserialize_normalize_tests(#[case] json: &str, #[case] expected: Scoped<String, bool>) {
+        let perms = serde_json::from_str::<Scoped<String, bool>>(json).unwrap();
+
+        assert!(perms.children().is_none());
+        assert_eq!(perms, expected);
+    }
+}
@@ -0,0 +1,281 @@
+use crate::Set;
+use crate::operations::{DifferenceAssign, IntersectionAssign, UnionAssign};
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::ops::Bound;
+
+/// A set whose parts expire, such as temporary bans or trial perks, where `T` is any ordered instant type.
+///
+/// Every part of the set is stored once, under the instant it expires at, and is held at every instant before that.
+/// Unions keep the later expiry of parts held by both sets and intersections keep the earlier one.
+///
+/// The difference and disjunctive union of two timed sets could hold parts that start in the future, which can not be expressed by expiries alone, so they are not implemented. Instead, parts can be revoked for good with the difference of an untimed set.
+///
+/// Deserializing grants every part with [`Timed::grant`], so empty parts are dropped and parts held under several expiries keep the latest one.
+///
+/// ```
+/// # use std::collections::HashSet;
+/// # use finit::collections::Timed;
+/// let mut perks = Timed::new();
+/// perks.grant(HashSet::from(["fly"]), 10);
+/// perks.grant(HashSet::from(["fly", "glow"]), 20);
+///
+/// assert_eq!(perks.at(&5), HashSet::from(["fly", "glow"]));
+/// assert_eq!(perks.next_change(), Some(&20));
+///
+/// perks.expire(&20);
+/// assert_eq!(perks.at(&20), HashSet::new());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(transparent))]
+pub struct Timed<S, T: Ord> {
+    /// The disjoint, non-empty parts of the set by the instant they expire at.
+    expiries: BTreeMap<T, S>,
+}
+
+impl<S, T: Ord> Timed<S, T> {
+    pub fn new() -> Self {
+        Self {
+            expiries: BTreeMap::new(),
+        }
+    }
+
+    /// The disjoint parts of the set by the instant they expire at.
+    pub fn expiries(&self) -> &BTreeMap<T, S> {
+        &self.expiries
+    }
+
+    /// The instant that the next part of the set expires at, which is when [`Timed::at`] changes next.
+    ///
+    /// Parts that have already expired are kept until they are removed with [`Timed::expire`], so call it first to get the next change after now.
+    pub fn next_change(&self) -> Option<&T> {
+        self.expiries.keys().next()
+    }
+
+    /// Removes every part that has expired at `instant`.
+    pub fn expire(&mut self, instant: &T) {
+        let mut later = self.expiries.split_off(instant);
+        later.remove(instant);
+        self.expiries = later;
+    }
+}
+
+impl<S: Set<Empty = S> + Clone, T: Ord + Clone> Timed<S, T>
+where
+    for<'a> S: UnionAssign<&'a S> + DifferenceAssign<&'a S>,
+{
+    /// Grants `set` until `expiry`, keeping the later expiry of parts that are already held.
+    pub fn grant(&mut self, set: S, expiry: T) {
+        if set.is_empty() {
+            return;
+        }
+
+        self.union_assign(&Timed {
+            expiries: BTreeMap::from([(expiry, set)]),
+        });
+    }
+
+    /// The set that is held at `instant`, which is every part that expires after it.
+    pub fn at(&self, instant: &T) -> S {
+        let mut set = S::empty();
+
+        for part in self
+            .expiries
+            .range((Bound::Excluded(instant), Bound::Unbounded))
+            .map(|(_, part)| part)
+        {
+            set.union_assign(part);
+        }
+
+        set
+    }
+
+    /// Combines the sets held by `self` and `rhs` at every instant with `operation`, going from the latest expiry to the earliest.
+    ///
+    /// The part stored under an expiry is what is held until then, without what is already stored under later expiries.
+    fn combine_assign(&mut self, rhs: &Timed<S, T>, operation: impl Fn(&mut S, &S)) {
+        let mut expiries: Vec<&T> = self.expiries.keys().chain(rhs.expiries.keys()).collect();
+        expiries.sort();
+        expiries.dedup();
+
+        let mut held = S::empty();
+        let mut rhs_held = S::empty();
+        let mut covered = S::empty();
+        let mut result = BTreeMap::new();
+
+        for expiry in expiries.into_iter().rev() {
+            if let Some(part) = self.expiries.get(expiry) {
+                held.union_assign(part);
+            }
+
+            if let Some(part) = rhs.expiries.get(expiry) {
+                rhs_held.union_assign(part);
+            }
+
+            let mut part = held.clone();
+            operation(&mut part, &rhs_held);
+            part.difference_assign(&covered);
+
+            if !part.is_empty() {
+                covered.union_assign(&part);
+                result.insert(expiry.clone(), part);
+            }
+        }
+
+        self.expiries = result;
+    }
+}
+
+impl<S: Set<Empty = S>, T: Ord> Set for Timed<S, T> {
+    type Empty = Self;
+
+    fn is_empty(&self) -> bool {
+        self.expiries.is_empty()
+    }
+
+    fn empty() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Set<Empty = S>, T: Ord> Default for Timed<S, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Set<Empty = S> + Clone, T: Ord + Clone> UnionAssign<&Timed<S, T>> for Timed<S, T>
+where
+    for<'a> S: UnionAssign<&'a S> + DifferenceAssign<&'a S>,
+{
+    fn union_assign(&mut self, rhs: &Timed<S, T>) {
+        self.combine_assign(rhs, |part, rhs_part| part.union_assign(rhs_part));
+    }
+}
+
+impl<S: Set<Empty = S> + Clone, T: Ord + Clone> IntersectionAssign<&Timed<S, T>> for Timed<S, T>
+where
+    for<'a> S: UnionAssign<&'a S> + DifferenceAssign<&'a S> + IntersectionAssign<&'a S>,
+{
+    fn intersection_assign(&mut self, rhs: &Timed<S, T>) {
+        self.combine_assign(rhs, |part, rhs_part| part.intersection_assign(rhs_part));
+    }
+}
+
+/// Revokes `rhs` at every instant.
+impl<S: Set<Empty = S>, T: Ord> DifferenceAssign<&S> for Timed<S, T>
+where
+    for<'a> S: DifferenceAssign<&'a S>,
+{
+    fn difference_assign(&mut self, rhs: &S) {
+        self.expiries.retain(|_, part| {
+            part.difference_assign(rhs);
+            !part.is_empty()
+        });
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, S, T> serde::Deserialize<'de> for Timed<S, T>
+where
+    S: Set<Empty = S> + Clone + serde::Deserialize<'de>,
+    T: Ord + Clone + serde::Deserialize<'de>,
+    for<'a> S: UnionAssign<&'a S> + DifferenceAssign<&'a S>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut timed = Timed::new();
+
+        for (expiry, set) in BTreeMap::<T, S>::deserialize(deserializer)? {
+            timed.grant(set, expiry);
+        }
+
+        Ok(timed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use rstest::*;
+
+    #[allow(unused_imports)]
+    use super::*;
+
+    type Perks = Timed<HashSet<u8>, u32>;
+
+    fn timed<const N: usize>(parts: [(u32, &[u8]); N]) -> Perks {
+        let mut timed = Timed::new();
+        for (expiry, part) in parts {
+            timed.grant(part.iter().copied().collect(), expiry);
+        }
+        timed
+    }
+
+    fn set(values: &[u8]) -> HashSet<u8> {
+        values.iter().copied().collect()
+    }
+
+    #[rstest]
+    #[case(timed([(10, &[1, 2])]), timed([(20, &[2, 3])]), timed([(10, &[1]), (20, &[2, 3])]))]
+    #[case(timed([(20, &[1])]), timed([(10, &[1])]), timed([(20, &[1])]))]
+    #[case(timed([]), timed([(10, &[1])]), timed([(10, &[1])]))]
+    fn union_tests(#[case] mut a: Perks, #[case] b: Perks, #[case] result: Perks) {
+        a.union_assign(&b);
+        assert_eq!(a, result);
+    }
+
+    #[rstest]
+    #[case(timed([(10, &[1, 2])]), timed([(20, &[2, 3])]), timed([(10, &[2])]))]
+    #[case(timed([(10, &[1]), (30, &[2])]), timed([(20, &[1, 2])]), timed([(10, &[1]), (20, &[2])]))]
+    #[case(timed([(10, &[1])]), timed([]), timed([]))]
+    fn intersection_tests(#[case] mut a: Perks, #[case] b: Perks, #[case] result: Perks) {
+        a.intersection_assign(&b);
+        assert_eq!(a, result);
+    }
+
+    #[test]
+    fn revoke_test() {
+        let mut perks = timed([(10, &[1]), (20, &[2, 3])]);
+        perks.difference_assign(&set(&[1, 2]));
+
+        assert_eq!(perks, timed([(20, &[3])]));
+    }
+
+    #[rstest]
+    #[case(0, set(&[1, 2, 3]))]
+    #[case(9, set(&[1, 2, 3]))]
+    #[case(10, set(&[2, 3]))]
+    #[case(20, set(&[]))]
+    fn at_tests(#[case] instant: u32, #[case] expected: HashSet<u8>) {
+        assert_eq!(timed([(10, &[1, 2]), (20, &[2, 3])]).at(&instant), expected);
+    }
+
+    #[test]
+    fn expire_test() {
+        let mut perks = timed([(10, &[1]), (20, &[2]), (30, &[3])]);
+        assert_eq!(perks.next_change(), Some(&10));
+
+        perks.expire(&20);
+        assert_eq!(perks, timed([(30, &[3])]));
+        assert_eq!(perks.next_change(), Some(&30));
+
+        perks.expire(&30);
+        assert!(perks.is_empty());
+        assert_eq!(perks.next_change(), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[rstest]
+    #[case(r#"{"10":[1],"20":[2]}"#, timed([(10, &[1]), (20, &[2])]))]
+    #[case(r#"{"10":[],"20":[2]}"#, timed([(20, &[2])]))]
+    #[case(r#"{"10":[1,2],"20":[2]}"#, timed([(10, &[1]), (20, &[2])]))]
+    fn deserialize_tests(#[case] json: &str, #[case] expected: Perks) {
+        let perks: Perks = serde_json::from_str(json).unwrap();
+
+        assert_eq!(perks, expected);
+        assert_eq!(
+            serde_json::from_str::<Perks>(&serde_json::to_string(&perks).unwrap()).unwrap(),
+            perks
+        );
+    }
+}
@@ -1,533 +1,30 @@
 use crate::Set;
 use crate::comparisons::{SetEq, SubsetOf};
-use crate::operations::identity::{
-    disjunctive_union_using_difference_and_union, intersection_using_double_difference,
-};
 use crate::operations::{
     Difference, DifferenceAssign, DisjunctiveUnionAssign, IntersectionAssign, Union, UnionAssign,
 };
 use std::{collections::HashMap, hash::Hash, ops::Deref};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-/// An extension of [`HashMap`] with easy representation of undefined key values.
-///
-/// This is a generalization of a [`HashMap`] which cannot represent the universal set of its domain in an easy way without having to define a specific value for every value possible of its key type.
-///
-/// For a similar structure that uses a [`std::collections::BTreeMap`] instead of a [`HashMap`], see [`super::WildcardBTreeMap`].
-pub struct WildcardHashMap<Key: Hash + Eq + Clone, Value: Set<Empty = Value>> {
-    #[cfg_attr(
-        feature = "serde",
-        serde(default = "HashMap::empty", skip_serializing_if = "HashMap::is_empty")
-    )]
-    wildcard_exceptions: HashMap<Key, Value>,
-    #[cfg_attr(
-        feature = "serde",
-        serde(
-            default = "Box::<Value>::empty",
-            skip_serializing_if = "Box::<Value>::is_empty"
-        )
-    )]
-    wildcard_value: Box<Value>,
-    #[cfg_attr(
-        feature = "serde",
-        serde(default = "HashMap::empty", skip_serializing_if = "HashMap::is_empty")
-    )]
-    rest_list: HashMap<Key, Value>,
-}
-
+wildcard_map!(
+    /// An extension of [`HashMap`] with easy representation of undefined key values.
+    ///
+    /// This is a generalization of a [`HashMap`] which cannot represent the universal set of its domain in an easy way without having to define a specific value for every value possible of its key type.
+    ///
+    /// For a similar structure that uses a [`std::collections::BTreeMap`] instead of a [`HashMap`], see [`super::WildcardBTreeMap`].
+    WildcardHashMap,
+    map: HashMap,
+    key: Hash,
+    wildcard: Box,
+    remove_empty_keys: crate::impls::hashmap::remove_empty_keys,
+    serde: ("HashMap::empty", "HashMap::is_empty", "Box::<Value>::empty", "Box::<Value>::is_empty"),
+    Value: ()
+);
+
+#[cfg(feature = "rayon")]
 impl<Key: Hash + Eq + Clone, Value: Set<Empty = Value>> WildcardHashMap<Key, Value> {
-    pub fn new(wildcard_value: Value) -> Self {
-        Self {
-            wildcard_exceptions: HashMap::empty(),
-            wildcard_value: Box::new(wildcard_value),
-            rest_list: HashMap::empty(),
-        }
-    }
-}
-
-impl<Key: Hash + Eq + Clone, Value: Set<Empty = Value>> Set for WildcardHashMap<Key, Value> {
-    type Empty = Self;
-
-    fn is_empty(&self) -> bool {
-        self.rest_list.is_empty() && self.wildcard_value.is_empty()
-    }
-
-    fn empty() -> Self::Empty {
-        WildcardHashMap {
-            wildcard_exceptions: HashMap::empty(),
-            wildcard_value: Box::new(Value::empty()),
-            rest_list: HashMap::empty(),
-        }
-    }
-}
-
-impl<Key: Hash + Eq + Clone, Value: Set<Empty = Value>> Default for WildcardHashMap<Key, Value> {
-    fn default() -> Self {
-        Self::empty()
-    }
-}
-
-impl<Key: Hash + Eq + Clone, Value: Set<Empty = Value>> From<HashMap<Key, Value>>
-    for WildcardHashMap<Key, Value>
-{
-    fn from(rest_list: HashMap<Key, Value>) -> Self {
-        WildcardHashMap {
-            rest_list,
-            ..Default::default()
-        }
-    }
-}
-
-// WildcardList A <-> List B
-impl<Key: Hash + Eq + Clone, Value: Set<Empty = Value>, OtherValue: Clone>
-    UnionAssign<&HashMap<Key, OtherValue>> for WildcardHashMap<Key, Value>
-where
-    for<'a> Value: DifferenceAssign<&'a Value>
-        + IntersectionAssign<&'a OtherValue>
-        + UnionAssign<&'a OtherValue>,
-    for<'a> OtherValue: DifferenceAssign<&'a Value>,
-{
-    fn union_assign(&mut self, rhs: &HashMap<Key, OtherValue>) {
-        for (key, value) in rhs.iter() {
-            //For each key, for the intersection that is covered by the wildcard and value, remove it from the exceptions for this key.
-            //For the rest (that is not part of the intersection), add it to the rest list.
-            self.wildcard_value.intersection_assign(value);
-
-            let mut rest = value.clone();
-
-            rest.difference_assign(&self.wildcard_value);
-
-            if !self.wildcard_value.is_empty() {
-                let mut remove: bool = false;
-                if let Some(val) = self.wildcard_exceptions.get_mut(key) {
-                    val.difference_assign(&self.wildcard_value);
-
-                    remove = val.is_empty();
-                };
-
-                if remove {
-                    self.wildcard_exceptions.remove(key);
-                };
-            }
-
-            if !rest.is_empty() {
-                self.rest_list
-                    .entry(key.clone())
-                    .or_insert_with(Value::empty)
-                    .union_assign(&rest);
-            }
-        }
-    }
-}
-
-impl<Key: Hash + Eq + Clone, Value: Set<Empty = Value>, OtherValue: Clone>
-    Union<&HashMap<Key, OtherValue>> for WildcardHashMap<Key, Value>
-where
-    for<'a> Value: DifferenceAssign<&'a Value>
-        + IntersectionAssign<&'a OtherValue>
-        + UnionAssign<&'a OtherValue>,
-    for<'a> OtherValue: DifferenceAssign<&'a Value>,
-{
-    type Output = Self;
-
-    fn union(mut self, rhs: &HashMap<Key, OtherValue>) -> Self::Output {
-        self.union_assign(rhs);
-        self
-    }
-}
-
-impl<Key: Hash + Eq + Clone, Value: Set<Empty = Value>, OtherValue: Clone>
-    Union<HashMap<Key, OtherValue>> for WildcardHashMap<Key, Value>
-where
-    for<'a> Self: Union<&'a HashMap<Key, OtherValue>, Output = Self>,
-{
-    type Output = Self;
-
-    fn union(self, rhs: HashMap<Key, OtherValue>) -> Self::Output {
-        self.union(&rhs)
-    }
-}
-
-impl<Key: Hash + Eq + Clone, Value: Set<Empty = Value>, OtherValue: Set<Empty = OtherValue>>
-    DifferenceAssign<&HashMap<Key, OtherValue>> for WildcardHashMap<Key, Value>
-where
-    for<'a> Value: DifferenceAssign<&'a OtherValue>
-        + IntersectionAssign<&'a OtherValue>
-        + UnionAssign<&'a Value>,
-{
-    fn difference_assign(&mut self, rhs: &HashMap<Key, OtherValue>) {
-        self.rest_list.difference_assign(rhs);
-
-        for (key, value) in rhs.iter() {
-            self.wildcard_value.intersection_assign(value);
-
-            // Whatever intersection exists between the wildcard and the value of a key should be inserted as an exception on that key.
-            if !self.wildcard_value.is_empty() {
-                self.wildcard_exceptions
-                    .entry(key.clone())
-                    .or_insert_with(Value::empty)
-                    .union_assign(&self.wildcard_value);
-            }
-        }
-    }
-}
-
-impl<Key: Hash + Eq + Clone, Value: Set<Empty = Value>, OtherValue: Set<Empty = OtherValue>>
-    Difference<&HashMap<Key, OtherValue>> for WildcardHashMap<Key, Value>
-where
-    for<'a> Value: DifferenceAssign<&'a OtherValue>
-        + IntersectionAssign<&'a OtherValue>
-        + UnionAssign<&'a Value>,
-{
-    type Output = Self;
-
-    fn difference(mut self, rhs: &HashMap<Key, OtherValue>) -> Self::Output {
-        self.difference_assign(rhs);
-        self
-    }
-}
-
-// WildcardList A <-> WildcardList B
-impl<Key, Value, OtherValue> UnionAssign<&WildcardHashMap<Key, OtherValue>>
-    for WildcardHashMap<Key, Value>
-where
-    Key: Hash + Eq + Clone,
-    Value: Set<Empty = Value> + Clone,
-    for<'a> Value: DifferenceAssign<&'a Value>
-        + DifferenceAssign<&'a OtherValue>
-        + UnionAssign<&'a OtherValue>,
-    OtherValue: Set<Empty = OtherValue> + Clone,
-    for<'a> OtherValue: DifferenceAssign<&'a Value> + DifferenceAssign<&'a OtherValue>,
-    for<'a> HashMap<Key, Value>: UnionAssign<&'a HashMap<Key, OtherValue>>,
-{
-    fn union_assign(&mut self, rhs: &WildcardHashMap<Key, OtherValue>) {
-        let mut cleaned_rhs_wildcard_exceptions = rhs.wildcard_exceptions.clone();
-
-        /// This function removes covered exceptions from a wildcard value (and it's associated exceptions).
-        fn remove_covered_values<Key, Value, OtherValue>(
-            exceptions: &mut HashMap<Key, OtherValue>,
-            wildcard_value: &Value,
-            wildcard_exceptions: &HashMap<Key, Value>,
-        ) where
-            Key: Hash + Eq + Clone,
-            Value: Set<Empty = Value> + Clone,
-            for<'a> Value: DifferenceAssign<&'a Value>, // + DifferenceAssign<&'a OtherValue>,
-            for<'a> OtherValue: DifferenceAssign<&'a Value>, // + DifferenceAssign<&'a OtherValue>,
-        {
-            for (key, other_exception) in exceptions.iter_mut() {
-                if let Some(exception) = wildcard_exceptions.get(key) {
-                    let mut wildcard_value = wildcard_value.clone();
-                    wildcard_value.difference_assign(exception);
-                    other_exception.difference_assign(&wildcard_value);
-                } else {
-                    other_exception.difference_assign(wildcard_value);
-                };
-            }
-
-            crate::impls::hashmap::remove_empty_keys(exceptions);
-        }
-
-        // Remove exceptions in rhs covered by selfs wildcard.
-        remove_covered_values(
-            &mut cleaned_rhs_wildcard_exceptions,
-            self.wildcard_value.as_ref(),
-            &self.wildcard_exceptions,
-        );
-        cleaned_rhs_wildcard_exceptions.difference_assign(&self.rest_list);
-
-        // Remove exceptions in self covered by rhs' wildcard.
-        remove_covered_values(
-            &mut self.wildcard_exceptions,
-            rhs.wildcard_value.as_ref(),
-            &rhs.wildcard_exceptions,
-        );
-        self.wildcard_exceptions.difference_assign(&rhs.rest_list);
-
-        // Merge the exception lists and the wildcards.
-        self.wildcard_exceptions
-            .union_assign(&cleaned_rhs_wildcard_exceptions);
-        self.wildcard_value.union_assign(rhs.wildcard_value.deref());
-
-        // Merge rest lists.
-        self.rest_list.union_assign(&rhs.rest_list);
-
-        // Remove values in rest list covered by new wildcard.
-        remove_covered_values(
-            &mut self.rest_list,
-            self.wildcard_value.as_ref(),
-            &self.wildcard_exceptions,
-        );
-    }
-}
-
-impl<Key, Value, OtherValue> Union<&WildcardHashMap<Key, OtherValue>>
-    for WildcardHashMap<Key, Value>
-where
-    Key: Hash + Eq + Clone,
-    Value: Set<Empty = Value> + Clone,
-    for<'a> Value: DifferenceAssign<&'a Value>
-        + DifferenceAssign<&'a OtherValue>
-        + UnionAssign<&'a OtherValue>,
-    OtherValue: Set<Empty = OtherValue> + Clone,
-    for<'a> OtherValue: DifferenceAssign<&'a Value> + DifferenceAssign<&'a OtherValue>,
-    for<'a> HashMap<Key, Value>: UnionAssign<&'a HashMap<Key, OtherValue>>,
-{
-    type Output = Self;
-
-    fn union(mut self, rhs: &WildcardHashMap<Key, OtherValue>) -> Self::Output {
-        self.union_assign(rhs);
-        self
-    }
-}
-
-impl<Key, Value, OtherValue> Union<WildcardHashMap<Key, OtherValue>> for WildcardHashMap<Key, Value>
-where
-    Key: Hash + Eq + Clone,
-    Value: Set<Empty = Value> + Clone,
-    OtherValue: Set<Empty = OtherValue> + Clone,
-    for<'a> Self: Union<&'a WildcardHashMap<Key, OtherValue>, Output = Self>,
-{
-    type Output = Self;
-
-    fn union(self, rhs: WildcardHashMap<Key, OtherValue>) -> Self::Output {
-        self.union(&rhs)
-    }
-}
-
-impl<Key, Value, OtherValue> DifferenceAssign<&WildcardHashMap<Key, OtherValue>>
-    for WildcardHashMap<Key, Value>
-where
-    Key: Hash + Eq + Clone,
-    Value: Set<Empty = Value> + Clone,
-    for<'a> Value: DifferenceAssign<&'a Value>
-        + DifferenceAssign<&'a OtherValue>
-        + IntersectionAssign<&'a OtherValue>
-        + UnionAssign<&'a OtherValue>
-        + UnionAssign<&'a Value>,
-    OtherValue: Set<Empty = OtherValue> + Clone,
-    for<'a> OtherValue: IntersectionAssign<&'a Value>,
-{
-    fn difference_assign(&mut self, rhs: &WildcardHashMap<Key, OtherValue>) {
-        //If exception exists for X key, that value should not be removed for that key.
-        //That means, if there is an intersection between that exception and the wildcard value, it should be added to the rest list.
-        for (key, other_exception) in rhs.wildcard_exceptions.iter() {
-            let mut value = self.wildcard_value.deref().clone();
-
-            if let Some(exception) = self.wildcard_exceptions.get(key) {
-                value.difference_assign(exception);
-            }
-
-            value.intersection_assign(other_exception);
-
-            if value.is_empty() {
-                continue;
-            }
-
-            if let Some(rest_value) = self.rest_list.get_mut(key) {
-                rest_value.union_assign(&value);
-            } else {
-                self.rest_list.insert(key.clone(), value);
-            }
-        }
-        //Remove rhs wildcard from self wildcard.
-        self.wildcard_value
-            .difference_assign(rhs.wildcard_value.as_ref());
-
-        // If any rest list items in rhs intersect with the self wildcard, add them to the exceptions.
-        // Subtract any rest list items in self with rhs.
-
-        for (key, value) in rhs.rest_list.iter() {
-            let mut value = value.clone();
-
-            value.intersection_assign(&self.wildcard_value);
-
-            if value.is_empty() {
-                continue;
-            }
-
-            self.wildcard_exceptions
-                .entry(key.clone())
-                .or_insert_with(Value::empty)
-                .union_assign(&value);
-        }
-
-        self.rest_list.difference_assign(&rhs.rest_list);
-    }
-}
-
-impl<Key, Value, OtherValue> Difference<&WildcardHashMap<Key, OtherValue>>
-    for WildcardHashMap<Key, Value>
-where
-    Key: Hash + Eq + Clone,
-    Value: Set<Empty = Value> + Clone,
-    OtherValue: Set<Empty = OtherValue> + Clone,
-    for<'a> Self: DifferenceAssign<&'a WildcardHashMap<Key, OtherValue>>,
-{
-    type Output = Self;
-
-    fn difference(mut self, rhs: &WildcardHashMap<Key, OtherValue>) -> Self::Output {
-        self.difference_assign(rhs);
-        self
-    }
-}
-
-impl<Key, Value, OtherValue> Difference<WildcardHashMap<Key, OtherValue>>
-    for WildcardHashMap<Key, Value>
-where
-    Key: Hash + Eq + Clone,
-    Value: Set<Empty = Value> + Clone,
-    OtherValue: Set<Empty = OtherValue> + Clone,
-    for<'a> Self: Difference<&'a WildcardHashMap<Key, OtherValue>, Output = Self>,
-{
-    type Output = Self;
-
-    fn difference(self, rhs: WildcardHashMap<Key, OtherValue>) -> Self::Output {
-        self.difference(&rhs)
-    }
-}
-
-impl<Key, Value, OtherValue> IntersectionAssign<&WildcardHashMap<Key, OtherValue>>
-    for WildcardHashMap<Key, Value>
-where
-    Key: Hash + Eq + Clone,
-    Value: Set<Empty = Value> + Clone,
-    OtherValue: Set<Empty = OtherValue> + Clone,
-    for<'a> Self: Difference<&'a WildcardHashMap<Key, OtherValue>, Output = Self>,
-    Self: Difference<Self, Output = Self>,
-{
-    fn intersection_assign(&mut self, rhs: &WildcardHashMap<Key, OtherValue>) {
-        *self = intersection_using_double_difference(self.clone(), rhs);
-    }
-}
-
-impl<Key, Value, OtherValue> IntersectionAssign<WildcardHashMap<Key, OtherValue>>
-    for WildcardHashMap<Key, Value>
-where
-    Key: Hash + Eq + Clone,
-    Value: Set<Empty = Value> + Clone,
-    OtherValue: Set<Empty = OtherValue> + Clone,
-    for<'a> Self: IntersectionAssign<&'a WildcardHashMap<Key, OtherValue>>,
-{
-    fn intersection_assign(&mut self, rhs: WildcardHashMap<Key, OtherValue>) {
-        self.intersection_assign(&rhs);
-    }
-}
-
-impl<Key, Value, OtherValue> DisjunctiveUnionAssign<&WildcardHashMap<Key, OtherValue>>
-    for WildcardHashMap<Key, Value>
-where
-    Key: Hash + Eq + Clone,
-    Value: Set<Empty = Value> + Clone,
-    OtherValue: Set<Empty = OtherValue> + Clone,
-    Self: DisjunctiveUnionAssign<WildcardHashMap<Key, OtherValue>>,
-    WildcardHashMap<Key, OtherValue>: Clone,
-{
-    fn disjunctive_union_assign(&mut self, rhs: &WildcardHashMap<Key, OtherValue>) {
-        self.disjunctive_union_assign(rhs.clone());
-    }
-}
-
-impl<Key, Value, OtherValue> DisjunctiveUnionAssign<WildcardHashMap<Key, OtherValue>>
-    for WildcardHashMap<Key, Value>
-where
-    Key: Hash + Eq + Clone,
-    Value: Set<Empty = Value> + Clone,
-    OtherValue: Set<Empty = OtherValue> + Clone,
-    Self: Difference<WildcardHashMap<Key, OtherValue>, Output = Self> + Union<Self, Output = Self>,
-    WildcardHashMap<Key, OtherValue>: Difference<Self, Output = Self>,
-{
-    fn disjunctive_union_assign(&mut self, rhs: WildcardHashMap<Key, OtherValue>) {
-        *self = disjunctive_union_using_difference_and_union(self.clone(), rhs);
-    }
-}
-
-impl<
-    Key: Hash + Eq + Clone,
-    Value: Set<Empty = Value> + SetEq<OtherValue>,
-    OtherValue: Set<Empty = OtherValue>,
-> SetEq<WildcardHashMap<Key, OtherValue>> for WildcardHashMap<Key, Value>
-{
-    fn set_eq(&self, rhs: &WildcardHashMap<Key, OtherValue>) -> bool {
-        self.wildcard_value.set_eq(rhs.wildcard_value.as_ref())
-            && self.wildcard_exceptions.set_eq(&rhs.wildcard_exceptions)
-            && self.rest_list.set_eq(&rhs.rest_list)
-    }
-}
-
-impl<Key: Hash + Eq + Clone, Value: Set<Empty = Value> + SetEq<OtherValue>, OtherValue: Set>
-    SetEq<HashMap<Key, OtherValue>> for WildcardHashMap<Key, Value>
-{
-    fn set_eq(&self, rhs: &HashMap<Key, OtherValue>) -> bool {
-        self.wildcard_value.is_empty() && self.rest_list.set_eq(rhs)
-    }
-}
-
-impl<
-    Key: Hash + Eq + Clone,
-    Value: Set<Empty = Value> + SubsetOf<OtherValue>,
-    OtherValue: Set<Empty = OtherValue>,
-> SubsetOf<WildcardHashMap<Key, OtherValue>> for WildcardHashMap<Key, Value>
-where
-    for<'a> Value: DifferenceAssign<&'a Value>
-        + DifferenceAssign<&'a OtherValue>
-        + UnionAssign<&'a Value>
-        + IntersectionAssign<&'a OtherValue>
-        + Clone,
-{
-    fn subset_of(&self, rhs: &WildcardHashMap<Key, OtherValue>) -> bool {
-        // The wildcard itself must be a subset
-        if !self.wildcard_value.subset_of(rhs.wildcard_value.as_ref()) {
-            return false;
-        }
-
-        let mut checked_keys = std::collections::HashSet::new();
-
-        for key in rhs.wildcard_exceptions.keys().chain(self.rest_list.keys()) {
-            if checked_keys.contains(key) {
-                continue;
-            }
-            checked_keys.insert(key);
-
-            let mut a = self.wildcard_value.deref().clone();
-            if let Some(exc) = self.wildcard_exceptions.get(key) {
-                a.difference_assign(exc);
-            }
-            if let Some(rest) = self.rest_list.get(key) {
-                a.union_assign(rest);
-            }
-
-            if let Some(rhs_rest) = rhs.rest_list.get(key) {
-                a.difference_assign(rhs_rest);
-            }
-
-            let mut a_minus_wb = a.clone();
-            a_minus_wb.difference_assign(rhs.wildcard_value.as_ref());
-            if !a_minus_wb.is_empty() {
-                return false;
-            }
-
-            if let Some(rhs_exc) = rhs.wildcard_exceptions.get(key) {
-                a.intersection_assign(rhs_exc);
-                if !a.is_empty() {
-                    return false;
-                }
-            }
-        }
-
-        true
-    }
-}
-
-impl<Key: Hash + Eq + Clone, Value: Set<Empty = Value> + SubsetOf<OtherValue>, OtherValue: Set>
-    SubsetOf<HashMap<Key, OtherValue>> for WildcardHashMap<Key, Value>
-{
-    fn subset_of(&self, rhs: &HashMap<Key, OtherValue>) -> bool {
-        if !self.wildcard_value.is_empty() {
-            return false;
-        }
-
-        self.rest_list.subset_of(rhs)
+    /// Takes the rest list out of the map, leaving the wildcard value with its exceptions, so the parallel union can split the rest lists by key.
+    pub(crate) fn take_rest_list(&mut self) -> HashMap<Key, Value> {
+        std::mem::take(&mut self.rest_list)
     }
 }
 
@@ -539,6 +36,9 @@ mod tests {
 
     #[allow(unused_imports)]
     use super::*;
+    use crate::operations::identity::{
+        disjunctive_union_using_difference_and_union, intersection_using_double_difference,
+    };
 
     use maplit::hashmap;
 
@@ -674,6 +174,17 @@ mod tests {
         },
         rest_list: hashmap! {}
     })]
+    #[case(WildcardHashMap::<i32, bool> {
+        wildcard_value: Box::new(true),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {},
+    }, hashmap! {
+        1 => false,
+    }, WildcardHashMap {
+        wildcard_value: Box::new(true),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {}
+    })]
     fn union_list_tests<I1, I2, R>(#[case] mut list1: I1, #[case] list2: I2, #[case] result: R)
     where
         I1: PartialEq<R> + Debug,
@@ -823,6 +334,45 @@ mod tests {
             }
         }
     })]
+    #[case(WildcardHashMap::<i32, bool> {
+        wildcard_value: Box::new(false),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {
+            1 => true,
+        },
+    }, WildcardHashMap {
+        wildcard_value: Box::new(true),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {},
+    }, WildcardHashMap {
+        wildcard_value: Box::new(false),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {}
+    })]
+    // WildcardList A <-> List B
+    #[case(WildcardHashMap {
+        wildcard_value: Box::new(hashmap! {
+            1 => true,
+            2 => true
+        }),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {},
+    }, hashmap! {
+        1 => hashmap! {
+            1 => true
+        },
+    }, WildcardHashMap {
+        wildcard_value: Box::new(hashmap! {
+            1 => true,
+            2 => true
+        }),
+        wildcard_exceptions: hashmap! {
+            1 => hashmap! {
+                1 => true
+            },
+        },
+        rest_list: hashmap! {}
+    })]
     fn difference_list_tests<I1, I2, R>(#[case] mut list1: I1, #[case] list2: I2, #[case] result: R)
     where
         I1: PartialEq<R> + Debug,
@@ -938,8 +488,15 @@ mod tests {
         I1: PartialEq<R> + Debug,
         I2: Debug,
         R: Debug,
-        for<'a> I1: IntersectionAssign<&'a I2>,
+        I1: Clone + Difference<I1, Output = I1>,
+        for<'a> I1: IntersectionAssign<&'a I2> + Difference<&'a I2, Output = I1>,
     {
+        // The identity based intersection is used as an oracle for the direct implementation.
+        assert_eq!(
+            intersection_using_double_difference(list1.clone(), &list2),
+            result
+        );
+
         list1.intersection_assign(&list2);
         assert_eq!(list1, result);
     }
@@ -983,12 +540,90 @@ mod tests {
         I1: PartialEq<R> + Debug,
         I2: Debug,
         R: Debug,
-        I1: DisjunctiveUnionAssign<I2>,
+        I1: DisjunctiveUnionAssign<I2>
+            + Clone
+            + Difference<I2, Output = I1>
+            + Union<I2, Output = I1>,
+        I2: Clone + Difference<I1, Output = I2>,
     {
+        // The identity based disjunctive union is used as an oracle for the direct implementation.
+        assert_eq!(
+            disjunctive_union_using_difference_and_union(list1.clone(), list2.clone()),
+            result
+        );
+
         list1.disjunctive_union_assign(list2);
         assert_eq!(list1, result);
     }
 
+    /// Every map of `bool` with keys in `0..2`, with exceptions and rest lists in all combinations.
+    fn all_small_lists() -> Vec<WildcardHashMap<i32, bool>> {
+        let key_sets: [&[i32]; 4] = [&[], &[0], &[1], &[0, 1]];
+        let mut lists = Vec::new();
+
+        for wildcard_value in [false, true] {
+            for exceptions in key_sets {
+                for rest in key_sets {
+                    lists.push(WildcardHashMap {
+                        wildcard_value: Box::new(wildcard_value),
+                        wildcard_exceptions: exceptions.iter().map(|key| (*key, true)).collect(),
+                        rest_list: rest.iter().map(|key| (*key, true)).collect(),
+                    });
+                }
+            }
+        }
+
+        lists
+    }
+
+    #[test]
+    fn direct_operations_match_identities() {
+        let lists = all_small_lists();
+
+        for list1 in &lists {
+            for list2 in &lists {
+                let mut intersection = list1.clone();
+                intersection.intersection_assign(list2);
+                let intersection_oracle =
+                    intersection_using_double_difference(list1.clone(), list2);
+                let mut disjunctive_union = list1.clone();
+                disjunctive_union.disjunctive_union_assign(list2);
+                let disjunctive_union_oracle =
+                    disjunctive_union_using_difference_and_union(list1.clone(), list2.clone());
+
+                for key in 0..3 {
+                    assert_eq!(
+                        intersection.value_of(&key),
+                        intersection_oracle.value_of(&key),
+                        "{list1:?} ∩ {list2:?} for key {key}"
+                    );
+                    assert_eq!(
+                        disjunctive_union.value_of(&key),
+                        disjunctive_union_oracle.value_of(&key),
+                        "{list1:?} ⊖ {list2:?} for key {key}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn extend_and_collect_test() {
+        let mut list: WildcardHashMap<i32, bool> = [(0, true), (1, false)].into_iter().collect();
+        assert_eq!(list, WildcardHashMap::from(hashmap! { 0 => true }));
+
+        list.extend([WildcardHashMap::new(true)]);
+        list.extend([(2, true)]);
+        assert_eq!(list, WildcardHashMap::new(true));
+
+        let lists = all_small_lists();
+        let mut union = WildcardHashMap::empty();
+        for list in &lists {
+            union.union_assign(list);
+        }
+        assert_eq!(lists.into_iter().collect::<WildcardHashMap<_, _>>(), union);
+    }
+
     #[rstest]
     // Subset tests
     #[case(WildcardHashMap::<i32, bool> {
@@ -1101,4 +736,54 @@ mod tests {
 
         assert_eq!(list, deserialized);
     }
+
+    #[cfg(feature = "codec")]
+    #[rstest]
+    #[case(WildcardHashMap::<u32, bool> {
+        wildcard_value: Box::new(false),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {},
+    }, &[crate::codec::FORMAT_VERSION, 0])]
+    #[case(WildcardHashMap {
+        wildcard_value: Box::new(true),
+        wildcard_exceptions: hashmap! {
+            1 => true,
+        },
+        rest_list: hashmap! {},
+    }, &[crate::codec::FORMAT_VERSION, 0b011, 1, 1, 1, 1])]
+    #[case(WildcardHashMap {
+        wildcard_value: Box::new(false),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {
+            300 => true,
+        },
+    }, &[crate::codec::FORMAT_VERSION, 0b100, 1, 0xac, 0x02, 1])]
+    fn codec_test(#[case] list: WildcardHashMap<u32, bool>, #[case] bytes: &[u8]) {
+        assert_eq!(crate::codec::to_bytes(&list), bytes);
+        assert_eq!(crate::codec::from_bytes(bytes), Ok(list));
+    }
+
+    #[cfg(feature = "codec")]
+    #[rstest]
+    #[case::unknown_section(&[crate::codec::FORMAT_VERSION, 0b1000], crate::codec::DecodeError::InvalidTag(0b1000))]
+    #[case::empty_wildcard(&[crate::codec::FORMAT_VERSION, 0b001, 0], crate::codec::DecodeError::EmptyValue)]
+    #[case::empty_rest_list(&[crate::codec::FORMAT_VERSION, 0b100, 0], crate::codec::DecodeError::EmptyValue)]
+    #[case::exceptions_without_wildcard(&[crate::codec::FORMAT_VERSION, 0b010, 1, 5, 1], crate::codec::DecodeError::NotCanonical)]
+    fn codec_invalid_test(#[case] bytes: &[u8], #[case] error: crate::codec::DecodeError) {
+        assert_eq!(
+            crate::codec::from_bytes::<WildcardHashMap<u32, bool>>(bytes),
+            Err(error)
+        );
+    }
+
+    #[cfg(feature = "codec")]
+    #[rstest]
+    #[case::exception_outside_wildcard(&[crate::codec::FORMAT_VERSION, 0b011, 1, 1, 1, 1, 5, 1, 2, 1])]
+    #[case::rest_list_overlapping_wildcard(&[crate::codec::FORMAT_VERSION, 0b101, 1, 1, 1, 1, 5, 1, 1, 1])]
+    fn codec_not_canonical_test(#[case] bytes: &[u8]) {
+        assert_eq!(
+            crate::codec::from_bytes::<WildcardHashMap<u32, HashMap<u32, bool>>>(bytes),
+            Err(crate::codec::DecodeError::NotCanonical)
+        );
+    }
 }
@@ -0,0 +1,790 @@
+use crate::Set;
+use crate::comparisons::{SetEq, SubsetOf};
+use crate::operations::{
+    Difference, DifferenceAssign, DisjunctiveUnionAssign, IntersectionAssign, Union, UnionAssign,
+};
+use ::im::HashMap as ImHashMap;
+use std::{hash::Hash, ops::Deref, sync::Arc};
+
+wildcard_map!(
+    /// A persistent variant of [`super::WildcardHashMap`] using [`im::HashMap`].
+    ///
+    /// The wildcard value is kept in an [`Arc`], so cloning is O(1) and clones share all of their parts.
+    /// The wildcard value is only replaced when an operation changes it, and intersections and disjunctive unions only write the entries whose value changes, so the unchanged parts stay shared with earlier versions of the map.
+    /// Unions and differences with other wildcard maps visit every entry mutably, which copies the internal maps if they are shared.
+    WildcardImHashMap,
+    map: ImHashMap,
+    key: Hash,
+    wildcard: Arc,
+    remove_empty_keys: crate::impls::im::remove_empty_keys,
+    serde: ("ImHashMap::empty", "ImHashMap::is_empty", "empty_wildcard_value", "is_empty_wildcard_value"),
+    Value: (+ Clone)
+);
+
+#[cfg(feature = "serde")]
+fn empty_wildcard_value<Value: Set<Empty = Value>>() -> Arc<Value> {
+    Arc::new(Value::empty())
+}
+
+#[cfg(feature = "serde")]
+fn is_empty_wildcard_value<Value: Set>(wildcard_value: &Arc<Value>) -> bool {
+    wildcard_value.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Debug;
+
+    use rstest::*;
+
+    #[allow(unused_imports)]
+    use super::*;
+    use crate::operations::identity::{
+        disjunctive_union_using_difference_and_union, intersection_using_double_difference,
+    };
+
+    use ::im::hashmap;
+
+    #[rstest]
+    // WildcardList A <-> List B
+    #[case(WildcardImHashMap {
+        wildcard_value: Arc::new(false),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {
+            0 => true,
+        },
+    }, hashmap! {
+        1 => true,
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(false),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {
+            0 => true,
+            1 => true,
+        }
+    })]
+    #[case(WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {},
+    }, hashmap! {
+        1 => true,
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {}
+    })]
+    #[case(WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {
+            1 => true
+        },
+        rest_list: hashmap! {},
+    }, hashmap! {
+        1 => true,
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {}
+    })]
+    #[case(WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {
+            1 => true,
+            2 => true,
+        },
+        rest_list: hashmap! {},
+    }, hashmap! {
+        1 => true,
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {
+            2 => true,
+        },
+        rest_list: hashmap! {}
+    })]
+    // WildcardList A <-> WildcardList B
+    #[case(WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {
+            2 => true,
+        },
+        rest_list: hashmap! {},
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {
+            1 => true,
+        },
+        rest_list: hashmap! {},
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {}
+    })]
+    #[case(WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {
+            1 => true,
+            2 => true,
+        },
+        rest_list: hashmap! {},
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {
+            1 => true,
+        },
+        rest_list: hashmap! {},
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {
+            1 => true,
+        },
+        rest_list: hashmap! {}
+    })]
+    #[case(WildcardImHashMap {
+        wildcard_value: Arc::new(hashmap! {
+            1 => true
+        }),
+        wildcard_exceptions: hashmap! {
+            2 => hashmap! {
+                1 => true
+            }
+        },
+        rest_list: hashmap! {},
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(hashmap! {
+            2 => true
+        }),
+        wildcard_exceptions: hashmap! {
+            1 => hashmap! {
+                2 => true
+            }
+        },
+        rest_list: hashmap! {
+            2 => hashmap! {
+                1 => true
+            }
+        },
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(hashmap! {
+            1 => true,
+            2 => true
+        }),
+        wildcard_exceptions: hashmap! {
+            1 => hashmap! {
+                2 => true
+            }
+        },
+        rest_list: hashmap! {}
+    })]
+    #[case(WildcardImHashMap::<i32, bool> {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {},
+    }, hashmap! {
+        1 => false,
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {}
+    })]
+    fn union_list_tests<I1, I2, R>(#[case] mut list1: I1, #[case] list2: I2, #[case] result: R)
+    where
+        I1: PartialEq<R> + Debug,
+        I2: Debug,
+        R: Debug,
+        for<'a> I1: UnionAssign<&'a I2>,
+    {
+        list1.union_assign(&list2);
+
+        assert_eq!(list1, result);
+    }
+
+    #[rstest]
+    #[case(WildcardImHashMap {
+        wildcard_value: Arc::new(false),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {
+            0 => true,
+        },
+    }, hashmap! {
+        1 => true,
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(false),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {
+            0 => true,
+        }
+    })]
+    #[case(WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {},
+    }, hashmap! {
+        1 => true,
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {
+            1 => true,
+        },
+        rest_list: hashmap! {}
+    })]
+    #[case(WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {
+            1 => true
+        },
+        rest_list: hashmap! {},
+    }, hashmap! {
+        1 => true,
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {
+            1 => true,
+        },
+        rest_list: hashmap! {}
+    })]
+    #[case(WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {
+            2 => true,
+        },
+        rest_list: hashmap! {
+        },
+    }, hashmap! {
+        1 => true,
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {
+            2 => true,
+            1 => true,
+        },
+        rest_list: hashmap! {}
+    })]
+    // WildcardList A <-> WildcardList B
+    #[case(WildcardImHashMap::<i32, bool> {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {},
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {},
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(false),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {}
+    })]
+    #[case(WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {},
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {
+            1 => true,
+        },
+        rest_list: hashmap! {},
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(false),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {
+            1 => true,
+        }
+    })]
+    #[case(WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {},
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(false),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {
+            1 => true,
+        },
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {
+            1 => true,
+        },
+        rest_list: hashmap! {}
+    })]
+    #[case(WildcardImHashMap {
+        wildcard_value: Arc::new(hashmap! {
+            1 => true,
+            2 => true
+        }),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {},
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(hashmap! {
+            1 => true
+        }),
+        wildcard_exceptions: hashmap! {
+            2 => hashmap! {
+                1 => true
+            }
+        },
+        rest_list: hashmap! {},
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(hashmap! {
+            2 => true
+        }),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {
+            2 => hashmap! {
+                1 => true
+            }
+        }
+    })]
+    #[case(WildcardImHashMap::<i32, bool> {
+        wildcard_value: Arc::new(false),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {
+            1 => true,
+        },
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {},
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(false),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {}
+    })]
+    fn difference_list_tests<I1, I2, R>(#[case] mut list1: I1, #[case] list2: I2, #[case] result: R)
+    where
+        I1: PartialEq<R> + Debug,
+        I2: Debug,
+        R: Debug,
+        for<'a> I1: DifferenceAssign<&'a I2>,
+    {
+        list1.difference_assign(&list2);
+
+        assert_eq!(list1, result);
+    }
+
+    #[test]
+    fn test_add() {
+        let tree_1 = hashmap! {
+            1 => WildcardImHashMap {
+                rest_list: hashmap! {},
+                wildcard_exceptions: hashmap! {},
+                wildcard_value: Arc::new(hashmap! {
+                    15 => true,
+                })
+            }
+        };
+
+        let tree_2 = hashmap! {
+            1 => hashmap! {
+                5 => hashmap! {
+                    15 => true,
+                    5 => true,
+                },
+            },
+        };
+
+        let mut tree_1_minus_2 = tree_1.clone();
+        tree_1_minus_2.difference_assign(&tree_2);
+
+        let result = hashmap! {
+          1 => WildcardImHashMap {
+            rest_list: hashmap! {},
+            wildcard_exceptions: hashmap! {
+                5 => hashmap! {
+                    15 => true,
+                },
+            },
+            wildcard_value: Arc::new(hashmap! {
+                15 => true,
+            }),
+          }
+        };
+
+        assert_eq!(tree_1_minus_2, result);
+
+        tree_1_minus_2.union_assign(&tree_2);
+        //Does not equal tree_1 because 1.5.5 has been added.
+        assert_ne!(tree_1, tree_1_minus_2);
+    }
+
+    #[rstest]
+    #[case(WildcardImHashMap::<i32, bool> {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {},
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {},
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {}
+    })]
+    #[case(WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {
+            1 => true,
+        },
+        rest_list: hashmap! {},
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {},
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {
+            1 => true,
+        },
+        rest_list: hashmap! {}
+    })]
+    #[case(WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {
+            1 => true,
+        },
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(false),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {
+            1 => true,
+        },
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(false),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {
+            1 => true,
+        }
+    })]
+    fn intersection_list_tests<I1, I2, R>(
+        #[case] mut list1: I1,
+        #[case] list2: I2,
+        #[case] result: R,
+    ) where
+        I1: PartialEq<R> + Debug,
+        I2: Debug,
+        R: Debug,
+        I1: Clone + Difference<I1, Output = I1>,
+        for<'a> I1: IntersectionAssign<&'a I2> + Difference<&'a I2, Output = I1>,
+    {
+        // The identity based intersection is used as an oracle for the direct implementation.
+        assert_eq!(
+            intersection_using_double_difference(list1.clone(), &list2),
+            result
+        );
+
+        list1.intersection_assign(&list2);
+        assert_eq!(list1, result);
+    }
+
+    #[rstest]
+    #[case(WildcardImHashMap::<i32, bool> {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {},
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {},
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(false),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {}
+    })]
+    #[case(WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {
+            1 => true,
+        },
+        rest_list: hashmap! {},
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(false),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {
+            1 => true,
+        },
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {}
+    })]
+    fn disjunctive_union_list_tests<I1, I2, R>(
+        #[case] mut list1: I1,
+        #[case] list2: I2,
+        #[case] result: R,
+    ) where
+        I1: PartialEq<R> + Debug,
+        I2: Debug,
+        R: Debug,
+        I1: DisjunctiveUnionAssign<I2>
+            + Clone
+            + Difference<I2, Output = I1>
+            + Union<I2, Output = I1>,
+        I2: Clone + Difference<I1, Output = I2>,
+    {
+        // The identity based disjunctive union is used as an oracle for the direct implementation.
+        assert_eq!(
+            disjunctive_union_using_difference_and_union(list1.clone(), list2.clone()),
+            result
+        );
+
+        list1.disjunctive_union_assign(list2);
+        assert_eq!(list1, result);
+    }
+
+    /// Every map of `bool` with keys in `0..2`, with exceptions and rest lists in all combinations.
+    fn all_small_lists() -> Vec<WildcardImHashMap<i32, bool>> {
+        let key_sets: [&[i32]; 4] = [&[], &[0], &[1], &[0, 1]];
+        let mut lists = Vec::new();
+
+        for wildcard_value in [false, true] {
+            for exceptions in key_sets {
+                for rest in key_sets {
+                    lists.push(WildcardImHashMap {
+                        wildcard_value: Arc::new(wildcard_value),
+                        wildcard_exceptions: exceptions.iter().map(|key| (*key, true)).collect(),
+                        rest_list: rest.iter().map(|key| (*key, true)).collect(),
+                    });
+                }
+            }
+        }
+
+        lists
+    }
+
+    #[test]
+    fn direct_operations_match_identities() {
+        let lists = all_small_lists();
+
+        for list1 in &lists {
+            for list2 in &lists {
+                let mut intersection = list1.clone();
+                intersection.intersection_assign(list2);
+                let intersection_oracle =
+                    intersection_using_double_difference(list1.clone(), list2);
+                let mut disjunctive_union = list1.clone();
+                disjunctive_union.disjunctive_union_assign(list2);
+                let disjunctive_union_oracle =
+                    disjunctive_union_using_difference_and_union(list1.clone(), list2.clone());
+
+                for key in 0..3 {
+                    assert_eq!(
+                        intersection.value_of(&key),
+                        intersection_oracle.value_of(&key),
+                        "{list1:?} ∩ {list2:?} for key {key}"
+                    );
+                    assert_eq!(
+                        disjunctive_union.value_of(&key),
+                        disjunctive_union_oracle.value_of(&key),
+                        "{list1:?} ⊖ {list2:?} for key {key}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[rstest]
+    // Subset tests
+    #[case(WildcardImHashMap::<i32, bool> {
+        wildcard_value: Arc::new(false),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {},
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(false),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {},
+    }, true)]
+    #[case(WildcardImHashMap::<i32, bool> {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {},
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {},
+    }, true)]
+    // subset is smaller wildcard
+    #[case(WildcardImHashMap::<i32, bool> {
+        wildcard_value: Arc::new(false),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {},
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {},
+    }, true)]
+    // subset has more exceptions
+    #[case(WildcardImHashMap::<i32, bool> {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {
+            1 => true,
+        },
+        rest_list: hashmap! {},
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {},
+    }, true)]
+    // superset has more exceptions -> false
+    #[case(WildcardImHashMap::<i32, bool> {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {},
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {
+            1 => true,
+        },
+        rest_list: hashmap! {},
+    }, false)]
+    // subset rest_list goes into wildcard
+    #[case(WildcardImHashMap::<i32, bool> {
+        wildcard_value: Arc::new(false),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {
+            1 => true,
+        },
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {},
+    }, true)]
+    // subset rest_list goes into exception -> false
+    #[case(WildcardImHashMap::<i32, bool> {
+        wildcard_value: Arc::new(false),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {
+            1 => true,
+        },
+    }, WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {
+            1 => true,
+        },
+        rest_list: hashmap! {},
+    }, false)]
+    fn subset_of_list_tests<I1, I2>(#[case] list1: I1, #[case] list2: I2, #[case] expected: bool)
+    where
+        I1: SubsetOf<I2> + Debug,
+        I2: Debug,
+    {
+        assert_eq!(
+            list1.subset_of(&list2),
+            expected,
+            "{:?} subset_of {:?}",
+            list1,
+            list2
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_test() {
+        let list = WildcardImHashMap {
+            wildcard_value: Arc::new(false),
+            wildcard_exceptions: hashmap! {},
+            rest_list: hashmap! {
+                2 => true
+            },
+        };
+
+        let serialized = serde_json::to_string(&list).unwrap();
+        assert_eq!(serialized, "{\"rest_list\":{\"2\":true}}");
+
+        let deserialized: WildcardImHashMap<i32, bool> = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(list, deserialized);
+    }
+
+    #[cfg(feature = "codec")]
+    #[rstest]
+    #[case(WildcardImHashMap::<u32, bool> {
+        wildcard_value: Arc::new(false),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {},
+    }, &[crate::codec::FORMAT_VERSION, 0])]
+    #[case(WildcardImHashMap {
+        wildcard_value: Arc::new(true),
+        wildcard_exceptions: hashmap! {
+            1 => true,
+        },
+        rest_list: hashmap! {},
+    }, &[crate::codec::FORMAT_VERSION, 0b011, 1, 1, 1, 1])]
+    #[case(WildcardImHashMap {
+        wildcard_value: Arc::new(false),
+        wildcard_exceptions: hashmap! {},
+        rest_list: hashmap! {
+            300 => true,
+        },
+    }, &[crate::codec::FORMAT_VERSION, 0b100, 1, 0xac, 0x02, 1])]
+    fn codec_test(#[case] list: WildcardImHashMap<u32, bool>, #[case] bytes: &[u8]) {
+        assert_eq!(crate::codec::to_bytes(&list), bytes);
+        assert_eq!(crate::codec::from_bytes(bytes), Ok(list));
+    }
+
+    #[cfg(feature = "codec")]
+    #[rstest]
+    #[case::unknown_section(&[crate::codec::FORMAT_VERSION, 0b1000], crate::codec::DecodeError::InvalidTag(0b1000))]
+    #[case::empty_wildcard(&[crate::codec::FORMAT_VERSION, 0b001, 0], crate::codec::DecodeError::EmptyValue)]
+    #[case::empty_rest_list(&[crate::codec::FORMAT_VERSION, 0b100, 0], crate::codec::DecodeError::EmptyValue)]
+    #[case::exceptions_without_wildcard(&[crate::codec::FORMAT_VERSION, 0b010, 1, 5, 1], crate::codec::DecodeError::NotCanonical)]
+    fn codec_invalid_test(#[case] bytes: &[u8], #[case] error: crate::codec::DecodeError) {
+        assert_eq!(
+            crate::codec::from_bytes::<WildcardImHashMap<u32, bool>>(bytes),
+            Err(error)
+        );
+    }
+
+    #[cfg(feature = "codec")]
+    #[rstest]
+    #[case::exception_outside_wildcard(&[crate::codec::FORMAT_VERSION, 0b011, 1, 1, 1, 1, 5, 1, 2, 1])]
+    #[case::rest_list_overlapping_wildcard(&[crate::codec::FORMAT_VERSION, 0b101, 1, 1, 1, 1, 5, 1, 1, 1])]
+    fn codec_not_canonical_test(#[case] bytes: &[u8]) {
+        assert_eq!(
+            crate::codec::from_bytes::<WildcardImHashMap<u32, ImHashMap<u32, bool>>>(bytes),
+            Err(crate::codec::DecodeError::NotCanonical)
+        );
+    }
+
+    #[test]
+    fn snapshot_test() {
+        let snapshot = WildcardImHashMap::new(hashmap! { 0 => true });
+
+        let mut list = snapshot.clone();
+        list.union_assign(&hashmap! { 1 => hashmap! { 1 => true } });
+        list.difference_assign(&hashmap! { 2 => hashmap! { 0 => true } });
+
+        assert_eq!(snapshot, WildcardImHashMap::new(hashmap! { 0 => true }));
+        assert_eq!(list.value_of(&1), hashmap! { 0 => true, 1 => true });
+        assert_eq!(list.value_of(&2), hashmap! {});
+    }
+
+    #[test]
+    fn sharing_test() {
+        let snapshot = WildcardImHashMap {
+            wildcard_exceptions: hashmap! {},
+            wildcard_value: Arc::new(false),
+            rest_list: hashmap! { 1 => true, 2 => true },
+        };
+
+        let mut list = snapshot.clone();
+        list.intersection_assign(&WildcardImHashMap::new(true));
+        list.disjunctive_union_assign(&WildcardImHashMap::new(false));
+
+        assert_eq!(list, snapshot);
+        assert!(Arc::ptr_eq(&list.wildcard_value, &snapshot.wildcard_value));
+        assert!(list.rest_list.ptr_eq(&snapshot.rest_list));
+
+        list.union_assign(&WildcardImHashMap::new(false));
+
+        assert!(Arc::ptr_eq(&list.wildcard_value, &snapshot.wildcard_value));
+
+        list.union_assign(&hashmap! { 3 => true });
+
+        assert_eq!(snapshot.rest_list, hashmap! { 1 => true, 2 => true });
+        assert_eq!(list.rest_list, hashmap! { 1 => true, 2 => true, 3 => true });
+    }
+}
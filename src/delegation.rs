@@ -0,0 +1,309 @@
+//! This module contains helpers for handing out permissions, where a granter may only hand out what they may grant themselves, such as [`delegate`] and [`Grantable`](struct@Grantable).
+//!
+//! ```
+//! # use std::collections::HashMap;
+//! # use finit::delegation::{Excess, Grantable};
+//! let admin = Grantable::new(
+//!     HashMap::from([("kick", true), ("ban", true)]),
+//!     HashMap::from([("kick", true)]),
+//! );
+//!
+//! assert!(admin.delegate(&HashMap::from([("kick", true)])).is_ok());
+//!
+//! // The admin can ban, but may not let others ban.
+//! assert_eq!(
+//!     admin.delegate(&HashMap::from([("kick", true), ("ban", true)])),
+//!     Err(Excess(HashMap::from([("ban", true)])))
+//! );
+//! ```
+use core::fmt::{Debug, Display};
+
+use crate::Set;
+use crate::comparisons::SubsetOf;
+use crate::operations::{
+    DifferenceAssign, DisjunctiveUnionAssign, IntersectionAssign, UnionAssign,
+};
+
+#[cfg(feature = "derive")]
+pub use finit_derive::Grantable;
+
+/// [`Excess`] is returned by [`delegate`] when more is requested than the granter may grant.
+/// It contains the difference between the requested set and the granter's set, ie. everything that was requested but may not be granted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Excess<T>(pub T);
+
+impl<T> Excess<T> {
+    /// Returns the set of values that may not be granted.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: Debug> Display for Excess<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "may not grant: {:?}", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Debug> std::error::Error for Excess<T> {}
+
+/// Returns the requested set if `granter` contains all of it, and the part that it does not contain otherwise.
+pub fn delegate<S>(granter: &S, requested: &S) -> Result<S, Excess<S>>
+where
+    S: SubsetOf + Clone,
+    for<'a> S: DifferenceAssign<&'a S>,
+{
+    if requested.subset_of(granter) {
+        return Ok(requested.clone());
+    }
+
+    let mut excess = requested.clone();
+    excess.difference_assign(granter);
+
+    Err(Excess(excess))
+}
+
+/// Two parallel sets, the permissions that are held and the permissions that may be granted to others.
+///
+/// Operations and comparisons are applied to both sets. Use the `Grantable` derive to get a struct where every field, including nested structs, has both sets instead.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Grantable<S> {
+    /// The permissions that are held.
+    pub has: S,
+    /// The permissions that may be granted to others.
+    pub may_grant: S,
+}
+
+impl<S> Grantable<S> {
+    pub fn new(has: S, may_grant: S) -> Self {
+        Self { has, may_grant }
+    }
+}
+
+impl<S: Set<Empty = S> + SubsetOf + Clone> Grantable<S>
+where
+    for<'a> S: DifferenceAssign<&'a S> + UnionAssign<&'a S>,
+{
+    /// Returns the requested set if it may be granted, see [`delegate`].
+    pub fn delegate(&self, requested: &S) -> Result<S, Excess<S>> {
+        delegate(&self.may_grant, requested)
+    }
+
+    /// Adds `requested` to `grantee`, if both what it would hold and what it would be allowed to grant may be granted.
+    pub fn grant(
+        &self,
+        grantee: &mut Grantable<S>,
+        requested: &Grantable<S>,
+    ) -> Result<(), Excess<Grantable<S>>> {
+        GrantableLayers::grant(self, grantee, requested)
+    }
+}
+
+impl<S: Set<Empty = S>> Set for Grantable<S> {
+    type Empty = Self;
+
+    fn is_empty(&self) -> bool {
+        self.has.is_empty() && self.may_grant.is_empty()
+    }
+
+    fn empty() -> Self {
+        Self::new(S::empty(), S::empty())
+    }
+}
+
+macro_rules! impl_grantable_operation {
+    ($trait:ident, $func:ident) => {
+        impl<S: Set<Empty = S>> $trait<&Grantable<S>> for Grantable<S>
+        where
+            for<'a> S: $trait<&'a S>,
+        {
+            fn $func(&mut self, rhs: &Grantable<S>) {
+                self.has.$func(&rhs.has);
+                self.may_grant.$func(&rhs.may_grant);
+            }
+        }
+    };
+}
+
+impl_grantable_operation!(UnionAssign, union_assign);
+impl_grantable_operation!(DifferenceAssign, difference_assign);
+impl_grantable_operation!(IntersectionAssign, intersection_assign);
+impl_grantable_operation!(DisjunctiveUnionAssign, disjunctive_union_assign);
+
+impl<S: SubsetOf> SubsetOf for Grantable<S> {
+    fn subset_of(&self, rhs: &Grantable<S>) -> bool {
+        self.has.subset_of(&rhs.has) && self.may_grant.subset_of(&rhs.may_grant)
+    }
+}
+
+/// [`GrantableLayers`] converts between a set with both layers and the separate sets that are held and that may be granted.
+///
+/// This is implemented by [`Grantable`](struct@Grantable) and by the structs generated by the `Grantable` derive, whose fields can be either.
+/// Handing out permissions only needs the layers to be sets, so [`GrantableLayers::delegate`] and [`GrantableLayers::grant`] also work on the generated structs.
+pub trait GrantableLayers: Sized {
+    /// The type of each layer.
+    type Layer;
+
+    fn from_layers(has: Self::Layer, may_grant: Self::Layer) -> Self;
+
+    /// The permissions that are held.
+    fn has(&self) -> Self::Layer;
+
+    /// The permissions that may be granted to others.
+    fn may_grant(&self) -> Self::Layer;
+
+    /// Calls `f` with the permissions that are held.
+    ///
+    /// [`Grantable`](struct@Grantable) lends its layer without copying it. By default `f` gets [`GrantableLayers::has`], since the structs generated by the derive keep the layers of every field apart and have to assemble them.
+    fn with_has<R>(&self, f: impl FnOnce(&Self::Layer) -> R) -> R {
+        f(&self.has())
+    }
+
+    /// Calls `f` with the permissions that may be granted to others, like [`GrantableLayers::with_has`].
+    fn with_may_grant<R>(&self, f: impl FnOnce(&Self::Layer) -> R) -> R {
+        f(&self.may_grant())
+    }
+
+    /// Returns the requested set if it may be granted, see [`delegate`].
+    fn delegate(&self, requested: &Self::Layer) -> Result<Self::Layer, Excess<Self::Layer>>
+    where
+        Self::Layer: SubsetOf + Clone,
+        for<'a> Self::Layer: DifferenceAssign<&'a Self::Layer>,
+    {
+        self.with_may_grant(|may_grant| delegate(may_grant, requested))
+    }
+
+    /// Adds `requested` to `grantee`, if both what it would hold and what it would be allowed to grant may be granted.
+    fn grant(&self, grantee: &mut Self, requested: &Self) -> Result<(), Excess<Self>>
+    where
+        Self::Layer: Set<Empty = Self::Layer> + SubsetOf + Clone,
+        for<'a> Self::Layer: DifferenceAssign<&'a Self::Layer> + UnionAssign<&'a Self::Layer>,
+    {
+        let (has, allowed) = self.with_may_grant(|may_grant| {
+            (
+                requested.with_has(|has| delegate(may_grant, has)),
+                requested.with_may_grant(|allowed| delegate(may_grant, allowed)),
+            )
+        });
+
+        if let (Ok(has), Ok(allowed)) = (&has, &allowed) {
+            let mut grantee_has = grantee.has();
+            grantee_has.union_assign(has);
+            let mut grantee_may_grant = grantee.may_grant();
+            grantee_may_grant.union_assign(allowed);
+
+            *grantee = Self::from_layers(grantee_has, grantee_may_grant);
+            return Ok(());
+        }
+
+        let excess = |result: Result<Self::Layer, Excess<Self::Layer>>| {
+            result
+                .err()
+                .map_or_else(Self::Layer::empty, Excess::into_inner)
+        };
+
+        Err(Excess(Self::from_layers(excess(has), excess(allowed))))
+    }
+}
+
+impl<S: Clone> GrantableLayers for Grantable<S> {
+    type Layer = S;
+
+    fn from_layers(has: S, may_grant: S) -> Self {
+        Self::new(has, may_grant)
+    }
+
+    fn has(&self) -> S {
+        self.has.clone()
+    }
+
+    fn may_grant(&self) -> S {
+        self.may_grant.clone()
+    }
+
+    fn with_has<R>(&self, f: impl FnOnce(&S) -> R) -> R {
+        f(&self.has)
+    }
+
+    fn with_may_grant<R>(&self, f: impl FnOnce(&S) -> R) -> R {
+        f(&self.may_grant)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::collections::HashMap;
+
+    use maplit::hashmap;
+    use rstest::*;
+
+    #[allow(unused_imports)]
+    use super::*;
+
+    type Perms = HashMap<&'static str, bool>;
+
+    #[rstest]
+    #[case(hashmap! { "kick" => true, "ban" => true }, hashmap! { "kick" => true }, Ok(hashmap! { "kick" => true }))]
+    #[case(hashmap! { "kick" => true }, hashmap! {}, Ok(hashmap! {}))]
+    #[case(hashmap! { "kick" => true }, hashmap! { "kick" => true, "ban" => true }, Err(Excess(hashmap! { "ban" => true })))]
+    #[case(hashmap! {}, hashmap! { "kick" => true }, Err(Excess(hashmap! { "kick" => true })))]
+    fn delegate_tests(
+        #[case] granter: Perms,
+        #[case] requested: Perms,
+        #[case] result: Result<Perms, Excess<Perms>>,
+    ) {
+        assert_eq!(delegate(&granter, &requested), result);
+    }
+
+    #[test]
+    fn with_layers_test() {
+        let admin = Grantable::new(hashmap! { "kick" => true }, hashmap! {});
+
+        assert!(admin.with_has(|has| core::ptr::eq(has, &admin.has)));
+        assert!(admin.with_may_grant(|may_grant| core::ptr::eq(may_grant, &admin.may_grant)));
+        assert_eq!(
+            GrantableLayers::delegate(&admin, &hashmap! { "kick" => true }),
+            Err(Excess(hashmap! { "kick" => true }))
+        );
+    }
+
+    #[test]
+    fn grant_test() {
+        let admin = Grantable::new(
+            hashmap! { "kick" => true, "ban" => true },
+            hashmap! { "kick" => true, "mute" => true },
+        );
+        let mut moderator = Grantable::empty();
+
+        assert_eq!(
+            admin.grant(
+                &mut moderator,
+                &Grantable::new(hashmap! { "kick" => true }, hashmap! { "ban" => true })
+            ),
+            Err(Excess(Grantable::new(
+                hashmap! {},
+                hashmap! { "ban" => true }
+            )))
+        );
+        assert!(moderator.is_empty());
+
+        admin
+            .grant(
+                &mut moderator,
+                &Grantable::new(
+                    hashmap! { "kick" => true, "mute" => true },
+                    hashmap! { "mute" => true },
+                ),
+            )
+            .unwrap();
+        assert_eq!(
+            moderator,
+            Grantable::new(
+                hashmap! { "kick" => true, "mute" => true },
+                hashmap! { "mute" => true }
+            )
+        );
+    }
+}
@@ -0,0 +1,175 @@
+//! This module contains [`Delta`], the change between two versions of a set, which can be sent and applied instead of the whole new version.
+//!
+//! Since `(A - (A - B)) ∪ (B - A)` is `B` for every pair of sets, a delta computed with [`Delta::between`] always turns the old version into the new one, and its [inverse](Delta::invert) turns the new version back into the old one.
+//!
+//! ```
+//! # use std::collections::HashMap;
+//! # use finit::delta::Delta;
+//! let old = HashMap::from([("kick", true), ("ban", true)]);
+//! let new = HashMap::from([("kick", true), ("mute", true)]);
+//!
+//! let delta = Delta::between(&old, &new);
+//! assert_eq!(delta.added, HashMap::from([("mute", true)]));
+//! assert_eq!(delta.removed, HashMap::from([("ban", true)]));
+//!
+//! let mut synced = old.clone();
+//! delta.apply(&mut synced);
+//! assert_eq!(synced, new);
+//! ```
+use crate::Set;
+use crate::operations::{DifferenceAssign, UnionAssign};
+
+/// The change from one version of a set to another, as what was added and what was removed.
+///
+/// Deltas built with [`Delta::between`] and [`Delta::compose`] never add and remove the same part of a set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Delta<S> {
+    /// `new - old`
+    pub added: S,
+    /// `old - new`
+    pub removed: S,
+}
+
+impl<S> Delta<S> {
+    pub fn new(added: S, removed: S) -> Self {
+        Self { added, removed }
+    }
+
+    /// The delta that undoes this delta, by removing what it added and adding what it removed.
+    pub fn invert(self) -> Self {
+        Self {
+            added: self.removed,
+            removed: self.added,
+        }
+    }
+}
+
+impl<S: Clone> Delta<S>
+where
+    for<'a> S: DifferenceAssign<&'a S> + UnionAssign<&'a S>,
+{
+    /// The delta that turns `old` into `new`.
+    pub fn between(old: &S, new: &S) -> Self {
+        let mut added = new.clone();
+        added.difference_assign(old);
+
+        let mut removed = old.clone();
+        removed.difference_assign(new);
+
+        Self { added, removed }
+    }
+
+    /// Removes what was removed from `set` and then adds what was added.
+    pub fn apply(&self, set: &mut S) {
+        set.difference_assign(&self.removed);
+        set.union_assign(&self.added);
+    }
+
+    /// The delta that has the same effect as applying this delta and then `next`.
+    pub fn compose(self, next: &Delta<S>) -> Self {
+        let Self {
+            mut added,
+            mut removed,
+        } = self;
+
+        added.difference_assign(&next.removed);
+        added.union_assign(&next.added);
+
+        removed.difference_assign(&next.added);
+        removed.union_assign(&next.removed);
+
+        Self { added, removed }
+    }
+}
+
+impl<S: Set<Empty = S>> Set for Delta<S> {
+    type Empty = Self;
+
+    fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+
+    fn empty() -> Self {
+        Self {
+            added: S::empty(),
+            removed: S::empty(),
+        }
+    }
+}
+
+impl<S: Set<Empty = S>> Default for Delta<S> {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use maplit::hashmap;
+    use rstest::*;
+
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[rstest]
+    #[case::unchanged(hashmap! { 1 => true }, hashmap! { 1 => true })]
+    #[case::added(hashmap! { 1 => true }, hashmap! { 1 => true, 2 => true })]
+    #[case::removed(hashmap! { 1 => true, 2 => true }, hashmap! { 2 => true })]
+    #[case::replaced(hashmap! { 1 => true }, hashmap! { 2 => true })]
+    #[case::from_empty(hashmap! {}, hashmap! { 1 => true, 2 => true })]
+    fn round_trip_tests(#[case] old: HashMap<i32, bool>, #[case] new: HashMap<i32, bool>) {
+        let delta = Delta::between(&old, &new);
+        assert_eq!(delta.is_empty(), old == new);
+
+        let mut set = old.clone();
+        delta.apply(&mut set);
+        assert_eq!(set, new);
+
+        delta.invert().apply(&mut set);
+        assert_eq!(set, old);
+    }
+
+    #[rstest]
+    #[case(hashmap! { 1 => true }, hashmap! { 2 => true }, hashmap! { 1 => true, 3 => true })]
+    #[case(hashmap! { 1 => true, 2 => true }, hashmap! {}, hashmap! { 2 => true })]
+    #[case(hashmap! {}, hashmap! { 1 => true }, hashmap! {})]
+    fn compose_tests(
+        #[case] first: HashMap<i32, bool>,
+        #[case] second: HashMap<i32, bool>,
+        #[case] third: HashMap<i32, bool>,
+    ) {
+        let delta = Delta::between(&first, &second).compose(&Delta::between(&second, &third));
+
+        let mut set = first.clone();
+        delta.apply(&mut set);
+        assert_eq!(set, third);
+    }
+
+    #[test]
+    fn new_test() {
+        assert_eq!(
+            Delta::new(hashmap! { 1 => true }, hashmap! {}).invert(),
+            Delta {
+                added: hashmap! {},
+                removed: hashmap! { 1 => true },
+            }
+        );
+        assert!(Delta::<HashMap<i32, bool>>::empty().is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_test() {
+        let delta = Delta::between(&hashmap! { 1 => true }, &hashmap! { 2 => true });
+        let json = serde_json::to_string(&delta).unwrap();
+
+        assert_eq!(json, r#"{"added":{"2":true},"removed":{"1":true}}"#);
+        assert_eq!(
+            serde_json::from_str::<Delta<HashMap<i32, bool>>>(&json).unwrap(),
+            delta
+        );
+    }
+}
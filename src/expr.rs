@@ -0,0 +1,384 @@
+//! This module contains [`Expr`], a lazily evaluated expression of set operations.
+//!
+//! Composing operations eagerly, like `((a ∪ b) - denies) ∩ scope`, materializes every intermediate set. An [`Expr`] only records the operations, so it can be [simplified](Expr::simplify) first, [queried for a single key](Expr::value_of) without evaluating the whole expression, and [evaluated](Expr::eval) when the full set is needed.
+//!
+//! ```
+//! # use std::collections::HashMap;
+//! # use finit::expr::Expr;
+//! let role_a = HashMap::from([("kick", true)]);
+//! let role_b = HashMap::from([("ban", true), ("mute", true)]);
+//! let denies = HashMap::from([("ban", true)]);
+//!
+//! let expr = Expr::leaf(role_a).union(role_b).difference(denies);
+//!
+//! assert!(expr.contains(&"mute"));
+//! assert!(!expr.contains(&"ban"));
+//! assert_eq!(expr.eval(), HashMap::from([("kick", true), ("mute", true)]));
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+#[cfg(feature = "im")]
+use ::im::{HashMap as ImHashMap, OrdMap};
+
+use crate::Set;
+#[cfg(feature = "im")]
+use crate::collections::WildcardImHashMap;
+use crate::collections::{WildcardBTreeMap, WildcardHashMap};
+use crate::operations::{
+    DifferenceAssign, DisjunctiveUnionAssign, IntersectionAssign, UnionAssign,
+};
+
+/// A lazily evaluated expression of set operations over sets of type `S`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr<S> {
+    /// A ∪ B
+    Union(Box<Expr<S>>, Box<Expr<S>>),
+    /// A ∩ B
+    Intersection(Box<Expr<S>>, Box<Expr<S>>),
+    /// A - B
+    Difference(Box<Expr<S>>, Box<Expr<S>>),
+    /// A ⊖ B
+    DisjunctiveUnion(Box<Expr<S>>, Box<Expr<S>>),
+    /// A set.
+    Leaf(S),
+}
+
+/// [`ValueOf`] looks up the value of a single key in a keyed set, where missing keys have the empty value.
+///
+/// Since operations on keyed sets are performed per key, the value of a key in an [`Expr`] only depends on the values of that key in its leaves.
+pub trait ValueOf<Key> {
+    type Value;
+
+    fn value_of(&self, key: &Key) -> Self::Value;
+}
+
+impl<S> From<S> for Expr<S> {
+    fn from(set: S) -> Self {
+        Expr::Leaf(set)
+    }
+}
+
+impl<S> Expr<S> {
+    pub fn leaf(set: S) -> Self {
+        Expr::Leaf(set)
+    }
+
+    pub fn union(self, rhs: impl Into<Expr<S>>) -> Self {
+        Expr::Union(Box::new(self), Box::new(rhs.into()))
+    }
+
+    pub fn intersection(self, rhs: impl Into<Expr<S>>) -> Self {
+        Expr::Intersection(Box::new(self), Box::new(rhs.into()))
+    }
+
+    pub fn difference(self, rhs: impl Into<Expr<S>>) -> Self {
+        Expr::Difference(Box::new(self), Box::new(rhs.into()))
+    }
+
+    pub fn disjunctive_union(self, rhs: impl Into<Expr<S>>) -> Self {
+        Expr::DisjunctiveUnion(Box::new(self), Box::new(rhs.into()))
+    }
+}
+
+impl<S: Set<Empty = S>> Expr<S>
+where
+    for<'a> S: UnionAssign<&'a S>
+        + IntersectionAssign<&'a S>
+        + DifferenceAssign<&'a S>
+        + DisjunctiveUnionAssign<&'a S>,
+{
+    /// Evaluates the expression. The right hand side of an intersection or difference is not evaluated if the left hand side is empty.
+    pub fn eval(self) -> S {
+        match self {
+            Expr::Leaf(set) => set,
+            Expr::Union(lhs, rhs) => {
+                let mut set = lhs.eval();
+                set.union_assign(&rhs.eval());
+                set
+            }
+            Expr::Intersection(lhs, rhs) => {
+                let mut set = lhs.eval();
+                if !set.is_empty() {
+                    set.intersection_assign(&rhs.eval());
+                }
+                set
+            }
+            Expr::Difference(lhs, rhs) => {
+                let mut set = lhs.eval();
+                if !set.is_empty() {
+                    set.difference_assign(&rhs.eval());
+                }
+                set
+            }
+            Expr::DisjunctiveUnion(lhs, rhs) => {
+                let mut set = lhs.eval();
+                set.disjunctive_union_assign(&rhs.eval());
+                set
+            }
+        }
+    }
+}
+
+impl<S> Expr<S> {
+    /// The value of `key` in the evaluated expression, which is computed by only looking up `key` in the leaves.
+    pub fn value_of<Key, Value>(&self, key: &Key) -> Value
+    where
+        S: ValueOf<Key, Value = Value>,
+        Value: Set<Empty = Value>,
+        for<'a> Value: UnionAssign<&'a Value>
+            + IntersectionAssign<&'a Value>
+            + DifferenceAssign<&'a Value>
+            + DisjunctiveUnionAssign<&'a Value>,
+    {
+        match self {
+            Expr::Leaf(set) => set.value_of(key),
+            Expr::Union(lhs, rhs) => {
+                let mut value = lhs.value_of(key);
+                value.union_assign(&rhs.value_of(key));
+                value
+            }
+            Expr::Intersection(lhs, rhs) => {
+                let mut value = lhs.value_of(key);
+                if !value.is_empty() {
+                    value.intersection_assign(&rhs.value_of(key));
+                }
+                value
+            }
+            Expr::Difference(lhs, rhs) => {
+                let mut value = lhs.value_of(key);
+                if !value.is_empty() {
+                    value.difference_assign(&rhs.value_of(key));
+                }
+                value
+            }
+            Expr::DisjunctiveUnion(lhs, rhs) => {
+                let mut value = lhs.value_of(key);
+                value.disjunctive_union_assign(&rhs.value_of(key));
+                value
+            }
+        }
+    }
+
+    /// Returns true if `key` has a non-empty value in the evaluated expression.
+    pub fn contains<Key, Value>(&self, key: &Key) -> bool
+    where
+        S: ValueOf<Key, Value = Value>,
+        Value: Set<Empty = Value>,
+        for<'a> Value: UnionAssign<&'a Value>
+            + IntersectionAssign<&'a Value>
+            + DifferenceAssign<&'a Value>
+            + DisjunctiveUnionAssign<&'a Value>,
+    {
+        !self.value_of(key).is_empty()
+    }
+}
+
+impl<S: Set<Empty = S> + PartialEq> Expr<S> {
+    fn is_empty_leaf(&self) -> bool {
+        matches!(self, Expr::Leaf(set) if set.is_empty())
+    }
+
+    fn empty_leaf() -> Self {
+        Expr::Leaf(S::empty())
+    }
+
+    /// Simplifies the expression without evaluating any operations.
+    ///
+    /// Operations with an empty set or two equal operands are removed, and the identities in [`crate::operations::identity`] are applied in reverse, which replaces them with a single operation:
+    /// - A - (A - B) = A ∩ B
+    /// - A - (A ∩ B) = A - B
+    /// - (A − B) ∪ (B − A) = A ⊖ B
+    /// - (A ∪ B) - (A ∩ B) = A ⊖ B
+    pub fn simplify(self) -> Self {
+        match self {
+            Expr::Leaf(set) => Expr::Leaf(set),
+            Expr::Union(lhs, rhs) => {
+                let (lhs, rhs) = (lhs.simplify(), rhs.simplify());
+
+                match (lhs, rhs) {
+                    (lhs, rhs) if lhs.is_empty_leaf() => rhs,
+                    (lhs, rhs) if rhs.is_empty_leaf() || lhs == rhs => lhs,
+                    (Expr::Difference(a, b), Expr::Difference(c, d)) if a == d && b == c => {
+                        Expr::DisjunctiveUnion(a, b)
+                    }
+                    (lhs, rhs) => lhs.union(rhs),
+                }
+            }
+            Expr::Intersection(lhs, rhs) => {
+                let (lhs, rhs) = (lhs.simplify(), rhs.simplify());
+
+                match (lhs, rhs) {
+                    (lhs, rhs) if lhs.is_empty_leaf() || rhs.is_empty_leaf() => Self::empty_leaf(),
+                    (lhs, rhs) if lhs == rhs => lhs,
+                    (lhs, rhs) => lhs.intersection(rhs),
+                }
+            }
+            Expr::Difference(lhs, rhs) => {
+                let (lhs, rhs) = (lhs.simplify(), rhs.simplify());
+
+                match (lhs, rhs) {
+                    (lhs, rhs) if lhs.is_empty_leaf() || lhs == rhs => Self::empty_leaf(),
+                    (lhs, rhs) if rhs.is_empty_leaf() => lhs,
+                    (lhs, Expr::Difference(a, b)) if lhs == *a => Expr::Intersection(a, b),
+                    (lhs, Expr::Intersection(a, b)) if lhs == *a => Expr::Difference(a, b),
+                    (Expr::Union(a, b), Expr::Intersection(c, d)) if a == c && b == d => {
+                        Expr::DisjunctiveUnion(a, b)
+                    }
+                    (lhs, rhs) => lhs.difference(rhs),
+                }
+            }
+            Expr::DisjunctiveUnion(lhs, rhs) => {
+                let (lhs, rhs) = (lhs.simplify(), rhs.simplify());
+
+                match (lhs, rhs) {
+                    (lhs, rhs) if lhs == rhs => Self::empty_leaf(),
+                    (lhs, rhs) if lhs.is_empty_leaf() => rhs,
+                    (lhs, rhs) if rhs.is_empty_leaf() => lhs,
+                    (lhs, rhs) => lhs.disjunctive_union(rhs),
+                }
+            }
+        }
+    }
+}
+
+macro_rules! impl_map_value_of {
+    ($map:ident, Key: $($bounds:tt)*) => {
+        impl<Key: $($bounds)*, Value: Set<Empty = Value> + Clone> ValueOf<Key> for $map<Key, Value> {
+            type Value = Value;
+
+            fn value_of(&self, key: &Key) -> Value {
+                self.get(key).cloned().unwrap_or_else(Value::empty)
+            }
+        }
+    };
+}
+
+impl_map_value_of!(HashMap, Key: Hash + Eq);
+impl_map_value_of!(BTreeMap, Key: Ord);
+#[cfg(feature = "im")]
+impl_map_value_of!(ImHashMap, Key: Hash + Eq + Clone);
+#[cfg(feature = "im")]
+impl_map_value_of!(OrdMap, Key: Ord + Clone);
+
+macro_rules! impl_wildcard_value_of {
+    ($map:ident, Key: $($bounds:tt)*) => {
+        impl<Key: $($bounds)*, Value: Set<Empty = Value> + Clone> ValueOf<Key> for $map<Key, Value>
+        where
+            for<'a> Value: DifferenceAssign<&'a Value> + UnionAssign<&'a Value>,
+        {
+            type Value = Value;
+
+            fn value_of(&self, key: &Key) -> Value {
+                $map::value_of(self, key)
+            }
+        }
+    };
+}
+
+impl_wildcard_value_of!(WildcardHashMap, Key: Hash + Eq + Clone);
+impl_wildcard_value_of!(WildcardBTreeMap, Key: Ord + Clone);
+#[cfg(feature = "im")]
+impl_wildcard_value_of!(WildcardImHashMap, Key: Hash + Eq + Clone);
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    #[allow(unused_imports)]
+    use super::*;
+
+    use maplit::hashmap;
+
+    fn expressions() -> Vec<Expr<HashMap<u8, bool>>> {
+        let a = hashmap! { 0 => true, 1 => true };
+        let b = hashmap! { 1 => true, 2 => true };
+        let c = hashmap! { 2 => true, 3 => true };
+
+        vec![
+            Expr::leaf(a.clone()).union(b.clone()).difference(c.clone()),
+            Expr::leaf(a.clone())
+                .union(b.clone())
+                .difference(Expr::leaf(a.clone()).intersection(b.clone())),
+            Expr::leaf(a.clone()).difference(Expr::leaf(a.clone()).difference(b.clone())),
+            Expr::leaf(a.clone()).difference(Expr::leaf(a.clone()).intersection(c.clone())),
+            Expr::leaf(a.clone())
+                .difference(b.clone())
+                .union(Expr::leaf(b.clone()).difference(a.clone())),
+            Expr::leaf(a.clone())
+                .disjunctive_union(c.clone())
+                .intersection(Expr::leaf(b.clone()).union(HashMap::new())),
+            Expr::leaf(HashMap::new())
+                .intersection(a.clone())
+                .disjunctive_union(Expr::leaf(b.clone()).difference(b.clone())),
+        ]
+    }
+
+    #[rstest]
+    #[case(Expr::leaf(hashmap! { 0 => true }).union(HashMap::new()), Expr::leaf(hashmap! { 0 => true }))]
+    #[case(Expr::leaf(hashmap! { 0 => true }).intersection(HashMap::new()), Expr::leaf(HashMap::new()))]
+    #[case(Expr::leaf(hashmap! { 0 => true }).difference(hashmap! { 0 => true }), Expr::leaf(HashMap::new()))]
+    #[case(Expr::leaf(hashmap! { 0 => true }).disjunctive_union(hashmap! { 0 => true }), Expr::leaf(HashMap::new()))]
+    #[case(
+        Expr::leaf(hashmap! { 0 => true }).difference(Expr::leaf(hashmap! { 0 => true }).difference(hashmap! { 1 => true })),
+        Expr::leaf(hashmap! { 0 => true }).intersection(hashmap! { 1 => true }),
+    )]
+    #[case(
+        Expr::leaf(hashmap! { 0 => true }).difference(Expr::leaf(hashmap! { 0 => true }).intersection(hashmap! { 1 => true })),
+        Expr::leaf(hashmap! { 0 => true }).difference(hashmap! { 1 => true }),
+    )]
+    #[case(
+        Expr::leaf(hashmap! { 0 => true }).difference(hashmap! { 1 => true }).union(Expr::leaf(hashmap! { 1 => true }).difference(hashmap! { 0 => true })),
+        Expr::leaf(hashmap! { 0 => true }).disjunctive_union(hashmap! { 1 => true }),
+    )]
+    #[case(
+        Expr::leaf(hashmap! { 0 => true }).union(hashmap! { 1 => true }).difference(Expr::leaf(hashmap! { 0 => true }).intersection(hashmap! { 1 => true })),
+        Expr::leaf(hashmap! { 0 => true }).disjunctive_union(hashmap! { 1 => true }),
+    )]
+    fn simplify_tests(
+        #[case] expr: Expr<HashMap<u8, bool>>,
+        #[case] result: Expr<HashMap<u8, bool>>,
+    ) {
+        assert_eq!(expr.simplify(), result);
+    }
+
+    #[test]
+    fn simplify_preserves_value() {
+        for expr in expressions() {
+            assert_eq!(expr.clone().simplify().eval(), expr.eval());
+        }
+    }
+
+    #[test]
+    fn value_of_matches_eval() {
+        for expr in expressions() {
+            let result = expr.clone().eval();
+
+            for key in 0..5 {
+                assert_eq!(
+                    expr.value_of(&key),
+                    result.value_of(&key),
+                    "key {key} of {expr:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn wildcard_value_of_matches_eval() {
+        let all = WildcardHashMap::new(true);
+        let denies = WildcardHashMap::from(hashmap! { 0 => true });
+        let scope = WildcardHashMap::from(hashmap! { 0 => true, 1 => true });
+
+        let expr = Expr::leaf(all).difference(denies).intersection(scope);
+        let result = expr.clone().eval();
+
+        for key in 0..3 {
+            assert_eq!(expr.value_of(&key), result.value_of(&key));
+        }
+        assert!(expr.contains(&1));
+        assert!(!expr.contains(&0));
+        assert!(!expr.contains(&2));
+    }
+}
@@ -23,7 +23,7 @@ where
     Value: UnionAssign<OtherValue>,
 {
     fn union_assign(&mut self, rhs: [OtherValue; N]) {
-        for (self_i, rhs_i) in self.iter_mut().zip(rhs.into_iter()) {
+        for (self_i, rhs_i) in self.iter_mut().zip(rhs) {
             self_i.union_assign(rhs_i);
         }
     }
@@ -49,7 +49,7 @@ where
     Value: DifferenceAssign<OtherValue>,
 {
     fn difference_assign(&mut self, rhs: [OtherValue; N]) {
-        for (self_i, rhs_i) in self.iter_mut().zip(rhs.into_iter()) {
+        for (self_i, rhs_i) in self.iter_mut().zip(rhs) {
             self_i.difference_assign(rhs_i);
         }
     }
@@ -75,7 +75,7 @@ where
     Value: IntersectionAssign<OtherValue>,
 {
     fn intersection_assign(&mut self, rhs: [OtherValue; N]) {
-        for (self_i, rhs_i) in self.iter_mut().zip(rhs.into_iter()) {
+        for (self_i, rhs_i) in self.iter_mut().zip(rhs) {
             self_i.intersection_assign(rhs_i);
         }
     }
@@ -100,7 +100,7 @@ where
     Value: DisjunctiveUnionAssign<OtherValue>,
 {
     fn disjunctive_union_assign(&mut self, rhs: [OtherValue; N]) {
-        for (self_i, rhs_i) in self.iter_mut().zip(rhs.into_iter()) {
+        for (self_i, rhs_i) in self.iter_mut().zip(rhs) {
             self_i.disjunctive_union_assign(rhs_i);
         }
     }
@@ -1,3 +1,5 @@
+use alloc::boxed::Box;
+
 use crate::Set;
 use crate::operations::{
     Difference, DifferenceAssign, DisjunctiveUnion, DisjunctiveUnionAssign, Intersection,
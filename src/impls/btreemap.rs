@@ -1,5 +1,4 @@
-use std::collections::{BTreeMap, HashMap};
-use std::hash::Hash;
+use alloc::collections::BTreeMap;
 
 use crate::{Set, impl_map_owned_operations, impl_map_ref_operations, impl_map_comparisons};
 
@@ -7,11 +6,18 @@ use crate::impl_map;
 
 impl_map!(BTreeMap, Key: Ord + Eq);
 impl_map_ref_operations!(BTreeMap, BTreeMap, Key: Ord + Eq + Clone);
-impl_map_ref_operations!(BTreeMap, HashMap, Key: Hash + Ord + Eq + Clone);
 impl_map_owned_operations!(BTreeMap, BTreeMap, Key: Ord + Eq);
-impl_map_owned_operations!(BTreeMap, HashMap, Key: Hash + Ord + Eq);
 impl_map_comparisons!(BTreeMap, BTreeMap, Key: Ord + Eq);
-impl_map_comparisons!(BTreeMap, HashMap, Key: Hash + Ord + Eq);
+
+#[cfg(feature = "std")]
+mod std_impl {
+    use super::*;
+    use std::collections::HashMap;
+    use std::hash::Hash;
+    impl_map_ref_operations!(BTreeMap, HashMap, Key: Hash + Ord + Eq + Clone);
+    impl_map_owned_operations!(BTreeMap, HashMap, Key: Hash + Ord + Eq);
+    impl_map_comparisons!(BTreeMap, HashMap, Key: Hash + Ord + Eq);
+}
 
 #[cfg(feature = "phf")]
 mod phf_impl {
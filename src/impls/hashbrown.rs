@@ -0,0 +1,36 @@
+use ::hashbrown::HashMap;
+use core::hash::Hash;
+
+use crate::{Set, impl_map_comparisons, impl_map_owned_operations, impl_map_ref_operations};
+
+use crate::impl_map;
+
+impl_map!(HashMap, Key: Hash + Eq);
+impl_map_ref_operations!(HashMap, HashMap, Key: Hash + Eq + Clone);
+impl_map_owned_operations!(HashMap, HashMap, Key: Hash + Eq);
+impl_map_comparisons!(HashMap, HashMap, Key: Hash + Eq);
+
+#[cfg(test)]
+mod tests {
+    use crate::comparisons::SubsetOf;
+    use crate::operations::{DifferenceAssign, IntersectionAssign, UnionAssign};
+
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn operations_test() {
+        let mut map = HashMap::from([(0, true), (1, true)]);
+
+        map.union_assign(&HashMap::from([(2, true)]));
+        assert_eq!(map, HashMap::from([(0, true), (1, true), (2, true)]));
+
+        map.difference_assign(&HashMap::from([(0, true)]));
+        assert_eq!(map, HashMap::from([(1, true), (2, true)]));
+
+        map.intersection_assign(HashMap::from([(2, true), (3, true)]));
+        assert_eq!(map, HashMap::from([(2, true)]));
+
+        assert!(map.subset_of(&HashMap::from([(2, true), (3, true)])));
+    }
+}
@@ -28,7 +28,7 @@ mod phf_impl {
 mod tests {
     use core::fmt::Debug;
 
-    use crate::operations::{DifferenceAssign, UnionAssign};
+    use crate::operations::{DifferenceAssign, IntersectionAssign, UnionAssign};
     use maplit::hashmap;
     use rstest::*;
 
@@ -125,4 +125,38 @@ mod tests {
 
         assert_eq!(list1, result);
     }
+
+    #[rstest]
+    #[case(hashmap! {
+        0 => true,
+        1 => true,
+    }, hashmap! {
+        1 => true,
+    }, hashmap! {
+        1 => true,
+    })]
+    #[case(hashmap! {
+        0 => true,
+        1 => true,
+    }, hashmap! {
+        1 => false,
+    }, hashmap! {})]
+    #[case(hashmap! {
+        0 => hashmap! { 0 => true },
+    }, hashmap! {
+        0 => hashmap! { 1 => true },
+    }, hashmap! {})]
+    fn intersection_list_tests<K, V>(
+        #[case] mut list1: HashMap<K, V>,
+        #[case] list2: HashMap<K, V>,
+        #[case] result: HashMap<K, V>,
+    ) where
+        K: Hash + Eq + Clone + Debug,
+        V: Set + PartialEq + Clone + Debug,
+        for<'a> V: IntersectionAssign<&'a V>,
+    {
+        list1.intersection_assign(&list2);
+
+        assert_eq!(list1, result);
+    }
 }
@@ -0,0 +1,277 @@
+//! Implementations for the fixed-capacity maps of [`heapless`], which need no allocator.
+//!
+//! Operations that can add keys are only implemented as fallible operations, which return [`CapacityError`] without changing the map if the result does not fit.
+//!
+//! The values are combined with their infallible operations, so they should be sets like `bool`, arrays or tuples. Maps whose values are heapless maps themselves only support [`crate::operations::DifferenceAssign`], [`crate::operations::IntersectionAssign`] and the comparisons: a nested union could run out of capacity after earlier keys were already changed, which would break the guarantee of leaving the map unchanged on failure.
+
+use ::heapless::LinearMap;
+use ::heapless::index_map::FnvIndexMap;
+use core::hash::Hash;
+
+use crate::Set;
+use crate::collections::CapacityError;
+
+macro_rules! impl_heapless_map {
+    ($map:ident, Key: $($bounds:tt)*) => {
+        impl<Key: $($bounds)*, Value, const N: usize> Set for $map<Key, Value, N> {
+            type Empty = Self;
+
+            fn is_empty(&self) -> bool {
+                $map::is_empty(self)
+            }
+
+            fn empty() -> Self::Empty {
+                $map::new()
+            }
+        }
+
+        impl<Key, Value, OtherValue, const N: usize, const M: usize>
+            $crate::operations::TryUnionAssign<&$map<Key, OtherValue, M>> for $map<Key, Value, N>
+        where
+            Key: $($bounds)* + Clone,
+            for<'a> Value: $crate::operations::UnionAssign<&'a OtherValue>,
+            OtherValue: Set + Clone + Into<Value>,
+        {
+            type Error = CapacityError;
+
+            fn try_union_assign(&mut self, other: &$map<Key, OtherValue, M>) -> Result<(), CapacityError> {
+                let new_keys = other
+                    .iter()
+                    .filter(|(key, value)| !value.is_empty() && !self.contains_key(key))
+                    .count();
+
+                if self.len() + new_keys > self.capacity() {
+                    return Err(CapacityError);
+                }
+
+                for (key, other_value) in other.iter() {
+                    if let Some(value) = self.get_mut(key) {
+                        value.union_assign(other_value);
+                    } else if !other_value.is_empty() {
+                        self.insert(key.clone(), other_value.clone().into())
+                            .map_err(|_| CapacityError)?;
+                    }
+                }
+
+                Ok(())
+            }
+        }
+
+        impl<Key, Value, OtherValue, const N: usize, const M: usize>
+            $crate::operations::DifferenceAssign<&$map<Key, OtherValue, M>> for $map<Key, Value, N>
+        where
+            Key: $($bounds)*,
+            for<'a> Value: $crate::operations::DifferenceAssign<&'a OtherValue>,
+        {
+            fn difference_assign(&mut self, other: &$map<Key, OtherValue, M>) {
+                for (key, other_value) in other.iter() {
+                    let Some(value) = self.get_mut(key) else {
+                        continue;
+                    };
+
+                    value.difference_assign(other_value);
+
+                    if value.is_empty() {
+                        self.remove(key);
+                    }
+                }
+            }
+        }
+
+        impl<Key, Value, OtherValue, const N: usize, const M: usize>
+            $crate::operations::IntersectionAssign<&$map<Key, OtherValue, M>> for $map<Key, Value, N>
+        where
+            Key: $($bounds)*,
+            for<'a> Value: $crate::operations::IntersectionAssign<&'a OtherValue>,
+        {
+            fn intersection_assign(&mut self, other: &$map<Key, OtherValue, M>) {
+                self.retain(|key, value| match other.get(key) {
+                    Some(other_value) => {
+                        value.intersection_assign(other_value);
+                        !value.is_empty()
+                    }
+                    None => false,
+                });
+            }
+        }
+
+        impl<Key, Value, OtherValue, const N: usize, const M: usize>
+            $crate::operations::TryDisjunctiveUnionAssign<&$map<Key, OtherValue, M>> for $map<Key, Value, N>
+        where
+            Key: $($bounds)* + Clone,
+            for<'a> Value: $crate::operations::DisjunctiveUnionAssign<&'a OtherValue>,
+            OtherValue: Set + Clone + Into<Value>,
+        {
+            type Error = CapacityError;
+
+            /// Fails if the keys that are only in `other` do not fit next to the current keys, even if keys present in both would be removed.
+            fn try_disjunctive_union_assign(&mut self, other: &$map<Key, OtherValue, M>) -> Result<(), CapacityError> {
+                let new_keys = other
+                    .iter()
+                    .filter(|(key, value)| !value.is_empty() && !self.contains_key(key))
+                    .count();
+
+                if self.len() + new_keys > self.capacity() {
+                    return Err(CapacityError);
+                }
+
+                for (key, other_value) in other.iter() {
+                    if let Some(value) = self.get_mut(key) {
+                        value.disjunctive_union_assign(other_value);
+
+                        if value.is_empty() {
+                            self.remove(key);
+                        }
+                    } else if !other_value.is_empty() {
+                        self.insert(key.clone(), other_value.clone().into())
+                            .map_err(|_| CapacityError)?;
+                    }
+                }
+
+                Ok(())
+            }
+        }
+
+        impl_heapless_map!(@derived $map, TryUnionAssign, try_union_assign, TryUnion, try_union);
+        impl_heapless_map!(@derived $map, TryDifferenceAssign, try_difference_assign, TryDifference, try_difference);
+        impl_heapless_map!(@derived $map, TryIntersectionAssign, try_intersection_assign, TryIntersection, try_intersection);
+        impl_heapless_map!(@derived $map, TryDisjunctiveUnionAssign, try_disjunctive_union_assign, TryDisjunctiveUnion, try_disjunctive_union);
+
+        impl<Key, Value, OtherValue, const N: usize, const M: usize>
+            $crate::comparisons::SetEq<$map<Key, OtherValue, M>> for $map<Key, Value, N>
+        where
+            Key: $($bounds)*,
+            Value: $crate::comparisons::SetEq<OtherValue>,
+        {
+            fn set_eq(&self, rhs: &$map<Key, OtherValue, M>) -> bool {
+                rhs.keys().all(|key| self.contains_key(key))
+                    && self
+                        .iter()
+                        .all(|(key, value)| rhs.get(key).is_some_and(|rhs_value| value.set_eq(rhs_value)))
+            }
+        }
+
+        impl<Key, Value, OtherValue, const N: usize, const M: usize>
+            $crate::comparisons::SubsetOf<$map<Key, OtherValue, M>> for $map<Key, Value, N>
+        where
+            Key: $($bounds)*,
+            Value: $crate::comparisons::SubsetOf<OtherValue>,
+        {
+            fn subset_of(&self, rhs: &$map<Key, OtherValue, M>) -> bool {
+                self.iter()
+                    .all(|(key, value)| rhs.get(key).is_some_and(|rhs_value| value.subset_of(rhs_value)))
+            }
+        }
+    };
+    // Implements the non-assigning fallible operation using the assigning one.
+    (@derived $map:ident, $assign_trait:ident, $assign_func:ident, $trait:ident, $func:ident) => {
+        impl<'a, Key, Value, OtherValue, const N: usize, const M: usize>
+            $crate::operations::$trait<&'a $map<Key, OtherValue, M>> for $map<Key, Value, N>
+        where
+            Self: $crate::operations::$assign_trait<&'a $map<Key, OtherValue, M>>,
+        {
+            type Output = Self;
+            type Error = <Self as $crate::operations::$assign_trait<&'a $map<Key, OtherValue, M>>>::Error;
+
+            fn $func(mut self, other: &'a $map<Key, OtherValue, M>) -> Result<Self, Self::Error> {
+                $crate::operations::$assign_trait::$assign_func(&mut self, other)?;
+
+                Ok(self)
+            }
+        }
+    };
+}
+
+impl_heapless_map!(LinearMap, Key: Eq);
+impl_heapless_map!(FnvIndexMap, Key: Hash + Eq);
+
+#[cfg(test)]
+mod tests {
+    use crate::comparisons::SubsetOf;
+    use crate::operations::{
+        DifferenceAssign, IntersectionAssign, TryDisjunctiveUnionAssign, TryUnion, TryUnionAssign,
+    };
+
+    #[allow(unused_imports)]
+    use super::*;
+
+    fn linear_map<const N: usize>(entries: &[(u8, bool)]) -> LinearMap<u8, bool, N> {
+        entries.iter().copied().collect()
+    }
+
+    #[test]
+    fn operations_test() {
+        let mut map = linear_map::<4>(&[(0, true), (1, true)]);
+
+        map.try_union_assign(&linear_map::<2>(&[(1, true), (2, true)]))
+            .unwrap();
+        assert_eq!(map, linear_map::<4>(&[(0, true), (1, true), (2, true)]));
+
+        map.difference_assign(&linear_map::<1>(&[(0, true)]));
+        assert_eq!(map, linear_map::<4>(&[(1, true), (2, true)]));
+
+        map.intersection_assign(&linear_map::<2>(&[(2, true), (3, true)]));
+        assert_eq!(map, linear_map::<4>(&[(2, true)]));
+
+        map.try_disjunctive_union_assign(&linear_map::<2>(&[(2, true), (3, true)]))
+            .unwrap();
+        assert_eq!(map, linear_map::<4>(&[(3, true)]));
+
+        assert!(map.subset_of(&linear_map::<2>(&[(2, true), (3, true)])));
+    }
+
+    #[test]
+    fn capacity_test() {
+        let mut map = linear_map::<2>(&[(0, true)]);
+
+        assert_eq!(
+            map.try_union_assign(&linear_map::<2>(&[(1, true), (2, true)])),
+            Err(CapacityError)
+        );
+        assert_eq!(map, linear_map::<2>(&[(0, true)]));
+
+        assert_eq!(
+            map.clone()
+                .try_union(&linear_map::<2>(&[(1, true), (2, true)])),
+            Err(CapacityError)
+        );
+        assert_eq!(
+            map.try_union_assign(&linear_map::<2>(&[(0, true), (1, true)])),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn nested_test() {
+        let mut map: LinearMap<u8, LinearMap<u8, bool, 2>, 2> = LinearMap::new();
+        map.insert(0, linear_map(&[(0, true), (1, true)])).unwrap();
+        map.insert(1, linear_map(&[(0, true)])).unwrap();
+
+        let mut other: LinearMap<u8, LinearMap<u8, bool, 2>, 2> = LinearMap::new();
+        other.insert(0, linear_map(&[(0, true)])).unwrap();
+        other.insert(1, linear_map(&[(0, true)])).unwrap();
+
+        map.difference_assign(&other);
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.get(&0), Some(&linear_map(&[(1, true)])));
+        assert!(map.subset_of(&map.clone()));
+
+        map.intersection_assign(&other);
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn index_map_test() {
+        let mut map: FnvIndexMap<u8, bool, 2> = FnvIndexMap::new();
+        let mut other: FnvIndexMap<u8, bool, 4> = FnvIndexMap::new();
+        other.insert(0, true).unwrap();
+        other.insert(1, false).unwrap();
+        other.insert(2, true).unwrap();
+
+        map.try_union_assign(&other).unwrap();
+        assert_eq!(map.len(), 2);
+
+        other.insert(3, true).unwrap();
+        assert_eq!(map.try_union_assign(&other), Err(CapacityError));
+    }
+}
@@ -0,0 +1,259 @@
+//! Implementations for the persistent maps of [`im`].
+//!
+//! Unlike the [`std::collections`] maps, these maps share structure between clones, so cloning is O(1) and an operation only copies the parts of a map it changes. To keep as much structure shared as possible, the operations only touch the keys they have to, instead of going over every entry like [`super::map`] does.
+
+use std::hash::Hash;
+
+use ::im::{HashMap as ImHashMap, OrdMap};
+
+use crate::{Set, impl_map_comparisons};
+
+pub(crate) fn remove_empty_keys<K: Hash + Eq + Clone, V: Set + Clone>(map: &mut ImHashMap<K, V>) {
+    map.retain(|_key, value| !value.is_empty());
+}
+
+macro_rules! impl_im_map {
+    ($map:ident, Key: $($bounds:tt)*) => {
+        impl<Key: $($bounds)*, Value: Clone> Set for $map<Key, Value> {
+            type Empty = Self;
+
+            fn is_empty(&self) -> bool {
+                $map::is_empty(self)
+            }
+
+            fn empty() -> Self::Empty {
+                $map::new()
+            }
+        }
+
+        impl<Key: $($bounds)*, Value: Set<Empty = Value> + Clone> $crate::requirements::SetEntry for $map<Key, Value> {
+            type Key = Key;
+            type Value = Value;
+
+            fn set_entry(&mut self, key: Key) -> &mut Value {
+                self.entry(key).or_insert_with(Value::empty)
+            }
+        }
+
+        impl<Key, Value, OtherValue> $crate::operations::UnionAssign<&$map<Key, OtherValue>> for $map<Key, Value>
+        where
+            Key: $($bounds)*,
+            Value: Clone,
+            for<'a> Value: $crate::operations::UnionAssign<&'a OtherValue>,
+            OtherValue: Clone + Set + Into<Value>,
+        {
+            fn union_assign(&mut self, other: &$map<Key, OtherValue>) {
+                for (key, other_value) in other.iter() {
+                    if let Some(value) = self.get_mut(key) {
+                        value.union_assign(other_value);
+                    } else if !other_value.is_empty() {
+                        self.insert(key.clone(), other_value.clone().into());
+                    }
+                }
+            }
+        }
+
+        impl<Key, Value, OtherValue> $crate::operations::DifferenceAssign<&$map<Key, OtherValue>> for $map<Key, Value>
+        where
+            Key: $($bounds)*,
+            Value: Set + Clone,
+            for<'a> Value: $crate::operations::DifferenceAssign<&'a OtherValue>,
+            OtherValue: Clone,
+        {
+            fn difference_assign(&mut self, other: &$map<Key, OtherValue>) {
+                for (key, other_value) in other.iter() {
+                    let Some(value) = self.get_mut(key) else {
+                        continue;
+                    };
+
+                    value.difference_assign(other_value);
+
+                    if value.is_empty() {
+                        self.remove(key);
+                    }
+                }
+            }
+        }
+
+        impl<Key, Value, OtherValue> $crate::operations::IntersectionAssign<&$map<Key, OtherValue>> for $map<Key, Value>
+        where
+            Key: $($bounds)*,
+            Value: Set + Clone,
+            for<'a> Value: $crate::operations::IntersectionAssign<&'a OtherValue>,
+            OtherValue: Clone,
+        {
+            fn intersection_assign(&mut self, other: &$map<Key, OtherValue>) {
+                let keys: Vec<Key> = self.keys().cloned().collect();
+
+                for key in keys {
+                    let Some(other_value) = other.get(&key) else {
+                        self.remove(&key);
+                        continue;
+                    };
+
+                    let value = self.get_mut(&key).expect("Key was taken from self.");
+                    value.intersection_assign(other_value);
+
+                    if value.is_empty() {
+                        self.remove(&key);
+                    }
+                }
+            }
+        }
+
+        impl<Key, Value, OtherValue> $crate::operations::DisjunctiveUnionAssign<&$map<Key, OtherValue>> for $map<Key, Value>
+        where
+            Key: $($bounds)*,
+            Value: Set + Clone,
+            for<'a> Value: $crate::operations::DisjunctiveUnionAssign<&'a OtherValue>,
+            OtherValue: Clone + Set + Into<Value>,
+        {
+            fn disjunctive_union_assign(&mut self, other: &$map<Key, OtherValue>) {
+                for (key, other_value) in other.iter() {
+                    if let Some(value) = self.get_mut(key) {
+                        value.disjunctive_union_assign(other_value);
+
+                        if value.is_empty() {
+                            self.remove(key);
+                        }
+                    } else if !other_value.is_empty() {
+                        self.insert(key.clone(), other_value.clone().into());
+                    }
+                }
+            }
+        }
+
+        impl_im_map!(@derived $map, Key: ($($bounds)*), UnionAssign, union_assign, Union, union);
+        impl_im_map!(@derived $map, Key: ($($bounds)*), DifferenceAssign, difference_assign, Difference, difference);
+        impl_im_map!(@derived $map, Key: ($($bounds)*), IntersectionAssign, intersection_assign, Intersection, intersection);
+        impl_im_map!(@derived $map, Key: ($($bounds)*), DisjunctiveUnionAssign, disjunctive_union_assign, DisjunctiveUnion, disjunctive_union);
+    };
+    // Implements the owned variant of an assigning operation, as well as the non-assigning operation, using the assigning operation by reference.
+    (@derived $map:ident, Key: ($($bounds:tt)*), $assign_trait:ident, $assign_func:ident, $trait:ident, $func:ident) => {
+        impl<Key, Value, OtherValue> $crate::operations::$assign_trait<$map<Key, OtherValue>> for $map<Key, Value>
+        where
+            Key: $($bounds)*,
+            Value: Clone,
+            OtherValue: Clone,
+            for<'a> Self: $crate::operations::$assign_trait<&'a $map<Key, OtherValue>>,
+        {
+            fn $assign_func(&mut self, other: $map<Key, OtherValue>) {
+                $crate::operations::$assign_trait::$assign_func(self, &other);
+            }
+        }
+
+        impl<Key, Value, OtherValue> $crate::operations::$trait<&$map<Key, OtherValue>> for $map<Key, Value>
+        where
+            Key: $($bounds)*,
+            Value: Clone,
+            OtherValue: Clone,
+            for<'a> Self: $crate::operations::$assign_trait<&'a $map<Key, OtherValue>>,
+        {
+            type Output = Self;
+
+            fn $func(mut self, other: &$map<Key, OtherValue>) -> Self::Output {
+                $crate::operations::$assign_trait::$assign_func(&mut self, other);
+
+                self
+            }
+        }
+
+        impl<Key, Value, OtherValue> $crate::operations::$trait<$map<Key, OtherValue>> for $map<Key, Value>
+        where
+            Key: $($bounds)*,
+            Value: Clone,
+            OtherValue: Clone,
+            for<'a> Self: $crate::operations::$assign_trait<&'a $map<Key, OtherValue>>,
+        {
+            type Output = Self;
+
+            fn $func(mut self, other: $map<Key, OtherValue>) -> Self::Output {
+                $crate::operations::$assign_trait::$assign_func(&mut self, &other);
+
+                self
+            }
+        }
+    };
+}
+
+impl_im_map!(ImHashMap, Key: Hash + Eq + Clone);
+impl_im_map!(OrdMap, Key: Ord + Clone);
+impl_map_comparisons!(ImHashMap, ImHashMap, Key: Hash + Eq);
+impl_map_comparisons!(OrdMap, OrdMap, Key: Ord);
+
+#[cfg(test)]
+mod tests {
+    use core::fmt::Debug;
+
+    use crate::comparisons::SubsetOf;
+    use crate::operations::{
+        DifferenceAssign, DisjunctiveUnionAssign, IntersectionAssign, UnionAssign,
+    };
+    use ::im::{hashmap, ordmap};
+    use rstest::*;
+
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[rstest]
+    #[case(hashmap! { 0 => true }, hashmap! { 1 => true }, hashmap! { 0 => true, 1 => true })]
+    #[case(ordmap! { 0 => true }, ordmap! { 0 => false, 1 => true }, ordmap! { 0 => true, 1 => true })]
+    #[case(ordmap! { 0 => ordmap! { 0 => true } }, ordmap! { 0 => ordmap! { 1 => true } }, ordmap! { 0 => ordmap! { 0 => true, 1 => true } })]
+    fn union_tests<M: Debug + PartialEq + for<'a> UnionAssign<&'a M>>(
+        #[case] mut a: M,
+        #[case] b: M,
+        #[case] result: M,
+    ) {
+        a.union_assign(&b);
+        assert_eq!(a, result);
+    }
+
+    #[rstest]
+    #[case(hashmap! { 0 => true, 1 => true }, hashmap! { 1 => true }, hashmap! { 0 => true })]
+    #[case(ordmap! { 0 => true }, ordmap! { 0 => false }, ordmap! { 0 => true })]
+    #[case(ordmap! { 0 => ordmap! { 0 => true } }, ordmap! { 0 => ordmap! { 0 => true } }, ordmap! {})]
+    fn difference_tests<M: Debug + PartialEq + for<'a> DifferenceAssign<&'a M>>(
+        #[case] mut a: M,
+        #[case] b: M,
+        #[case] result: M,
+    ) {
+        a.difference_assign(&b);
+        assert_eq!(a, result);
+    }
+
+    #[rstest]
+    #[case(hashmap! { 0 => true, 1 => true }, hashmap! { 1 => true, 2 => true }, hashmap! { 1 => true })]
+    #[case(ordmap! { 0 => true }, ordmap! { 0 => false }, ordmap! {})]
+    fn intersection_tests<M: Debug + PartialEq + for<'a> IntersectionAssign<&'a M>>(
+        #[case] mut a: M,
+        #[case] b: M,
+        #[case] result: M,
+    ) {
+        a.intersection_assign(&b);
+        assert_eq!(a, result);
+    }
+
+    #[rstest]
+    #[case(hashmap! { 0 => true, 1 => true }, hashmap! { 1 => true, 2 => true }, hashmap! { 0 => true, 2 => true })]
+    #[case(ordmap! { 0 => true }, ordmap! { 1 => false }, ordmap! { 0 => true })]
+    fn disjunctive_union_tests<M: Debug + PartialEq + for<'a> DisjunctiveUnionAssign<&'a M>>(
+        #[case] mut a: M,
+        #[case] b: M,
+        #[case] result: M,
+    ) {
+        a.disjunctive_union_assign(&b);
+        assert_eq!(a, result);
+    }
+
+    #[test]
+    fn snapshot_test() {
+        let snapshot = ordmap! { 0 => true, 1 => true };
+
+        let mut map = snapshot.clone();
+        map.difference_assign(&ordmap! { 1 => true });
+
+        assert_eq!(map, ordmap! { 0 => true });
+        assert_eq!(snapshot, ordmap! { 0 => true, 1 => true });
+        assert!(map.subset_of(&snapshot));
+    }
+}
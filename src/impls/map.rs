@@ -16,6 +16,15 @@ macro_rules! impl_map {
                 $map::new()
             }
         }
+
+        impl<Key: $($bounds)*, Value: $crate::Set<Empty = Value>> $crate::requirements::SetEntry for $map<Key, Value> {
+            type Key = Key;
+            type Value = Value;
+
+            fn set_entry(&mut self, key: Key) -> &mut Value {
+                self.entry(key).or_insert_with(Value::empty)
+            }
+        }
     }
 }
 
@@ -104,6 +113,8 @@ macro_rules! impl_map_owned_operations {
 
                     $crate::operations::IntersectionAssign::intersection_assign(value, other_value);
                 }
+
+                remove_empty_keys(self);
             }
         }
 
@@ -248,6 +259,8 @@ macro_rules! impl_map_ref_operations {
 
                     $crate::operations::IntersectionAssign::intersection_assign(value, other_value);
                 }
+
+                remove_empty_keys(self);
             }
         }
 
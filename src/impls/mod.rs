@@ -1,18 +1,30 @@
 mod bool;
 
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
 pub(crate) mod btreemap;
 #[cfg(feature = "std")]
 pub(crate) mod hashmap;
 
+#[cfg(feature = "hashbrown")]
+pub(crate) mod hashbrown;
+
+#[cfg(feature = "heapless")]
+mod heapless;
+
+#[cfg(feature = "im")]
+pub(crate) mod im;
+
 #[macro_use]
 pub(crate) mod map;
 
 mod option;
 
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
 mod r#box;
 
+#[cfg(feature = "alloc")]
+mod set;
+
 mod array;
 
 mod tuples;
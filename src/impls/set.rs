@@ -0,0 +1,202 @@
+//! Implementations for the set collections, such as [`BTreeSet`] and [`std::collections::HashSet`], where every element that is present is a member of the set.
+
+use alloc::collections::BTreeSet;
+
+use crate::Set;
+
+macro_rules! impl_set {
+    ($set:ident, Element: $($bounds:tt)*) => {
+        impl<Element: $($bounds)*> Set for $set<Element> {
+            type Empty = Self;
+
+            fn is_empty(&self) -> bool {
+                $set::is_empty(self)
+            }
+
+            fn empty() -> Self::Empty {
+                $set::new()
+            }
+        }
+
+        impl<Element: $($bounds)* + Clone> $crate::operations::UnionAssign<&$set<Element>> for $set<Element> {
+            fn union_assign(&mut self, other: &$set<Element>) {
+                self.extend(other.iter().cloned());
+            }
+        }
+
+        impl<Element: $($bounds)*> $crate::operations::UnionAssign<$set<Element>> for $set<Element> {
+            fn union_assign(&mut self, other: $set<Element>) {
+                self.extend(other);
+            }
+        }
+
+        impl<Element: $($bounds)*> $crate::operations::DifferenceAssign<&$set<Element>> for $set<Element> {
+            fn difference_assign(&mut self, other: &$set<Element>) {
+                self.retain(|element| !other.contains(element));
+            }
+        }
+
+        impl<Element: $($bounds)*> $crate::operations::DifferenceAssign<$set<Element>> for $set<Element> {
+            fn difference_assign(&mut self, other: $set<Element>) {
+                $crate::operations::DifferenceAssign::difference_assign(self, &other);
+            }
+        }
+
+        impl<Element: $($bounds)*> $crate::operations::IntersectionAssign<&$set<Element>> for $set<Element> {
+            fn intersection_assign(&mut self, other: &$set<Element>) {
+                self.retain(|element| other.contains(element));
+            }
+        }
+
+        impl<Element: $($bounds)*> $crate::operations::IntersectionAssign<$set<Element>> for $set<Element> {
+            fn intersection_assign(&mut self, other: $set<Element>) {
+                $crate::operations::IntersectionAssign::intersection_assign(self, &other);
+            }
+        }
+
+        impl<Element: $($bounds)* + Clone> $crate::operations::DisjunctiveUnionAssign<&$set<Element>> for $set<Element> {
+            fn disjunctive_union_assign(&mut self, other: &$set<Element>) {
+                for element in other.iter() {
+                    if !self.remove(element) {
+                        self.insert(element.clone());
+                    }
+                }
+            }
+        }
+
+        impl<Element: $($bounds)*> $crate::operations::DisjunctiveUnionAssign<$set<Element>> for $set<Element> {
+            fn disjunctive_union_assign(&mut self, other: $set<Element>) {
+                for element in other {
+                    if !self.remove(&element) {
+                        self.insert(element);
+                    }
+                }
+            }
+        }
+
+        impl_set!(@derived $set, Element: ($($bounds)*), UnionAssign, union_assign, Union, union);
+        impl_set!(@derived $set, Element: ($($bounds)*), DifferenceAssign, difference_assign, Difference, difference);
+        impl_set!(@derived $set, Element: ($($bounds)*), IntersectionAssign, intersection_assign, Intersection, intersection);
+        impl_set!(@derived $set, Element: ($($bounds)*), DisjunctiveUnionAssign, disjunctive_union_assign, DisjunctiveUnion, disjunctive_union);
+
+        impl<Element: $($bounds)*> $crate::comparisons::SetEq for $set<Element> {
+            fn set_eq(&self, rhs: &$set<Element>) -> bool {
+                self == rhs
+            }
+        }
+
+        impl<Element: $($bounds)*> $crate::comparisons::SubsetOf for $set<Element> {
+            fn subset_of(&self, rhs: &$set<Element>) -> bool {
+                self.is_subset(rhs)
+            }
+        }
+    };
+    // Implements the non-assigning operation for both reference and owned sets, using the assigning operation.
+    (@derived $set:ident, Element: ($($bounds:tt)*), $assign_trait:ident, $assign_func:ident, $trait:ident, $func:ident) => {
+        impl<Element: $($bounds)*, Rhs> $crate::operations::$trait<Rhs> for $set<Element>
+        where
+            Self: $crate::operations::$assign_trait<Rhs>,
+        {
+            type Output = Self;
+
+            fn $func(mut self, other: Rhs) -> Self::Output {
+                $crate::operations::$assign_trait::$assign_func(&mut self, other);
+
+                self
+            }
+        }
+    };
+}
+
+impl_set!(BTreeSet, Element: Ord);
+
+#[cfg(feature = "std")]
+mod std_impl {
+    use super::*;
+    use std::collections::HashSet;
+    use std::hash::Hash;
+    impl_set!(HashSet, Element: Hash + Eq);
+}
+
+#[cfg(feature = "hashbrown")]
+mod hashbrown_impl {
+    use super::*;
+    use ::hashbrown::HashSet;
+    use core::hash::Hash;
+    impl_set!(HashSet, Element: Hash + Eq);
+}
+
+#[cfg(test)]
+mod tests {
+    use core::fmt::Debug;
+    use std::collections::HashSet;
+
+    use crate::comparisons::{SetEq, StrictSubsetOf, SubsetOf};
+    use crate::operations::{
+        DifferenceAssign, DisjunctiveUnion, DisjunctiveUnionAssign, IntersectionAssign, UnionAssign,
+    };
+    use maplit::{btreeset, hashset};
+    use rstest::*;
+
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[rstest]
+    #[case(btreeset! { 0, 1 }, btreeset! { 1, 2 }, btreeset! { 0, 1, 2 })]
+    #[case(hashset! { 0 }, hashset! {}, hashset! { 0 })]
+    fn union_tests<S: Debug + PartialEq + for<'a> UnionAssign<&'a S>>(
+        #[case] mut a: S,
+        #[case] b: S,
+        #[case] result: S,
+    ) {
+        a.union_assign(&b);
+        assert_eq!(a, result);
+    }
+
+    #[rstest]
+    #[case(btreeset! { 0, 1 }, btreeset! { 1, 2 }, btreeset! { 0 })]
+    #[case(hashset! { 0 }, hashset! { 0 }, hashset! {})]
+    fn difference_tests<S: Debug + PartialEq + for<'a> DifferenceAssign<&'a S>>(
+        #[case] mut a: S,
+        #[case] b: S,
+        #[case] result: S,
+    ) {
+        a.difference_assign(&b);
+        assert_eq!(a, result);
+    }
+
+    #[rstest]
+    #[case(btreeset! { 0, 1 }, btreeset! { 1, 2 }, btreeset! { 1 })]
+    #[case(hashset! { 0 }, hashset! { 1 }, hashset! {})]
+    fn intersection_tests<S: Debug + PartialEq + for<'a> IntersectionAssign<&'a S>>(
+        #[case] mut a: S,
+        #[case] b: S,
+        #[case] result: S,
+    ) {
+        a.intersection_assign(&b);
+        assert_eq!(a, result);
+    }
+
+    #[rstest]
+    #[case(btreeset! { 0, 1 }, btreeset! { 1, 2 }, btreeset! { 0, 2 })]
+    #[case(hashset! { 0 }, hashset! { 0 }, hashset! {})]
+    fn disjunctive_union_tests<S: Debug + PartialEq + for<'a> DisjunctiveUnionAssign<&'a S>>(
+        #[case] mut a: S,
+        #[case] b: S,
+        #[case] result: S,
+    ) {
+        a.disjunctive_union_assign(&b);
+        assert_eq!(a, result);
+    }
+
+    #[test]
+    fn comparison_test() {
+        let a: HashSet<u8> = hashset! { 0 };
+        let b: HashSet<u8> = hashset! { 0, 1 };
+
+        assert!(a.subset_of(&b));
+        assert!(a.strict_subset_of(&b));
+        assert!(!b.subset_of(&a));
+        assert!(a.clone().disjunctive_union(b).set_eq(&hashset! { 1 }));
+    }
+}
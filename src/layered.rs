@@ -0,0 +1,394 @@
+//! This module contains [`LayeredPermissions`], a stack of layers such as user, group and default permissions, where every layer grants and denies parts of a set and higher priority layers decide first.
+//!
+//! ```
+//! # use std::collections::HashSet;
+//! # use finit::layered::{Layer, LayeredPermissions};
+//! let mut permissions = LayeredPermissions::new();
+//! permissions.insert(Layer::new("default", 0, HashSet::from(["chat", "trade"]), HashSet::new()));
+//! permissions.insert(Layer::new("muted", 10, HashSet::new(), HashSet::from(["chat", "trade"])));
+//! permissions.insert(Layer::new("user", 20, HashSet::from(["trade"]), HashSet::new()));
+//!
+//! assert_eq!(permissions.effective(), HashSet::from(["trade"]));
+//!
+//! let explanation = permissions.explain(&HashSet::from(["chat", "trade", "fly"]));
+//! assert_eq!(explanation.decisions[0].layer, "user");
+//! assert_eq!(explanation.decisions[0].granted, HashSet::from(["trade"]));
+//! assert_eq!(explanation.decisions[1].layer, "muted");
+//! assert_eq!(explanation.decisions[1].denied, HashSet::from(["chat"]));
+//! assert_eq!(explanation.undecided, HashSet::from(["fly"]));
+//! ```
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::Set;
+use crate::operations::{DifferenceAssign, IntersectionAssign, UnionAssign};
+
+/// A layer of [`LayeredPermissions`], which grants and denies parts of a set with a priority.
+///
+/// If a layer both grants and denies the same part, it is denied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Layer<S> {
+    pub name: String,
+    /// Layers with a higher priority decide first. Layers with the same priority decide in the order they were inserted.
+    pub priority: u32,
+    pub grants: S,
+    pub denies: S,
+}
+
+impl<S> Layer<S> {
+    pub fn new(name: impl Into<String>, priority: u32, grants: S, denies: S) -> Self {
+        Self {
+            name: name.into(),
+            priority,
+            grants,
+            denies,
+        }
+    }
+}
+
+/// A stack of [`Layer`]s ordered by priority, where every part of the set is decided by the layer with the highest priority that grants or denies it.
+///
+/// This lets a user level grant override a group level deny, unlike the union of the layers where only grants are kept.
+///
+/// With serde, it is a sequence of layers. Deserializing inserts them one by one, so they end up ordered by priority whatever order they are in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        from = "Vec<Layer<S>>",
+        into = "Vec<Layer<S>>",
+        bound(serialize = "S: Clone + serde::Serialize")
+    )
+)]
+pub struct LayeredPermissions<S> {
+    /// The layers from the highest priority to the lowest.
+    layers: Vec<Layer<S>>,
+}
+
+/// The grants and denies of a layer, returned by [`LayeredPermissions::layer_mut`].
+///
+/// The name and priority can not be changed in place, since the layers are kept ordered by priority. Remove the layer and insert it again instead.
+#[derive(Debug)]
+pub struct LayerMut<'a, S> {
+    pub grants: &'a mut S,
+    pub denies: &'a mut S,
+}
+
+/// What a single layer decided for the queried set, as part of an [`Explanation`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decision<'a, S> {
+    /// The name of the layer.
+    pub layer: &'a str,
+    /// The part of the queried set that the layer granted.
+    pub granted: S,
+    /// The part of the queried set that the layer denied.
+    pub denied: S,
+}
+
+/// Which layer decided every part of a queried set, returned by [`LayeredPermissions::explain`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation<'a, S> {
+    /// The layers that decided part of the queried set, from the highest priority to the lowest.
+    pub decisions: Vec<Decision<'a, S>>,
+    /// The part of the queried set that no layer grants or denies.
+    pub undecided: S,
+}
+
+impl<S> LayeredPermissions<S> {
+    pub fn new() -> Self {
+        Self { layers: Vec::new() }
+    }
+
+    /// Inserts `layer` below every layer with a higher or equal priority.
+    pub fn insert(&mut self, layer: Layer<S>) {
+        let index = self
+            .layers
+            .partition_point(|other| other.priority >= layer.priority);
+
+        self.layers.insert(index, layer);
+    }
+
+    /// Removes and returns the layer called `name`.
+    pub fn remove(&mut self, name: &str) -> Option<Layer<S>> {
+        let index = self.layers.iter().position(|layer| layer.name == name)?;
+
+        Some(self.layers.remove(index))
+    }
+
+    /// The layers, from the highest priority to the lowest.
+    pub fn layers(&self) -> &[Layer<S>] {
+        &self.layers
+    }
+
+    /// The grants and denies of the layer called `name`, for changing them.
+    pub fn layer_mut(&mut self, name: &str) -> Option<LayerMut<'_, S>> {
+        self.layers
+            .iter_mut()
+            .find(|layer| layer.name == name)
+            .map(|layer| LayerMut {
+                grants: &mut layer.grants,
+                denies: &mut layer.denies,
+            })
+    }
+}
+
+impl<S: Set<Empty = S> + Clone> LayeredPermissions<S>
+where
+    for<'a> S: UnionAssign<&'a S> + DifferenceAssign<&'a S> + IntersectionAssign<&'a S>,
+{
+    /// The set that the layers grant, which is every part whose highest priority layer grants it.
+    pub fn effective(&self) -> S {
+        let mut effective = S::empty();
+        let mut decided = S::empty();
+
+        for layer in &self.layers {
+            let mut granted = layer.grants.clone();
+            granted.difference_assign(&layer.denies);
+            granted.difference_assign(&decided);
+            effective.union_assign(&granted);
+
+            decided.union_assign(&layer.grants);
+            decided.union_assign(&layer.denies);
+        }
+
+        effective
+    }
+
+    /// Splits `query` by the layer that decided each part of it.
+    pub fn explain(&self, query: &S) -> Explanation<'_, S> {
+        let mut undecided = query.clone();
+        let mut decisions = Vec::new();
+
+        for layer in &self.layers {
+            let mut granted = undecided.clone();
+            granted.intersection_assign(&layer.grants);
+            granted.difference_assign(&layer.denies);
+
+            let mut denied = undecided.clone();
+            denied.intersection_assign(&layer.denies);
+
+            undecided.difference_assign(&layer.grants);
+            undecided.difference_assign(&layer.denies);
+
+            if !granted.is_empty() || !denied.is_empty() {
+                decisions.push(Decision {
+                    layer: &layer.name,
+                    granted,
+                    denied,
+                });
+            }
+        }
+
+        Explanation {
+            decisions,
+            undecided,
+        }
+    }
+}
+
+impl<S> Default for LayeredPermissions<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> FromIterator<Layer<S>> for LayeredPermissions<S> {
+    fn from_iter<I: IntoIterator<Item = Layer<S>>>(iter: I) -> Self {
+        let mut permissions = Self::new();
+
+        for layer in iter {
+            permissions.insert(layer);
+        }
+
+        permissions
+    }
+}
+
+impl<S> From<Vec<Layer<S>>> for LayeredPermissions<S> {
+    fn from(layers: Vec<Layer<S>>) -> Self {
+        layers.into_iter().collect()
+    }
+}
+
+impl<S> From<LayeredPermissions<S>> for Vec<Layer<S>> {
+    /// The layers, from the highest priority to the lowest.
+    fn from(permissions: LayeredPermissions<S>) -> Self {
+        permissions.layers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use maplit::hashmap;
+    use rstest::*;
+
+    #[allow(unused_imports)]
+    use super::*;
+    use crate::collections::WildcardHashMap;
+
+    type Perms = HashMap<&'static str, bool>;
+
+    fn layer(name: &str, priority: u32, grants: Perms, denies: Perms) -> Layer<Perms> {
+        Layer::new(name, priority, grants, denies)
+    }
+
+    fn permissions() -> LayeredPermissions<Perms> {
+        [
+            layer(
+                "default",
+                0,
+                hashmap! { "chat" => true, "trade" => true },
+                hashmap! {},
+            ),
+            layer(
+                "group",
+                10,
+                hashmap! { "kick" => true },
+                hashmap! { "trade" => true, "fly" => true },
+            ),
+            layer(
+                "user",
+                20,
+                hashmap! { "fly" => true },
+                hashmap! { "chat" => true },
+            ),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn insert_test() {
+        let names = permissions()
+            .layers()
+            .iter()
+            .map(|layer| layer.name.clone())
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, ["user", "group", "default"]);
+    }
+
+    #[test]
+    fn effective_test() {
+        assert_eq!(
+            permissions().effective(),
+            hashmap! { "kick" => true, "fly" => true }
+        );
+    }
+
+    #[test]
+    fn same_layer_test() {
+        let permissions: LayeredPermissions<Perms> = [layer(
+            "user",
+            0,
+            hashmap! { "chat" => true },
+            hashmap! { "chat" => true },
+        )]
+        .into_iter()
+        .collect();
+
+        assert_eq!(permissions.effective(), hashmap! {});
+    }
+
+    #[rstest]
+    #[case(hashmap! { "fly" => true }, vec![("user", hashmap! { "fly" => true }, hashmap! {})], hashmap! {})]
+    #[case(hashmap! { "chat" => true, "trade" => true }, vec![("user", hashmap! {}, hashmap! { "chat" => true }), ("group", hashmap! {}, hashmap! { "trade" => true })], hashmap! {})]
+    #[case(hashmap! { "ban" => true }, vec![], hashmap! { "ban" => true })]
+    fn explain_tests(
+        #[case] query: Perms,
+        #[case] decisions: Vec<(&str, Perms, Perms)>,
+        #[case] undecided: Perms,
+    ) {
+        let permissions = permissions();
+
+        assert_eq!(
+            permissions.explain(&query),
+            Explanation {
+                decisions: decisions
+                    .into_iter()
+                    .map(|(layer, granted, denied)| Decision {
+                        layer,
+                        granted,
+                        denied
+                    })
+                    .collect(),
+                undecided,
+            }
+        );
+    }
+
+    #[test]
+    fn wildcard_test() {
+        let mut permissions = LayeredPermissions::new();
+        permissions.insert(Layer::new(
+            "group",
+            0,
+            WildcardHashMap::new(true),
+            WildcardHashMap::empty(),
+        ));
+        permissions.insert(Layer::new(
+            "user",
+            10,
+            WildcardHashMap::empty(),
+            WildcardHashMap::from(hashmap! { "secret" => true }),
+        ));
+
+        let effective = permissions.effective();
+
+        assert!(effective.value_of(&"general"));
+        assert!(!effective.value_of(&"secret"));
+    }
+
+    #[test]
+    fn remove_test() {
+        let mut permissions = permissions();
+        permissions.remove("user");
+
+        assert_eq!(
+            permissions.effective(),
+            hashmap! { "chat" => true, "kick" => true }
+        );
+        assert!(permissions.remove("user").is_none());
+    }
+
+    #[test]
+    fn layer_mut_test() {
+        let mut permissions = permissions();
+        let layer = permissions.layer_mut("user").unwrap();
+        layer.denies.remove("chat");
+        layer.grants.insert("chat", true);
+
+        assert_eq!(
+            permissions.effective(),
+            hashmap! { "chat" => true, "kick" => true, "fly" => true }
+        );
+        assert!(permissions.layer_mut("admin").is_none());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_test() {
+        let permissions: LayeredPermissions<Perms> = serde_json::from_str(
+            r#"[
+                {"name": "default", "priority": 0, "grants": {"chat": true, "trade": true}, "denies": {}},
+                {"name": "user", "priority": 20, "grants": {"fly": true}, "denies": {"chat": true}},
+                {"name": "group", "priority": 10, "grants": {"kick": true}, "denies": {"trade": true, "fly": true}}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(permissions, self::permissions());
+        let serialized = serde_json::to_value(&permissions).unwrap();
+        let names = serialized
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|layer| layer["name"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["user", "group", "default"]);
+    }
+}
@@ -13,6 +13,8 @@
 //! - [`operations::DifferenceAssign`]
 //! - [`operations::DisjunctiveUnion`] (⊖)
 //! - [`operations::DisjunctiveUnionAssign`]
+//! - [`operations::TryUnion`], [`operations::TryIntersection`], [`operations::TryDifference`] and [`operations::TryDisjunctiveUnion`] - fallible versions of the operations and their assigning versions, which are implemented for every infallible operation and can be derived with `#[finit(error = E)]` to report the path of the failing field.
+//! - [`operations::SetIterator`] - folds iterators of sets with `union_all`, `intersection_all` and `difference_all`.
 //!
 //!
 //! ## [Comparisons](comparisons)
@@ -22,15 +24,49 @@
 //! - [`comparisons::SupersetOf`] (⊇)
 //! - [`comparisons::StrictSupersetOf`] (⊃)
 //!
+//! ## [Expressions](expr)
+//! - [`expr::Expr`] - a lazily evaluated expression of operations, which can be simplified, queried per key and evaluated on demand.
+//!
+//! ## [Deltas](delta)
+//! - [`delta::Delta`] - the change between two versions of a set, which can be applied, inverted, composed and serialized.
+//!
+//! ## [Observing changes](observed)
+//! - [`observed::Observed`] - wraps a set and calls observers with what changed, or with every changed key of maps implementing [`observed::ChangedKeys`].
+//!
+//! ## [Overlays](overlay)
+//! - [`overlay::TriState`] - a value that is unset, allowed or explicitly denied.
+//! - [`overlay::Overlay`] - layers of sets where higher layers override lower ones, resolved into a plain set with [`overlay::Resolve`].
+//!
+//! ## [Layered permissions](layered)
+//! - [`layered::LayeredPermissions`] - layers of grants and denies ordered by priority, where the highest priority layer decides, with an explanation of which layer decided what.
+//!
+//! ## [Requirements](requirements)
+//! - [`requirements::Require`] - checks that a set contains a required set and returns the [`requirements::MissingPermissions`] otherwise.
+//! - [`require!`] - builds a minimal required set from field paths on an empty set, without any derive metadata, and checks it with [`requirements::Require`].
+//!
+//! ## [Delegation](delegation)
+//! - [`delegation::delegate`] - checks that a granter may grant a requested set and returns the [`delegation::Excess`] otherwise.
+//! - [`struct@delegation::Grantable`] - the permissions that are held next to the permissions that may be granted, which can be derived for nested structs.
+//!
 //! Feature | Description
 //! --- | --- 
-//! `std` (default) | Adds support for [`std::collections::HashMap`] and [`std::collections::BTreeMap`] as well as adds the types [`collections::WildcardBTreeMap`] and [`collections::WildcardHashMap`].
-//! `derive` | Adds derive macros for operations and comparisons.
-//! `serde`| Adds [`serde::Serialize`] and [`serde::Deserialize`] support for built-in types.
+//! `std` (default) | Enables `alloc` and adds support for [`std::collections::HashMap`] and [`std::collections::HashSet`] as well as adds the type [`collections::WildcardHashMap`] and the [`expr`] and [`symbol`] modules.
+//! `alloc` | Adds support for `BTreeMap`, `BTreeSet` and `Box` from [`alloc`] as well as adds the types [`collections::WildcardBTreeMap`], [`collections::Timed`], [`collections::Conditional`] and [`collections::Scoped`] and the [`schema`] module, without requiring `std`.
+//! `hashbrown` | Adds support for `hashbrown::HashMap` and `hashbrown::HashSet`, which can be used as hash maps without `std`.
+//! `heapless` | Adds fixed-capacity support for `heapless::LinearMap` and `heapless::index_map::FnvIndexMap` as well as the type `collections::HeaplessWildcardMap`, which need no allocator. Operations that can add keys are fallible, see [`operations::TryUnionAssign`]. Maps whose values are heapless maps only support difference, intersection and the comparisons, since a nested union could fail halfway.
+//! `derive` | Adds derive macros for operations, comparisons, [`codec`] and [`schema`].
+//! `serde`| Adds [`serde::Serialize`] and [`serde::Deserialize`] support for built-in types. The representations are documented in [`representation`].
 //! `phf` | Adds operations between [`phf::Map`], [`phf::OrderedMap`] and [`std::collections`] maps.
+//! `codec` | Adds the compact binary format in [`codec`], which only requires `alloc`.
+//! `policy` | Adds the [`policy`] module, which loads roles from TOML, YAML and JSON documents into sets.
+//! `rayon` | Adds parallel versions of operations and comparisons in [`parallel`].
+//! `im` | Adds support for the persistent maps `im::HashMap` and `im::OrdMap`, as well as the type `collections::WildcardImHashMap`, which share structure between clones.
 //! 
 //! This library was originally designed to create a permission system, but it can be used for any kind of system that requires set-based data structures.
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 #[cfg(test)]
 #[doc = include_str!("../README.md")]
 mod doc_test {}
@@ -52,6 +88,43 @@ pub mod operations;
 #[macro_use]
 pub mod comparisons;
 
+#[macro_use]
+pub mod requirements;
+
+pub mod delegation;
+
+pub mod delta;
+
+#[cfg(feature = "alloc")]
+pub mod observed;
+
+#[cfg(feature = "alloc")]
+pub mod overlay;
+
+#[cfg(feature = "alloc")]
+pub mod layered;
+
 mod impls;
 
 pub mod collections;
+
+#[cfg(feature = "codec")]
+pub mod codec;
+
+#[cfg(feature = "serde")]
+pub mod representation;
+
+#[cfg(feature = "alloc")]
+pub mod schema;
+
+#[cfg(feature = "std")]
+pub mod expr;
+
+#[cfg(feature = "policy")]
+pub mod policy;
+
+#[cfg(feature = "rayon")]
+pub mod parallel;
+
+#[cfg(feature = "std")]
+pub mod symbol;
@@ -0,0 +1,328 @@
+//! This module contains [`Observed`], a wrapper around a set that tells registered callbacks what actually changed when it is modified, such as to invalidate cached decisions.
+//!
+//! ```
+//! # use std::cell::RefCell;
+//! # use std::collections::HashMap;
+//! # use std::rc::Rc;
+//! # use finit::observed::{ChangedKey, Observed};
+//! # use finit::operations::UnionAssign;
+//! let invalidated = Rc::new(RefCell::new(Vec::new()));
+//!
+//! let mut perms = Observed::new(HashMap::from([("kick", true)]));
+//! perms.on_key_change({
+//!     let invalidated = invalidated.clone();
+//!     move |key| invalidated.borrow_mut().push(key.cloned())
+//! });
+//!
+//! perms.union_assign(&HashMap::from([("kick", true), ("ban", true)]));
+//! assert_eq!(*invalidated.borrow(), [ChangedKey::Key("ban")]);
+//! ```
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use core::ops::Deref;
+
+use crate::Set;
+use crate::delta::Delta;
+use crate::operations::{
+    DifferenceAssign, DisjunctiveUnionAssign, IntersectionAssign, UnionAssign,
+};
+
+type Observer<T> = Box<dyn FnMut(&T)>;
+
+/// A set that calls its observers with a [`Delta`] of what changed whenever one of its `*_assign` operations changes it.
+///
+/// What changed is computed as the [disjunctive union](DisjunctiveUnionAssign) of the set before and after the operation, so observers are not called for operations that leave the set as it was. The set is only cloned for operations while there are observers.
+pub struct Observed<S> {
+    set: S,
+    observers: Vec<Observer<Delta<S>>>,
+    key_observers: Vec<Observer<S>>,
+}
+
+impl<S> Observed<S> {
+    pub fn new(set: S) -> Self {
+        Self {
+            set,
+            observers: Vec::new(),
+            key_observers: Vec::new(),
+        }
+    }
+
+    pub fn get(&self) -> &S {
+        &self.set
+    }
+
+    pub fn into_inner(self) -> S {
+        self.set
+    }
+
+    /// Registers `observer` to be called with what was added and removed on every change.
+    pub fn on_change(&mut self, observer: impl FnMut(&Delta<S>) + 'static) {
+        self.observers.push(Box::new(observer));
+    }
+
+    /// Registers `observer` to be called once for every key whose value may have changed on every change.
+    pub fn on_key_change(&mut self, mut observer: impl FnMut(ChangedKey<&S::Key>) + 'static)
+    where
+        S: ChangedKeys,
+    {
+        self.key_observers.push(Box::new(move |changed: &S| {
+            changed.changed_keys(&mut observer)
+        }));
+    }
+
+    fn has_observers(&self) -> bool {
+        !self.observers.is_empty() || !self.key_observers.is_empty()
+    }
+}
+
+impl<S: Set + Clone> Observed<S>
+where
+    for<'a> S: DisjunctiveUnionAssign<&'a S> + IntersectionAssign<&'a S>,
+{
+    /// Modifies the set with `f` and notifies the observers of what changed.
+    pub fn update(&mut self, f: impl FnOnce(&mut S)) {
+        let before = self.has_observers().then(|| self.set.clone());
+
+        f(&mut self.set);
+
+        if let Some(before) = before {
+            self.notify(before);
+        }
+    }
+
+    fn notify(&mut self, mut before: S) {
+        let mut changed = before.clone();
+        changed.disjunctive_union_assign(&self.set);
+
+        if changed.is_empty() {
+            return;
+        }
+
+        let mut added = changed.clone();
+        added.intersection_assign(&self.set);
+        before.intersection_assign(&changed);
+
+        let delta = Delta {
+            added,
+            removed: before,
+        };
+
+        for observer in &mut self.observers {
+            observer(&delta);
+        }
+
+        for observer in &mut self.key_observers {
+            observer(&changed);
+        }
+    }
+}
+
+impl<S: Set<Empty = S>> Set for Observed<S> {
+    type Empty = Self;
+
+    fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    fn empty() -> Self {
+        Self::new(S::empty())
+    }
+}
+
+impl<S> Deref for Observed<S> {
+    type Target = S;
+
+    fn deref(&self) -> &S {
+        &self.set
+    }
+}
+
+impl<S: Debug> Debug for Observed<S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Observed")
+            .field("set", &self.set)
+            .field(
+                "observers",
+                &(self.observers.len() + self.key_observers.len()),
+            )
+            .finish()
+    }
+}
+
+macro_rules! impl_observed_operation {
+    ($trait:ident, $func:ident) => {
+        impl<S, Rhs> $trait<Rhs> for Observed<S>
+        where
+            S: $trait<Rhs> + Set<Empty = S> + Clone,
+            for<'a> S: DisjunctiveUnionAssign<&'a S> + IntersectionAssign<&'a S>,
+        {
+            fn $func(&mut self, rhs: Rhs) {
+                self.update(|set| set.$func(rhs));
+            }
+        }
+    };
+}
+
+impl_observed_operation!(UnionAssign, union_assign);
+impl_observed_operation!(DifferenceAssign, difference_assign);
+impl_observed_operation!(IntersectionAssign, intersection_assign);
+impl_observed_operation!(DisjunctiveUnionAssign, disjunctive_union_assign);
+
+/// A key whose value may have changed, as reported by [`ChangedKeys`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChangedKey<Key> {
+    /// The value of this key changed.
+    Key(Key),
+    /// The wildcard value changed, so the value of every key may have changed.
+    Every,
+}
+
+impl<Key: Clone> ChangedKey<&Key> {
+    pub fn cloned(self) -> ChangedKey<Key> {
+        match self {
+            ChangedKey::Key(key) => ChangedKey::Key(key.clone()),
+            ChangedKey::Every => ChangedKey::Every,
+        }
+    }
+}
+
+/// [`ChangedKeys`] lists the keys of a keyed set that are affected by a change, where the change is the disjunctive union of the set before and after.
+///
+/// This is what [`Observed::on_key_change`] uses to report changes per key.
+pub trait ChangedKeys {
+    type Key;
+
+    fn changed_keys(&self, f: impl FnMut(ChangedKey<&Self::Key>));
+}
+
+macro_rules! impl_changed_keys_map {
+    ($map:ident, $($key_bounds:tt)+) => {
+        impl<Key: $($key_bounds)+, Value> ChangedKeys for $map<Key, Value> {
+            type Key = Key;
+
+            fn changed_keys(&self, f: impl FnMut(ChangedKey<&Key>)) {
+                self.keys().map(ChangedKey::Key).for_each(f);
+            }
+        }
+    };
+}
+
+macro_rules! impl_changed_keys_wildcard_map {
+    ($map:ident, $($key_bounds:tt)+) => {
+        impl<Key: $($key_bounds)+, Value: Set<Empty = Value>> ChangedKeys for $map<Key, Value> {
+            type Key = Key;
+
+            fn changed_keys(&self, mut f: impl FnMut(ChangedKey<&Key>)) {
+                // Every key has the wildcard value unless excepted, so any change to it may change every key.
+                if !self.wildcard_value().is_empty() {
+                    f(ChangedKey::Every);
+                    return;
+                }
+
+                self.rest_list().keys().map(ChangedKey::Key).for_each(f);
+            }
+        }
+    };
+}
+
+#[cfg(feature = "std")]
+mod std_impls {
+    use core::hash::Hash;
+    use std::collections::HashMap;
+
+    use super::{ChangedKey, ChangedKeys};
+    use crate::Set;
+    use crate::collections::WildcardHashMap;
+
+    impl_changed_keys_map!(HashMap, Hash + Eq);
+    impl_changed_keys_wildcard_map!(WildcardHashMap, Hash + Eq + Clone);
+}
+
+mod alloc_impls {
+    use alloc::collections::BTreeMap;
+
+    use super::{ChangedKey, ChangedKeys};
+    use crate::Set;
+    use crate::collections::WildcardBTreeMap;
+
+    impl_changed_keys_map!(BTreeMap, Ord);
+    impl_changed_keys_wildcard_map!(WildcardBTreeMap, Ord + Clone);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::rc::Rc;
+
+    use maplit::hashmap;
+    use rstest::*;
+
+    #[allow(unused_imports)]
+    use super::*;
+    use crate::collections::WildcardHashMap;
+
+    type Map = HashMap<i32, bool>;
+    type Deltas = Rc<RefCell<Vec<Delta<Map>>>>;
+
+    /// An observed map that records every delta it is notified of.
+    fn recorded(set: Map) -> (Observed<Map>, Deltas) {
+        let deltas = Rc::new(RefCell::new(Vec::new()));
+        let mut observed = Observed::new(set);
+        observed.on_change({
+            let deltas = deltas.clone();
+            move |delta| deltas.borrow_mut().push(delta.clone())
+        });
+
+        (observed, deltas)
+    }
+
+    #[rstest]
+    #[case::added(hashmap! { 1 => true }, hashmap! { 1 => true, 2 => true }, vec![Delta::new(hashmap! { 2 => true }, hashmap! {})])]
+    #[case::unchanged(hashmap! { 1 => true }, hashmap! { 1 => true }, vec![])]
+    fn union_assign_tests(#[case] set: Map, #[case] rhs: Map, #[case] expected: Vec<Delta<Map>>) {
+        let (mut observed, deltas) = recorded(set);
+        observed.union_assign(&rhs);
+
+        assert_eq!(*deltas.borrow(), expected);
+    }
+
+    #[test]
+    fn operations_test() {
+        let (mut observed, deltas) = recorded(hashmap! { 1 => true, 2 => true });
+
+        observed.difference_assign(&hashmap! { 1 => true });
+        observed.intersection_assign(&hashmap! { 3 => true });
+        observed.disjunctive_union_assign(&hashmap! { 3 => true });
+
+        assert_eq!(observed.get(), &hashmap! { 3 => true });
+        assert_eq!(
+            *deltas.borrow(),
+            [
+                Delta::new(hashmap! {}, hashmap! { 1 => true }),
+                Delta::new(hashmap! {}, hashmap! { 2 => true }),
+                Delta::new(hashmap! { 3 => true }, hashmap! {}),
+            ]
+        );
+    }
+
+    #[test]
+    fn key_change_test() {
+        let keys = Rc::new(RefCell::new(Vec::new()));
+        let mut observed = Observed::new(WildcardHashMap::<i32, bool>::new(false));
+        observed.on_key_change({
+            let keys = keys.clone();
+            move |key| keys.borrow_mut().push(key.cloned())
+        });
+
+        observed.union_assign(&WildcardHashMap::from(hashmap! { 1 => true }));
+        observed.union_assign(&WildcardHashMap::new(true));
+        observed.difference_assign(&WildcardHashMap::from(hashmap! { 2 => true }));
+
+        assert_eq!(
+            *keys.borrow(),
+            [ChangedKey::Key(1), ChangedKey::Every, ChangedKey::Key(2)]
+        );
+    }
+}
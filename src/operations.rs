@@ -1,11 +1,16 @@
 //! This module contains traits for performing operations on sets, such as [`Union`], [`Intersection`], [`Difference`], [`DisjunctiveUnion`], etc.
 
+use core::convert::Infallible;
+use core::fmt::Display;
+
 use crate::Set;
 
 #[cfg(feature = "derive")]
 pub use finit_derive::{
     Difference, DifferenceAssign, DisjunctiveUnion, DisjunctiveUnionAssign, Intersection,
-    IntersectionAssign, Union, UnionAssign,
+    IntersectionAssign, TryDifference, TryDifferenceAssign, TryDisjunctiveUnion,
+    TryDisjunctiveUnionAssign, TryIntersection, TryIntersectionAssign, TryUnion, TryUnionAssign,
+    Union, UnionAssign,
 };
 
 /// [`Union`] (denoted by ∪) is the sum of two sets. Union is a symmetric relation, which means that A ∪ B must equal B ∪ A.
@@ -56,6 +61,280 @@ pub trait DisjunctiveUnionAssign<Rhs = Self>: Set {
     fn disjunctive_union_assign(&mut self, rhs: Rhs);
 }
 
+/// [`TryUnion`] is the fallible version of the [`Union`] trait, for sets that can fail to hold the union, such as fixed-capacity collections.
+///
+/// It is implemented with [`Infallible`] as the error for every set that implements [`Union`].
+pub trait TryUnion<Rhs = Self>: Set {
+    type Output;
+    type Error;
+
+    fn try_union(self, rhs: Rhs) -> Result<Self::Output, Self::Error>;
+}
+
+/// [`TryUnionAssign`] is the in-place version of the [`TryUnion`] trait.
+///
+/// It is implemented with [`Infallible`] as the error for every set that implements [`UnionAssign`].
+pub trait TryUnionAssign<Rhs = Self>: Set {
+    type Error;
+
+    fn try_union_assign(&mut self, rhs: Rhs) -> Result<(), Self::Error>;
+}
+
+/// [`TryDifference`] is the fallible version of the [`Difference`] trait.
+///
+/// It is implemented with [`Infallible`] as the error for every set that implements [`Difference`].
+pub trait TryDifference<Rhs = Self>: Set {
+    type Output;
+    type Error;
+
+    fn try_difference(self, rhs: Rhs) -> Result<Self::Output, Self::Error>;
+}
+
+/// [`TryDifferenceAssign`] is the in-place version of the [`TryDifference`] trait.
+///
+/// It is implemented with [`Infallible`] as the error for every set that implements [`DifferenceAssign`].
+pub trait TryDifferenceAssign<Rhs = Self>: Set {
+    type Error;
+
+    fn try_difference_assign(&mut self, rhs: Rhs) -> Result<(), Self::Error>;
+}
+
+/// [`TryIntersection`] is the fallible version of the [`Intersection`] trait.
+///
+/// It is implemented with [`Infallible`] as the error for every set that implements [`Intersection`].
+pub trait TryIntersection<Rhs = Self>: Set {
+    type Output;
+    type Error;
+
+    fn try_intersection(self, rhs: Rhs) -> Result<Self::Output, Self::Error>;
+}
+
+/// [`TryIntersectionAssign`] is the in-place version of the [`TryIntersection`] trait.
+///
+/// It is implemented with [`Infallible`] as the error for every set that implements [`IntersectionAssign`].
+pub trait TryIntersectionAssign<Rhs = Self>: Set {
+    type Error;
+
+    fn try_intersection_assign(&mut self, rhs: Rhs) -> Result<(), Self::Error>;
+}
+
+/// [`TryDisjunctiveUnion`] is the fallible version of the [`DisjunctiveUnion`] trait.
+///
+/// It is implemented with [`Infallible`] as the error for every set that implements [`DisjunctiveUnion`].
+pub trait TryDisjunctiveUnion<Rhs = Self>: Set {
+    type Output;
+    type Error;
+
+    fn try_disjunctive_union(self, rhs: Rhs) -> Result<Self::Output, Self::Error>;
+}
+
+/// [`TryDisjunctiveUnionAssign`] is the in-place version of the [`TryDisjunctiveUnion`] trait.
+///
+/// It is implemented with [`Infallible`] as the error for every set that implements [`DisjunctiveUnionAssign`].
+pub trait TryDisjunctiveUnionAssign<Rhs = Self>: Set {
+    type Error;
+
+    fn try_disjunctive_union_assign(&mut self, rhs: Rhs) -> Result<(), Self::Error>;
+}
+
+macro_rules! impl_infallible {
+    ($trait:ident, $func:ident, $try_trait:ident, $try_func:ident) => {
+        impl<T: $trait<Rhs>, Rhs> $try_trait<Rhs> for T {
+            type Output = T::Output;
+            type Error = Infallible;
+
+            fn $try_func(self, rhs: Rhs) -> Result<Self::Output, Infallible> {
+                Ok(self.$func(rhs))
+            }
+        }
+    };
+    (assign $trait:ident, $func:ident, $try_trait:ident, $try_func:ident) => {
+        impl<T: $trait<Rhs>, Rhs> $try_trait<Rhs> for T {
+            type Error = Infallible;
+
+            fn $try_func(&mut self, rhs: Rhs) -> Result<(), Infallible> {
+                self.$func(rhs);
+
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_infallible!(Union, union, TryUnion, try_union);
+impl_infallible!(assign UnionAssign, union_assign, TryUnionAssign, try_union_assign);
+impl_infallible!(Difference, difference, TryDifference, try_difference);
+impl_infallible!(assign DifferenceAssign, difference_assign, TryDifferenceAssign, try_difference_assign);
+impl_infallible!(Intersection, intersection, TryIntersection, try_intersection);
+impl_infallible!(assign IntersectionAssign, intersection_assign, TryIntersectionAssign, try_intersection_assign);
+impl_infallible!(DisjunctiveUnion, disjunctive_union, TryDisjunctiveUnion, try_disjunctive_union);
+impl_infallible!(assign DisjunctiveUnionAssign, disjunctive_union_assign, TryDisjunctiveUnionAssign, try_disjunctive_union_assign);
+
+/// The number of field names a [`FieldError`] keeps in its path.
+pub const FIELD_PATH_DEPTH: usize = 8;
+
+/// [`FieldError`] is the error of derived fallible operations. It contains the error of the field whose operation failed, together with the path of field names leading to it.
+///
+/// The derived operations short-circuit on the first failing field, so with the assigning operations the fields before it have already been changed.
+///
+/// The path is stored inline so the error needs no allocator. It keeps the outermost [`FIELD_PATH_DEPTH`] field names, so for deeper nesting the innermost names are dropped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError<E> {
+    path: [&'static str; FIELD_PATH_DEPTH],
+    depth: usize,
+    error: E,
+}
+
+impl<E> FieldError<E> {
+    /// An error that has not been attributed to a field yet.
+    pub fn new(error: E) -> Self {
+        Self {
+            path: [""; FIELD_PATH_DEPTH],
+            depth: 0,
+            error,
+        }
+    }
+
+    /// Prepends `field` to the path, as the error is passed to the struct containing the field.
+    pub fn in_field(mut self, field: &'static str) -> Self {
+        self.path.copy_within(..FIELD_PATH_DEPTH - 1, 1);
+        self.path[0] = field;
+        self.depth = (self.depth + 1).min(FIELD_PATH_DEPTH);
+        self
+    }
+
+    /// The field names from the outermost struct to the field whose operation failed.
+    pub fn path(&self) -> &[&'static str] {
+        &self.path[..self.depth]
+    }
+
+    pub fn error(&self) -> &E {
+        &self.error
+    }
+
+    pub fn into_inner(self) -> E {
+        self.error
+    }
+}
+
+impl<E: Display> Display for FieldError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (index, field) in self.path().iter().enumerate() {
+            if index > 0 {
+                f.write_str(".")?;
+            }
+
+            f.write_str(field)?;
+        }
+
+        write!(f, ": {}", self.error)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: std::error::Error + 'static> std::error::Error for FieldError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// [`IntoFieldError`] converts the error of a field's fallible operation into the [`FieldError`] of the derived operation of the containing struct.
+///
+/// Derived operations use `#[finit(error = E)]` on the struct to choose `E`. To use a custom error type in fields, implement this trait for it, usually with [`FieldError::new`].
+pub trait IntoFieldError<E> {
+    fn into_field_error(self, field: &'static str) -> FieldError<E>;
+}
+
+impl<E> IntoFieldError<E> for FieldError<E> {
+    fn into_field_error(self, field: &'static str) -> FieldError<E> {
+        self.in_field(field)
+    }
+}
+
+impl<E> IntoFieldError<E> for Infallible {
+    fn into_field_error(self, _field: &'static str) -> FieldError<E> {
+        match self {}
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<E: From<crate::collections::CapacityError>> IntoFieldError<E> for crate::collections::CapacityError {
+    fn into_field_error(self, field: &'static str) -> FieldError<E> {
+        FieldError::new(self.into()).in_field(field)
+    }
+}
+
+/// [`SetIterator`] folds an iterator of sets into a single set using the assigning operations, which is implemented for all iterators.
+///
+/// ```
+/// # use std::collections::HashMap;
+/// # use finit::operations::SetIterator;
+/// let roles = [
+///     HashMap::from([("kick", true), ("ban", true)]),
+///     HashMap::from([("kick", true), ("mute", true)]),
+/// ];
+///
+/// assert_eq!(roles.clone().into_iter().union_all().len(), 3);
+/// assert_eq!(roles.into_iter().intersection_all(), HashMap::from([("kick", true)]));
+/// ```
+pub trait SetIterator: Iterator + Sized {
+    /// The union of all sets. The union of no sets is the empty set.
+    fn union_all(self) -> Self::Item
+    where
+        Self::Item: Set<Empty = Self::Item>,
+        for<'a> Self::Item: UnionAssign<&'a Self::Item>,
+    {
+        self.fold(Self::Item::empty(), |mut set, other| {
+            set.union_assign(&other);
+            set
+        })
+    }
+
+    /// The intersection of all sets, starting from the first set. The intersection of no sets is the empty set.
+    fn intersection_all(mut self) -> Self::Item
+    where
+        Self::Item: Set<Empty = Self::Item>,
+        for<'a> Self::Item: IntersectionAssign<&'a Self::Item>,
+    {
+        let Some(mut set) = self.next() else {
+            return Self::Item::empty();
+        };
+
+        for other in self {
+            if set.is_empty() {
+                break;
+            }
+
+            set.intersection_assign(&other);
+        }
+
+        set
+    }
+
+    /// The first set without all of the other sets. The difference of no sets is the empty set.
+    fn difference_all(mut self) -> Self::Item
+    where
+        Self::Item: Set<Empty = Self::Item>,
+        for<'a> Self::Item: DifferenceAssign<&'a Self::Item>,
+    {
+        let Some(mut set) = self.next() else {
+            return Self::Item::empty();
+        };
+
+        for other in self {
+            if set.is_empty() {
+                break;
+            }
+
+            set.difference_assign(&other);
+        }
+
+        set
+    }
+}
+
+impl<I: Iterator> SetIterator for I {}
+
 /// The [`identity`] submodule contains identities that can be used to implement some operations in terms of others.
 pub mod identity {
     /// A ∩ B = A - (A - B)
@@ -0,0 +1,516 @@
+//! This module contains [`TriState`], a permission value that can be explicitly denied instead of only not granted, and [`Overlay`], which stacks layers of sets where higher layers override lower ones, like role overwrites in chat platforms.
+//!
+//! ```
+//! # use std::collections::HashMap;
+//! # use finit::overlay::{Overlay, TriState};
+//! let mut overlay = Overlay::new();
+//! overlay.push(HashMap::from([("send", TriState::Allow), ("attach", TriState::Allow)]));
+//! overlay.push(HashMap::from([("attach", TriState::Deny)]));
+//!
+//! assert_eq!(overlay.resolve(), HashMap::from([("send", true)]));
+//! ```
+use alloc::vec::Vec;
+
+use crate::Set;
+use crate::operations::{
+    Difference, DifferenceAssign, DisjunctiveUnion, DisjunctiveUnionAssign, Intersection,
+    IntersectionAssign, Union, UnionAssign,
+};
+
+/// A value that is either unset, explicitly allowed or explicitly denied.
+///
+/// As a set, [`TriState::Allow`] and [`TriState::Deny`] are two different elements that are not subsets of each other, and unset is the empty set:
+/// - The intersection and the difference treat them as disjoint, so `Allow ∩ Deny` is unset and `Allow − Deny` is [`TriState::Allow`]. Holding a deny therefore never satisfies a required allow.
+/// - Since a value can not be both, the union of the two is [`TriState::Deny`], so denies win within a single layer. Use [`Overlay`] to let higher layers override lower ones instead.
+///
+/// Because of the last rule the union is not an upper bound: `Allow ∪ Deny` does not contain [`TriState::Allow`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum TriState {
+    #[default]
+    Unset,
+    Allow,
+    Deny,
+}
+
+impl Set for TriState {
+    type Empty = Self;
+
+    fn is_empty(&self) -> bool {
+        *self == TriState::Unset
+    }
+
+    fn empty() -> Self::Empty {
+        TriState::Unset
+    }
+}
+
+macro_rules! impl_tri_state_operation {
+    ($trait:ident, $func:ident, $assign_trait:ident, $assign_func:ident, |$lhs:ident, $rhs:ident| $body:expr) => {
+        impl $trait<TriState> for TriState {
+            type Output = TriState;
+
+            fn $func(self, rhs: TriState) -> Self::Output {
+                let ($lhs, $rhs) = (self, rhs);
+                $body
+            }
+        }
+
+        impl $trait<&TriState> for TriState {
+            type Output = TriState;
+
+            fn $func(self, rhs: &TriState) -> Self::Output {
+                self.$func(*rhs)
+            }
+        }
+
+        impl $assign_trait<TriState> for TriState {
+            fn $assign_func(&mut self, rhs: TriState) {
+                *self = self.$func(rhs);
+            }
+        }
+
+        impl $assign_trait<&TriState> for TriState {
+            fn $assign_func(&mut self, rhs: &TriState) {
+                *self = self.$func(*rhs);
+            }
+        }
+    };
+}
+
+impl_tri_state_operation!(Union, union, UnionAssign, union_assign, |lhs, rhs| {
+    match (lhs, rhs) {
+        (TriState::Unset, value) | (value, TriState::Unset) => value,
+        (TriState::Allow, TriState::Allow) => TriState::Allow,
+        _ => TriState::Deny,
+    }
+});
+impl_tri_state_operation!(
+    Difference,
+    difference,
+    DifferenceAssign,
+    difference_assign,
+    |lhs, rhs| if lhs == rhs { TriState::Unset } else { lhs }
+);
+impl_tri_state_operation!(
+    Intersection,
+    intersection,
+    IntersectionAssign,
+    intersection_assign,
+    |lhs, rhs| if lhs == rhs { lhs } else { TriState::Unset }
+);
+impl_tri_state_operation!(
+    DisjunctiveUnion,
+    disjunctive_union,
+    DisjunctiveUnionAssign,
+    disjunctive_union_assign,
+    |lhs, rhs| lhs.difference(rhs).union(rhs.difference(lhs))
+);
+
+crate::set_eq_partial_eq_impl!(TriState);
+
+crate::subset_of_intersection_identity_impl!(TriState);
+
+/// [`Override`] replaces the parts of a set that a higher priority layer sets, which is how [`Overlay`] combines its layers.
+pub trait Override {
+    fn override_with(&mut self, higher: &Self);
+}
+
+/// [`Resolve`] turns a set of [`TriState`] values into the plain set it grants, where only allowed values are granted.
+pub trait Resolve {
+    type Resolved;
+
+    fn resolve(&self) -> Self::Resolved;
+}
+
+impl Override for TriState {
+    fn override_with(&mut self, higher: &Self) {
+        if !higher.is_empty() {
+            *self = *higher;
+        }
+    }
+}
+
+impl Resolve for TriState {
+    type Resolved = bool;
+
+    fn resolve(&self) -> bool {
+        *self == TriState::Allow
+    }
+}
+
+macro_rules! impl_overlay_map {
+    ($map:ident, $($key_bounds:tt)+) => {
+        impl<Key: $($key_bounds)+, Value: Set<Empty = Value> + Override + Clone> Override for $map<Key, Value> {
+            fn override_with(&mut self, higher: &Self) {
+                for (key, value) in higher {
+                    self.entry(key.clone())
+                        .or_insert_with(Value::empty)
+                        .override_with(value);
+                }
+
+                self.retain(|_, value| !value.is_empty());
+            }
+        }
+
+        impl<Key: $($key_bounds)+, Value: Resolve> Resolve for $map<Key, Value>
+        where
+            Value::Resolved: Set,
+        {
+            type Resolved = $map<Key, Value::Resolved>;
+
+            fn resolve(&self) -> Self::Resolved {
+                self.iter()
+                    .map(|(key, value)| (key.clone(), value.resolve()))
+                    .filter(|(_, value)| !value.is_empty())
+                    .collect()
+            }
+        }
+    };
+}
+
+/// Builds a wildcard map where every key in `values` has exactly its value, and every other key has `wildcard_value`.
+macro_rules! wildcard_map_from_values {
+    ($wildcard_map:ident, $map:ident, $wildcard_value:expr, $values:expr) => {{
+        let wildcard_value = $wildcard_value;
+        let mut exceptions = $map::new();
+        let mut rest_list = $map::new();
+
+        for (key, value) in $values {
+            let mut exception = wildcard_value.clone();
+            exception.difference_assign(&value);
+            if !exception.is_empty() {
+                exceptions.insert(key.clone(), exception);
+            }
+
+            let mut rest_value = value;
+            rest_value.difference_assign(&wildcard_value);
+            if !rest_value.is_empty() {
+                rest_list.insert(key, rest_value);
+            }
+        }
+
+        let mut map = $wildcard_map::new(wildcard_value);
+        map.difference_assign(&exceptions);
+        map.union_assign(&rest_list);
+        map
+    }};
+}
+
+macro_rules! impl_overlay_wildcard_map {
+    ($wildcard_map:ident, $map:ident, $($key_bounds:tt)+) => {
+        impl<Key: $($key_bounds)+, Value> Override for $wildcard_map<Key, Value>
+        where
+            Value: Set<Empty = Value> + Override + Clone,
+            for<'a> Value: DifferenceAssign<&'a Value> + UnionAssign<&'a Value> + IntersectionAssign<&'a Value>,
+        {
+            fn override_with(&mut self, higher: &Self) {
+                let mut wildcard_value = self.wildcard_value().clone();
+                wildcard_value.override_with(higher.wildcard_value());
+
+                let keys = self
+                    .wildcard_exceptions()
+                    .keys()
+                    .chain(self.rest_list().keys())
+                    .chain(higher.wildcard_exceptions().keys())
+                    .chain(higher.rest_list().keys())
+                    .map(|key| (key.clone(), ()))
+                    .collect::<$map<_, _>>();
+
+                *self = wildcard_map_from_values!(
+                    $wildcard_map,
+                    $map,
+                    wildcard_value,
+                    keys.into_keys().map(|key| {
+                        let mut value = self.value_of(&key);
+                        value.override_with(&higher.value_of(&key));
+                        (key, value)
+                    })
+                );
+            }
+        }
+
+        impl<Key: $($key_bounds)+, Value> Resolve for $wildcard_map<Key, Value>
+        where
+            Value: Set<Empty = Value> + Resolve + Clone,
+            for<'a> Value: DifferenceAssign<&'a Value> + UnionAssign<&'a Value>,
+            Value::Resolved: Set<Empty = Value::Resolved> + Clone,
+            for<'a> Value::Resolved: DifferenceAssign<&'a Value::Resolved>
+                + UnionAssign<&'a Value::Resolved>
+                + IntersectionAssign<&'a Value::Resolved>,
+        {
+            type Resolved = $wildcard_map<Key, Value::Resolved>;
+
+            fn resolve(&self) -> Self::Resolved {
+                wildcard_map_from_values!(
+                    $wildcard_map,
+                    $map,
+                    self.wildcard_value().resolve(),
+                    self.wildcard_exceptions()
+                        .keys()
+                        .chain(self.rest_list().keys())
+                        .map(|key| (key.clone(), self.value_of(key).resolve()))
+                )
+            }
+        }
+    };
+}
+
+#[cfg(feature = "std")]
+mod std_impls {
+    use core::hash::Hash;
+    use std::collections::HashMap;
+
+    use super::{Override, Resolve};
+    use crate::Set;
+    use crate::collections::WildcardHashMap;
+    use crate::operations::{DifferenceAssign, IntersectionAssign, UnionAssign};
+
+    impl_overlay_map!(HashMap, Hash + Eq + Clone);
+    impl_overlay_wildcard_map!(WildcardHashMap, HashMap, Hash + Eq + Clone);
+}
+
+mod alloc_impls {
+    use alloc::collections::BTreeMap;
+
+    use super::{Override, Resolve};
+    use crate::Set;
+    use crate::collections::WildcardBTreeMap;
+    use crate::operations::{DifferenceAssign, IntersectionAssign, UnionAssign};
+
+    impl_overlay_map!(BTreeMap, Ord + Clone);
+    impl_overlay_wildcard_map!(WildcardBTreeMap, BTreeMap, Ord + Clone);
+}
+
+/// A stack of layers, from the lowest priority to the highest, where every layer overrides what the layers below it set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct Overlay<S> {
+    layers: Vec<S>,
+}
+
+impl<S> Overlay<S> {
+    pub fn new() -> Self {
+        Self { layers: Vec::new() }
+    }
+
+    /// Adds `layer` on top of every other layer.
+    pub fn push(&mut self, layer: S) {
+        self.layers.push(layer);
+    }
+
+    /// The layers, from the lowest priority to the highest.
+    pub fn layers(&self) -> &[S] {
+        &self.layers
+    }
+}
+
+impl<S: Set<Empty = S> + Override> Overlay<S> {
+    /// The single layer that has the same effect as all layers.
+    pub fn flatten(&self) -> S {
+        let mut flattened = S::empty();
+
+        for layer in &self.layers {
+            flattened.override_with(layer);
+        }
+
+        flattened
+    }
+
+    /// The plain set that all layers grant together.
+    pub fn resolve(&self) -> S::Resolved
+    where
+        S: Resolve,
+    {
+        self.flatten().resolve()
+    }
+}
+
+impl<S> Default for Overlay<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> FromIterator<S> for Overlay<S> {
+    /// Stacks the layers in order, so the last one has the highest priority.
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        Self {
+            layers: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use maplit::hashmap;
+    use rstest::*;
+
+    #[allow(unused_imports)]
+    use super::*;
+    use crate::collections::WildcardHashMap;
+    use crate::comparisons::SubsetOf;
+    use crate::requirements::Require;
+
+    use TriState::{Allow, Deny, Unset};
+
+    #[rstest]
+    #[case(Unset, Allow, Allow)]
+    #[case(Allow, Allow, Allow)]
+    #[case(Allow, Deny, Deny)]
+    #[case(Deny, Unset, Deny)]
+    fn union_tests(#[case] a: TriState, #[case] b: TriState, #[case] result: TriState) {
+        assert_eq!(a.union(b), result);
+        assert_eq!(b.union(a), result);
+    }
+
+    #[rstest]
+    #[case(Allow, Allow, Unset)]
+    #[case(Allow, Deny, Allow)]
+    #[case(Deny, Allow, Deny)]
+    #[case(Deny, Unset, Deny)]
+    #[case(Unset, Deny, Unset)]
+    fn difference_tests(#[case] a: TriState, #[case] b: TriState, #[case] result: TriState) {
+        assert_eq!(a.difference(b), result);
+    }
+
+    #[rstest]
+    #[case(Allow, Allow, Allow)]
+    #[case(Allow, Deny, Unset)]
+    #[case(Deny, Unset, Unset)]
+    fn intersection_tests(#[case] a: TriState, #[case] b: TriState, #[case] result: TriState) {
+        assert_eq!(a.intersection(b), result);
+        assert_eq!(b.intersection(a), result);
+    }
+
+    #[rstest]
+    #[case(Allow, Allow, Unset)]
+    #[case(Allow, Deny, Deny)]
+    #[case(Unset, Deny, Deny)]
+    fn disjunctive_union_tests(#[case] a: TriState, #[case] b: TriState, #[case] result: TriState) {
+        assert_eq!(a.disjunctive_union(b), result);
+    }
+
+    #[rstest]
+    #[case(Unset, Deny, true)]
+    #[case(Allow, Allow, true)]
+    #[case(Allow, Deny, false)]
+    #[case(Deny, Allow, false)]
+    #[case(Allow, Unset, false)]
+    fn subset_of_tests(#[case] a: TriState, #[case] b: TriState, #[case] expected: bool) {
+        assert_eq!(a.subset_of(&b), expected);
+
+        // Holding `b` satisfies a requirement for `a` exactly when `a` is a subset of it, so a deny never passes for an allow.
+        let held = hashmap! { "ban" => b };
+        assert_eq!(held.require(hashmap! { "ban" => a }).is_ok(), expected);
+    }
+
+    const ALL: [TriState; 3] = [Unset, Allow, Deny];
+
+    #[test]
+    fn set_laws_test() {
+        for a in ALL {
+            assert_eq!(a.union(a), a);
+            assert_eq!(a.intersection(a), a);
+            assert_eq!(a.difference(a), Unset);
+            for b in ALL {
+                assert_eq!(a.union(b), b.union(a));
+                assert_eq!(a.intersection(b), b.intersection(a));
+                assert_eq!(
+                    a.union(a.intersection(b)),
+                    a,
+                    "absorption of {a:?} and {b:?}"
+                );
+                assert!(a.intersection(b).subset_of(&a));
+                assert!(a.difference(b).subset_of(&a));
+                assert_eq!(a.difference(b).intersection(b), Unset);
+                assert_eq!(a.subset_of(&b), a.difference(b).is_empty());
+                assert_eq!(
+                    a.disjunctive_union(b),
+                    a.difference(b).union(b.difference(a))
+                );
+                for c in ALL {
+                    assert_eq!(a.union(b).union(c), a.union(b.union(c)));
+                    assert_eq!(
+                        a.intersection(b).intersection(c),
+                        a.intersection(b.intersection(c))
+                    );
+                }
+            }
+        }
+
+        // A deny overrides an allow, so the union is not an upper bound.
+        assert!(!Allow.subset_of(&Allow.union(Deny)));
+    }
+
+    #[test]
+    fn hashmap_overlay_test() {
+        let overlay: Overlay<HashMap<&str, TriState>> = [
+            hashmap! { "send" => Allow, "attach" => Allow, "kick" => Deny },
+            hashmap! { "attach" => Deny, "kick" => Allow },
+            hashmap! { "send" => Unset },
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            overlay.flatten(),
+            hashmap! { "send" => Allow, "attach" => Deny, "kick" => Allow }
+        );
+        assert_eq!(
+            overlay.resolve(),
+            hashmap! { "send" => true, "kick" => true }
+        );
+    }
+
+    #[test]
+    fn wildcard_overlay_test() {
+        let mut everyone = WildcardHashMap::new(Allow);
+        everyone.difference_assign(&hashmap! { "private" => Allow });
+
+        let mut moderators = WildcardHashMap::new(Unset);
+        moderators.union_assign(&hashmap! { "private" => Allow, "announcements" => Deny });
+
+        let overlay: Overlay<_> = [everyone, moderators].into_iter().collect();
+        let flattened = overlay.flatten();
+
+        assert_eq!(flattened.value_of(&"general"), Allow);
+        assert_eq!(flattened.value_of(&"private"), Allow);
+        assert_eq!(flattened.value_of(&"announcements"), Deny);
+
+        let resolved = overlay.resolve();
+
+        assert!(resolved.value_of(&"general"));
+        assert!(resolved.value_of(&"private"));
+        assert!(!resolved.value_of(&"announcements"));
+    }
+
+    #[test]
+    fn wildcard_deny_overlay_test() {
+        let everyone = WildcardHashMap::new(Deny);
+
+        let mut members = WildcardHashMap::new(Unset);
+        members.union_assign(&hashmap! { "general" => Allow });
+
+        let overlay: Overlay<_> = [everyone, members].into_iter().collect();
+        let flattened = overlay.flatten();
+
+        assert_eq!(flattened.value_of(&"general"), Allow);
+        assert_eq!(flattened.value_of(&"private"), Deny);
+
+        let resolved = overlay.resolve();
+
+        assert!(resolved.value_of(&"general"));
+        assert!(!resolved.value_of(&"private"));
+    }
+}
@@ -0,0 +1,590 @@
+//! This module contains parallel versions of operations and comparisons using [`rayon`], such as [`par_union_all`], [`par_intersection_all`] and [`ParSubsetOf`].
+//!
+//! [`par_union_all`] and [`par_intersection_all`] fold any parallel iterator of sets implementing [`ParUnion`] and [`ParIntersection`]. Maps split their entries by key, so the values of each key are combined on one thread with the existing [`UnionAssign`] and [`IntersectionAssign`] implementations, and merging thousands of role grants is spread over all threads.
+//!
+//! Wildcard maps only split their rest lists by key in a union. The wildcard values and their exceptions are still reduced pairwise, since the exception of a key in the union depends on the wildcard value of every map that does not mention the key, and their intersections are reduced pairwise as a whole.
+//!
+//! ```
+//! # use std::collections::HashMap;
+//! # use finit::parallel::{ParSubsetOf, par_union_all};
+//! let grants: Vec<HashMap<u32, bool>> = (0..1000).map(|role| HashMap::from([(role % 10, true)])).collect();
+//!
+//! let all = par_union_all(grants);
+//!
+//! assert_eq!(all.len(), 10);
+//! assert!(HashMap::from([(0, true)]).par_subset_of(&all));
+//! ```
+
+use std::collections::hash_map::{Entry, RandomState};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::hash::{BuildHasher, Hash};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rayon::prelude::*;
+
+use crate::Set;
+use crate::collections::{WildcardBTreeMap, WildcardHashMap};
+use crate::comparisons::SubsetOf;
+use crate::operations::{DifferenceAssign, IntersectionAssign, UnionAssign};
+
+/// The union of all sets, computed in parallel. The union of no sets is the empty set.
+pub fn par_union_all<S, I>(sets: I) -> S
+where
+    I: IntoParallelIterator<Item = S>,
+    S: ParUnion,
+{
+    S::par_union_all(sets)
+}
+
+/// The intersection of all sets, computed in parallel. The intersection of no sets is the empty set.
+pub fn par_intersection_all<S, I>(sets: I) -> S
+where
+    I: IntoParallelIterator<Item = S>,
+    S: ParIntersection,
+{
+    S::par_intersection_all(sets)
+}
+
+/// [`ParUnion`] is the parallel union of many sets behind [`par_union_all`].
+///
+/// By default the sets are reduced pairwise. [`HashMap`] and [`BTreeMap`] instead split their entries by key, so the values of every key are combined on one thread and no thread merges whole maps.
+/// [`WildcardHashMap`] and [`WildcardBTreeMap`] split their rest lists the same way and reduce the rest pairwise.
+/// Other sets can use the pairwise reduction with an empty `impl ParUnion for ... {}`.
+pub trait ParUnion: Set<Empty = Self> + Sized + Send + for<'a> UnionAssign<&'a Self> {
+    fn par_union_all<I: IntoParallelIterator<Item = Self>>(sets: I) -> Self {
+        sets.into_par_iter().reduce(Self::empty, |mut set, other| {
+            set.union_assign(&other);
+            set
+        })
+    }
+}
+
+/// [`ParIntersection`] is the parallel intersection of many sets behind [`par_intersection_all`].
+///
+/// By default the sets are reduced pairwise. [`HashMap`] and [`BTreeMap`] instead split their entries by key and keep the keys that every map has, with the intersection of their values.
+/// [`HashMap`] intersects hash shards of the maps pairwise, so the shards of two maps are intersected in parallel.
+/// Other sets can use the pairwise reduction with an empty `impl ParIntersection for ... {}`.
+pub trait ParIntersection:
+    Set<Empty = Self> + Sized + Send + for<'a> IntersectionAssign<&'a Self>
+{
+    fn par_intersection_all<I: IntoParallelIterator<Item = Self>>(sets: I) -> Self {
+        sets.into_par_iter()
+            .reduce_with(|mut set, other| {
+                if !set.is_empty() {
+                    set.intersection_assign(&other);
+                }
+                set
+            })
+            .unwrap_or_else(Self::empty)
+    }
+}
+
+/// Splits `map` into `shard_count` maps by the hash of its keys.
+fn hash_shards<Key: Hash + Eq, Value>(
+    map: HashMap<Key, Value>,
+    state: &RandomState,
+    shard_count: usize,
+) -> Vec<HashMap<Key, Value>> {
+    let mut shards: Vec<HashMap<Key, Value>> = (0..shard_count).map(|_| HashMap::new()).collect();
+    for (key, value) in map {
+        shards[state.hash_one(&key) as usize % shard_count].insert(key, value);
+    }
+    shards
+}
+
+/// Groups the entries of all maps by key with hash shards, folding the values of each key into an accumulator.
+///
+/// Every thread folds its maps into its own shards, after which each shard is merged across the threads on one thread.
+fn par_fold_hash_maps<Key, Value, Acc>(
+    maps: impl ParallelIterator<Item = HashMap<Key, Value>>,
+    init: impl Fn(Value) -> Acc + Sync,
+    fold: impl Fn(&mut Acc, Value) + Sync,
+    merge: impl Fn(&mut Acc, Acc) + Sync,
+) -> Vec<(Key, Acc)>
+where
+    Key: Hash + Eq + Send,
+    Value: Send,
+    Acc: Send,
+{
+    let state = RandomState::new();
+    let shard_count = rayon::current_num_threads() * 4;
+    let new_shards = || (0..shard_count).map(|_| HashMap::new()).collect::<Vec<_>>();
+
+    let partials: Vec<Vec<HashMap<Key, Acc>>> = maps
+        .fold(new_shards, |mut shards, map| {
+            for (key, value) in map {
+                let shard = &mut shards[state.hash_one(&key) as usize % shard_count];
+
+                match shard.entry(key) {
+                    Entry::Occupied(mut entry) => fold(entry.get_mut(), value),
+                    Entry::Vacant(entry) => {
+                        entry.insert(init(value));
+                    }
+                }
+            }
+
+            shards
+        })
+        .collect();
+
+    let mut by_shard: Vec<Vec<HashMap<Key, Acc>>> = (0..shard_count).map(|_| Vec::new()).collect();
+    for shards in partials {
+        for (index, shard) in shards.into_iter().enumerate() {
+            by_shard[index].push(shard);
+        }
+    }
+
+    by_shard
+        .into_par_iter()
+        .flat_map_iter(|shards| {
+            let mut shards = shards.into_iter();
+            let mut merged = shards.next().unwrap_or_default();
+
+            for shard in shards {
+                for (key, acc) in shard {
+                    match merged.entry(key) {
+                        Entry::Occupied(mut entry) => merge(entry.get_mut(), acc),
+                        Entry::Vacant(entry) => {
+                            entry.insert(acc);
+                        }
+                    }
+                }
+            }
+
+            merged
+        })
+        .collect()
+}
+
+/// Groups the entries of all maps by key with a parallel sort, folding the values of each key into an accumulator.
+///
+/// Also returns the number of maps, which the maps are counted for while their entries are gathered.
+fn par_fold_btree_maps<Key, Value, Acc>(
+    maps: impl ParallelIterator<Item = BTreeMap<Key, Value>>,
+    init: impl Fn(Value) -> Acc + Sync,
+    fold: impl Fn(&mut Acc, Value) + Sync,
+) -> (usize, Vec<(Key, Acc)>)
+where
+    Key: Ord + Clone + Send + Sync,
+    Value: Set<Empty = Value> + Send,
+    Acc: Send,
+{
+    let count = AtomicUsize::new(0);
+    let mut entries: Vec<(Key, Value)> = maps
+        .inspect(|_| {
+            count.fetch_add(1, Ordering::Relaxed);
+        })
+        .flat_map_iter(BTreeMap::into_iter)
+        .collect();
+    entries.par_sort_by(|(key, _), (other_key, _)| key.cmp(other_key));
+
+    let groups = entries
+        .par_chunk_by_mut(|(key, _), (other_key, _)| key == other_key)
+        .map(|group| {
+            let mut values = group
+                .iter_mut()
+                .map(|(_, value)| std::mem::replace(value, Value::empty()));
+            let mut acc = init(values.next().expect("Groups are never empty."));
+
+            for value in values {
+                fold(&mut acc, value);
+            }
+
+            (group[0].0.clone(), acc)
+        })
+        .collect();
+
+    (count.into_inner(), groups)
+}
+
+impl<Key, Value> ParUnion for HashMap<Key, Value>
+where
+    Key: Hash + Eq + Clone + Send,
+    Value: Set<Empty = Value> + Clone + Send,
+    for<'a> Value: UnionAssign<&'a Value>,
+{
+    fn par_union_all<I: IntoParallelIterator<Item = Self>>(sets: I) -> Self {
+        par_fold_hash_maps(
+            sets.into_par_iter(),
+            |value| value,
+            |acc, value| acc.union_assign(&value),
+            |acc, value| acc.union_assign(&value),
+        )
+        .into_iter()
+        .filter(|(_, value)| !value.is_empty())
+        .collect()
+    }
+}
+
+impl<Key, Value> ParIntersection for HashMap<Key, Value>
+where
+    Key: Hash + Eq + Clone + Send,
+    Value: Set<Empty = Value> + Send,
+    for<'a> Value: IntersectionAssign<&'a Value>,
+{
+    fn par_intersection_all<I: IntoParallelIterator<Item = Self>>(sets: I) -> Self {
+        let state = RandomState::new();
+        let shard_count = rayon::current_num_threads() * 4;
+
+        sets.into_par_iter()
+            .map(|map| hash_shards(map, &state, shard_count))
+            .reduce_with(|mut shards, other_shards| {
+                shards
+                    .par_iter_mut()
+                    .zip(other_shards)
+                    .for_each(|(shard, other_shard)| shard.intersection_assign(&other_shard));
+                shards
+            })
+            .map(|shards| shards.into_iter().flatten().collect())
+            .unwrap_or_default()
+    }
+}
+
+impl<Key, Value> ParUnion for BTreeMap<Key, Value>
+where
+    Key: Ord + Clone + Send + Sync,
+    Value: Set<Empty = Value> + Clone + Send,
+    for<'a> Value: UnionAssign<&'a Value>,
+{
+    fn par_union_all<I: IntoParallelIterator<Item = Self>>(sets: I) -> Self {
+        par_fold_btree_maps(
+            sets.into_par_iter(),
+            |value| value,
+            |acc, value| acc.union_assign(&value),
+        )
+        .1
+        .into_iter()
+        .filter(|(_, value)| !value.is_empty())
+        .collect()
+    }
+}
+
+impl<Key, Value> ParIntersection for BTreeMap<Key, Value>
+where
+    Key: Ord + Clone + Send + Sync,
+    Value: Set<Empty = Value> + Send,
+    for<'a> Value: IntersectionAssign<&'a Value>,
+{
+    fn par_intersection_all<I: IntoParallelIterator<Item = Self>>(sets: I) -> Self {
+        let (count, groups) = par_fold_btree_maps(
+            sets.into_par_iter(),
+            |value| (value, 1),
+            |(acc, seen), value| {
+                acc.intersection_assign(&value);
+                *seen += 1;
+            },
+        );
+
+        groups
+            .into_iter()
+            .filter(|(_, (value, seen))| *seen == count && !value.is_empty())
+            .map(|(key, (value, _))| (key, value))
+            .collect()
+    }
+}
+
+/// Implements [`ParUnion`] and [`ParIntersection`] with the pairwise reduction for sets that support the operations.
+macro_rules! impl_pairwise_par_fold {
+    ($([$($generics:tt)*] $set:ty),* $(,)?) => {
+        $(
+            impl<$($generics)*> ParUnion for $set
+            where
+                $set: Set<Empty = $set> + Send,
+                for<'a> $set: UnionAssign<&'a $set>,
+            {
+            }
+
+            impl<$($generics)*> ParIntersection for $set
+            where
+                $set: Set<Empty = $set> + Send,
+                for<'a> $set: IntersectionAssign<&'a $set>,
+            {
+            }
+        )*
+    };
+}
+
+impl_pairwise_par_fold!(
+    [] bool,
+    [Value] Option<Value>,
+    [Value] Box<Value>,
+    [const N: usize, Value] [Value; N],
+    [Element] HashSet<Element>,
+    [Element] BTreeSet<Element>,
+);
+
+/// Implements [`ParUnion`] for a wildcard map by splitting off the rest lists, which are united by key like `$map`, and reducing the wildcard values with their exceptions pairwise.
+///
+/// [`ParIntersection`] uses the pairwise reduction.
+macro_rules! impl_wildcard_par_fold {
+    ($name:ident, $map:ident, Key: $($bounds:tt)*) => {
+        impl<Key, Value> ParUnion for $name<Key, Value>
+        where
+            Key: $($bounds)*,
+            Value: Set<Empty = Value> + Clone + Send,
+            $name<Key, Value>: Set<Empty = $name<Key, Value>> + Send,
+            $map<Key, Value>: ParUnion,
+            for<'a> $name<Key, Value>: UnionAssign<&'a $name<Key, Value>> + UnionAssign<&'a $map<Key, Value>>,
+        {
+            fn par_union_all<I: IntoParallelIterator<Item = Self>>(sets: I) -> Self {
+                let (sets, rest_lists): (Vec<Self>, Vec<$map<Key, Value>>) = sets
+                    .into_par_iter()
+                    .map(|mut set| {
+                        let rest_list = set.take_rest_list();
+                        (set, rest_list)
+                    })
+                    .unzip();
+
+                let mut union = sets.into_par_iter().reduce(Self::empty, |mut set, other| {
+                    set.union_assign(&other);
+                    set
+                });
+                union.union_assign(&par_union_all(rest_lists));
+                union
+            }
+        }
+
+        impl<Key, Value> ParIntersection for $name<Key, Value>
+        where
+            Key: $($bounds)*,
+            Value: Set<Empty = Value>,
+            $name<Key, Value>: Set<Empty = $name<Key, Value>> + Send,
+            for<'a> $name<Key, Value>: IntersectionAssign<&'a $name<Key, Value>>,
+        {
+        }
+    };
+}
+
+impl_wildcard_par_fold!(WildcardHashMap, HashMap, Key: Hash + Eq + Clone);
+impl_wildcard_par_fold!(WildcardBTreeMap, BTreeMap, Key: Ord + Clone);
+
+/// [`ParSubsetOf`] is a parallel version of [`SubsetOf`] for maps, which checks the keys of the map in parallel.
+pub trait ParSubsetOf<Rhs = Self>: SubsetOf<Rhs> {
+    fn par_subset_of(&self, rhs: &Rhs) -> bool;
+}
+
+macro_rules! impl_map_par_subset_of {
+    ($map:ident, Key: $($bounds:tt)*) => {
+        impl<Key, Value, OtherValue> ParSubsetOf<$map<Key, OtherValue>> for $map<Key, Value>
+        where
+            Key: $($bounds)* + Sync,
+            Value: SubsetOf<OtherValue> + Sync,
+            OtherValue: Sync,
+        {
+            fn par_subset_of(&self, rhs: &$map<Key, OtherValue>) -> bool {
+                self.par_iter()
+                    .all(|(key, value)| rhs.get(key).is_some_and(|rhs_value| value.subset_of(rhs_value)))
+            }
+        }
+    };
+}
+
+impl_map_par_subset_of!(HashMap, Key: Hash + Eq);
+impl_map_par_subset_of!(BTreeMap, Key: Ord);
+
+macro_rules! impl_wildcard_par_subset_of {
+    ($map:ident, Key: $($bounds:tt)*) => {
+        impl<Key, Value, OtherValue> ParSubsetOf<$map<Key, OtherValue>> for $map<Key, Value>
+        where
+            Key: $($bounds)* + Sync,
+            Value: Set<Empty = Value> + SubsetOf<OtherValue> + Clone + Sync,
+            OtherValue: Set<Empty = OtherValue> + Sync,
+            for<'a> Value: DifferenceAssign<&'a Value>
+                + DifferenceAssign<&'a OtherValue>
+                + UnionAssign<&'a Value>
+                + IntersectionAssign<&'a OtherValue>,
+        {
+            fn par_subset_of(&self, rhs: &$map<Key, OtherValue>) -> bool {
+                self.wildcard_value().subset_of(rhs.wildcard_value())
+                    && rhs
+                        .wildcard_exceptions()
+                        .par_iter()
+                        .all(|(key, _)| self.key_subset_of(rhs, key))
+                    && self
+                        .rest_list()
+                        .par_iter()
+                        .filter(|(key, _)| !rhs.wildcard_exceptions().contains_key(key))
+                        .all(|(key, _)| self.key_subset_of(rhs, key))
+            }
+        }
+    };
+}
+
+impl_wildcard_par_subset_of!(WildcardHashMap, Key: Hash + Eq + Clone);
+impl_wildcard_par_subset_of!(WildcardBTreeMap, Key: Ord + Clone);
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    #[allow(unused_imports)]
+    use super::*;
+
+    use maplit::{btreemap, hashmap};
+
+    #[rstest]
+    #[case(vec![], hashmap! {})]
+    #[case(vec![hashmap! { 0 => true }], hashmap! { 0 => true })]
+    #[case(vec![hashmap! { 0 => true }, hashmap! { 1 => true }, hashmap! { 0 => false }], hashmap! { 0 => true, 1 => true })]
+    fn union_all_tests(#[case] sets: Vec<HashMap<u8, bool>>, #[case] result: HashMap<u8, bool>) {
+        assert_eq!(par_union_all(sets), result);
+    }
+
+    #[rstest]
+    #[case(vec![], hashmap! {})]
+    #[case(vec![hashmap! { 0 => true, 1 => true }], hashmap! { 0 => true, 1 => true })]
+    #[case(vec![hashmap! { 0 => true, 1 => true }, hashmap! { 1 => true, 2 => true }, hashmap! { 1 => true }], hashmap! { 1 => true })]
+    fn intersection_all_tests(
+        #[case] sets: Vec<HashMap<u8, bool>>,
+        #[case] result: HashMap<u8, bool>,
+    ) {
+        assert_eq!(par_intersection_all(sets), result);
+    }
+
+    #[rstest]
+    #[case(vec![], btreemap! {})]
+    #[case(vec![btreemap! { 0 => [true, false] }, btreemap! { 0 => [false, true], 1 => [false, false] }], btreemap! { 0 => [true, true] })]
+    fn btree_union_all_tests(
+        #[case] sets: Vec<BTreeMap<u8, [bool; 2]>>,
+        #[case] result: BTreeMap<u8, [bool; 2]>,
+    ) {
+        assert_eq!(par_union_all(sets), result);
+    }
+
+    #[rstest]
+    #[case(vec![btreemap! { 0 => [true, true], 1 => [true, false] }, btreemap! { 0 => [true, false], 1 => [false, true] }], btreemap! { 0 => [true, false] })]
+    #[case(vec![btreemap! { 0 => [true, true] }, btreemap! { 1 => [true, true] }], btreemap! {})]
+    fn btree_intersection_all_tests(
+        #[case] sets: Vec<BTreeMap<u8, [bool; 2]>>,
+        #[case] result: BTreeMap<u8, [bool; 2]>,
+    ) {
+        assert_eq!(par_intersection_all(sets), result);
+    }
+
+    #[test]
+    fn map_folds_match_sequential() {
+        let sets: Vec<HashMap<u32, [bool; 2]>> = (0..1000)
+            .map(|role| {
+                (0..50)
+                    .filter(|key| key % 5 != 0 || role % 7 != 0)
+                    .map(|key| (key, [key % 2 == 0 || role % 11 != 0, key % 3 != 0]))
+                    .collect()
+            })
+            .collect();
+
+        let mut union = HashMap::new();
+        let mut intersection = sets[0].clone();
+        for set in &sets {
+            union.union_assign(set);
+            intersection.intersection_assign(set);
+        }
+
+        assert_eq!(intersection.len(), 34);
+        assert_eq!(par_union_all(sets.clone()), union);
+        assert_eq!(par_intersection_all(sets.clone()), intersection);
+
+        let btree_sets: Vec<BTreeMap<u32, [bool; 2]>> = sets
+            .into_iter()
+            .map(|set| set.into_iter().collect())
+            .collect();
+
+        assert_eq!(
+            par_union_all(btree_sets.clone()),
+            union.into_iter().collect()
+        );
+        assert_eq!(
+            par_intersection_all(btree_sets),
+            intersection.into_iter().collect()
+        );
+    }
+
+    #[test]
+    fn union_all_matches_sequential() {
+        let sets: Vec<WildcardHashMap<u32, bool>> = (0..1000)
+            .map(|role| match role % 3 {
+                0 => WildcardHashMap::from(hashmap! { role => true }),
+                1 => WildcardHashMap::new(false),
+                _ => {
+                    let mut set = WildcardHashMap::new(true);
+                    set.difference_assign(&WildcardHashMap::from(hashmap! { role => true }));
+                    set
+                }
+            })
+            .collect();
+
+        let mut sequential = WildcardHashMap::new(false);
+        for set in &sets {
+            sequential.union_assign(set);
+        }
+
+        assert_eq!(par_union_all(sets), sequential);
+    }
+
+    #[test]
+    fn wildcard_union_all_splits_rest_lists() {
+        let sets: Vec<WildcardHashMap<u32, [bool; 2]>> = (0..1000)
+            .map(|role| {
+                let mut set = WildcardHashMap::new([role % 2 == 0, false]);
+                set.difference_assign(&hashmap! { role % 20 => [true, false] });
+                set.union_assign(
+                    &hashmap! { role % 30 => [false, true], role % 7 => [true, false] },
+                );
+                set
+            })
+            .collect();
+
+        let mut sequential = WildcardHashMap::new([false, false]);
+        for set in &sets {
+            sequential.union_assign(set);
+        }
+
+        assert_eq!(par_union_all(sets.clone()), sequential);
+
+        let btree_sets: Vec<WildcardBTreeMap<u32, [bool; 2]>> = sets
+            .iter()
+            .map(|set| {
+                let mut btree_set = WildcardBTreeMap::new(*set.wildcard_value());
+                btree_set.difference_assign(
+                    &set.wildcard_exceptions()
+                        .iter()
+                        .map(|(key, value)| (*key, *value))
+                        .collect::<BTreeMap<_, _>>(),
+                );
+                btree_set.union_assign(
+                    &set.rest_list()
+                        .iter()
+                        .map(|(key, value)| (*key, *value))
+                        .collect::<BTreeMap<_, _>>(),
+                );
+                btree_set
+            })
+            .collect();
+
+        let mut btree_sequential = WildcardBTreeMap::new([false, false]);
+        for set in &btree_sets {
+            btree_sequential.union_assign(set);
+        }
+
+        assert_eq!(par_union_all(btree_sets), btree_sequential);
+    }
+
+    #[test]
+    fn par_subset_of_matches_subset_of() {
+        let all = WildcardHashMap::new(true);
+        let mut most = WildcardHashMap::new(true);
+        most.difference_assign(&WildcardHashMap::from(hashmap! { 0 => true }));
+        let some = WildcardHashMap::from(hashmap! { 0 => true, 1 => true });
+
+        for a in [&all, &most, &some] {
+            for b in [&all, &most, &some] {
+                assert_eq!(a.par_subset_of(b), a.subset_of(b), "{a:?} ⊆ {b:?}");
+            }
+        }
+
+        let map = (0..10_000)
+            .map(|key| (key, true))
+            .collect::<HashMap<u32, bool>>();
+        assert!(map.par_subset_of(&map));
+        assert!(!map.par_subset_of(&hashmap! { 0 => true }));
+    }
+}
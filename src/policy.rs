@@ -0,0 +1,823 @@
+//! This module contains [`Policy`], which loads role definitions from TOML, YAML or JSON documents and resolves them into sets.
+//!
+//! A document lists roles, each with optional `inherits`, `grant` and `deny` lists. Grants and denies are permission nodes, which are paths of `.` separated segments into the set type, parsed with [`FromNode`]. A segment is a field name for structs, a key for maps and `*` for the wildcard of wildcard maps.
+//!
+//! ```toml
+//! [roles.member]
+//! grant = ["chat", "clans.*.kick"]
+//!
+//! [roles.moderator]
+//! inherits = ["member"]
+//! grant = ["clans.*.ban"]
+//! deny = ["clans.secret.kick"]
+//! ```
+//!
+//! The set of a role is the union of the sets of the roles it inherits, with the grants added and then the denies removed. Unknown nodes, unknown roles and inheritance cycles are all reported together, with the location of the offending entry.
+//!
+//! ```
+//! # use std::collections::HashMap;
+//! # use finit::policy::Policy;
+//! let policy = Policy::<HashMap<String, bool>>::from_json(
+//!     r#"{ "roles": { "guest": { "grant": ["read"] }, "user": { "inherits": ["guest"], "grant": ["write"] } } }"#,
+//! )
+//! .unwrap();
+//!
+//! assert_eq!(policy.role("user").unwrap().len(), 2);
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::Set;
+use crate::collections::{WildcardBTreeMap, WildcardHashMap};
+use crate::operations::{DifferenceAssign, UnionAssign};
+
+#[cfg(feature = "derive")]
+pub use finit_derive::FromNode;
+
+/// [`FromNode`] builds the smallest set that contains a permission node, given as its `.` separated segments.
+pub trait FromNode: Sized {
+    fn from_node(segments: &[&str]) -> Result<Self, NodeError>;
+}
+
+/// [`NodeError`] is returned by [`FromNode`] when a node does not describe a member of the set type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeError {
+    /// The segment is not a field of the struct.
+    UnknownField(String),
+    /// The segment could not be parsed as a key of the map.
+    InvalidKey(String),
+    /// The segment is `*`, but the map has no wildcard.
+    WildcardNotSupported,
+    /// The node ends before reaching a flag.
+    Incomplete,
+    /// The node continues after reaching a flag, starting with the segment.
+    Unexpected(String),
+}
+
+impl Display for NodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NodeError::UnknownField(segment) => write!(f, "unknown field `{segment}`"),
+            NodeError::InvalidKey(segment) => write!(f, "invalid key `{segment}`"),
+            NodeError::WildcardNotSupported => write!(f, "the map does not support `*`"),
+            NodeError::Incomplete => write!(f, "the node does not end in a flag"),
+            NodeError::Unexpected(segment) => {
+                write!(f, "unexpected segment `{segment}` after a flag")
+            }
+        }
+    }
+}
+
+impl std::error::Error for NodeError {}
+
+impl FromNode for bool {
+    fn from_node(segments: &[&str]) -> Result<Self, NodeError> {
+        match segments.first() {
+            Some(segment) => Err(NodeError::Unexpected(segment.to_string())),
+            None => Ok(true),
+        }
+    }
+}
+
+impl<Value: FromNode> FromNode for Option<Value> {
+    fn from_node(segments: &[&str]) -> Result<Self, NodeError> {
+        Value::from_node(segments).map(Some)
+    }
+}
+
+impl<Value: FromNode> FromNode for Box<Value> {
+    fn from_node(segments: &[&str]) -> Result<Self, NodeError> {
+        Value::from_node(segments).map(Box::new)
+    }
+}
+
+/// Splits a node of a keyed set into its key and the node of the value.
+fn split_key<'a, Key: FromStr>(
+    segments: &'a [&'a str],
+) -> Result<(Option<Key>, &'a [&'a str]), NodeError> {
+    let Some((segment, rest)) = segments.split_first() else {
+        return Err(NodeError::Incomplete);
+    };
+
+    if *segment == "*" {
+        return Ok((None, rest));
+    }
+
+    segment
+        .parse()
+        .map(|key| (Some(key), rest))
+        .map_err(|_| NodeError::InvalidKey(segment.to_string()))
+}
+
+impl<Key: FromStr + Hash + Eq, Value: FromNode> FromNode for HashMap<Key, Value> {
+    fn from_node(segments: &[&str]) -> Result<Self, NodeError> {
+        let (Some(key), rest) = split_key(segments)? else {
+            return Err(NodeError::WildcardNotSupported);
+        };
+
+        Ok(HashMap::from([(key, Value::from_node(rest)?)]))
+    }
+}
+
+impl<Key: FromStr + Ord, Value: FromNode> FromNode for BTreeMap<Key, Value> {
+    fn from_node(segments: &[&str]) -> Result<Self, NodeError> {
+        let (Some(key), rest) = split_key(segments)? else {
+            return Err(NodeError::WildcardNotSupported);
+        };
+
+        Ok(BTreeMap::from([(key, Value::from_node(rest)?)]))
+    }
+}
+
+macro_rules! impl_wildcard_from_node {
+    ($map:ident, Key: $($bounds:tt)*) => {
+        impl<Key, Value> FromNode for $map<Key, Value>
+        where
+            Key: FromStr + $($bounds)*,
+            Value: FromNode + Set<Empty = Value>,
+            Self: Extend<(Key, Value)>,
+        {
+            fn from_node(segments: &[&str]) -> Result<Self, NodeError> {
+                match split_key(segments)? {
+                    (None, rest) => Ok($map::new(Value::from_node(rest)?)),
+                    (Some(key), rest) => {
+                        let mut map = $map::new(Value::empty());
+                        map.extend([(key, Value::from_node(rest)?)]);
+                        Ok(map)
+                    }
+                }
+            }
+        }
+    };
+}
+
+impl_wildcard_from_node!(WildcardHashMap, Key: Hash + Eq + Clone);
+impl_wildcard_from_node!(WildcardBTreeMap, Key: Ord + Clone);
+
+/// A position in a policy document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    /// The file the document was loaded from, if it was loaded with [`Policy::load`].
+    pub file: Option<PathBuf>,
+    /// The line, starting at 1.
+    pub line: usize,
+    /// The column in characters, starting at 1.
+    pub column: usize,
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file.display())?;
+        }
+
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// A problem found while loading a [`Policy`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyError {
+    /// Where the problem is, if it could be located.
+    pub location: Option<Location>,
+    pub kind: PolicyErrorKind,
+}
+
+/// The kind of a [`PolicyError`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyErrorKind {
+    /// The file could not be read.
+    Io(std::io::ErrorKind),
+    /// The file extension is not `toml`, `yaml`, `yml` or `json`.
+    UnsupportedFormat,
+    /// The document is not valid TOML, YAML or JSON, or does not have the shape of a policy.
+    Parse(String),
+    /// A grant or deny of `role` is not a node of the set type.
+    UnknownNode {
+        role: String,
+        node: String,
+        error: NodeError,
+    },
+    /// `role` inherits a role that is not defined.
+    UnknownRole { role: String, inherits: String },
+    /// The roles inherit each other in a cycle, where the last role is the first role again.
+    Cycle { roles: Vec<String> },
+}
+
+impl Display for PolicyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(location) = &self.location {
+            write!(f, "{location}: ")?;
+        }
+
+        match &self.kind {
+            PolicyErrorKind::Io(kind) => write!(f, "could not read the policy: {kind}"),
+            PolicyErrorKind::UnsupportedFormat => {
+                write!(
+                    f,
+                    "policies must be `.toml`, `.yaml`, `.yml` or `.json` files"
+                )
+            }
+            PolicyErrorKind::Parse(message) => write!(f, "invalid policy: {message}"),
+            PolicyErrorKind::UnknownNode { role, node, error } => {
+                write!(f, "role `{role}` has unknown node `{node}`: {error}")
+            }
+            PolicyErrorKind::UnknownRole { role, inherits } => {
+                write!(f, "role `{role}` inherits unknown role `{inherits}`")
+            }
+            PolicyErrorKind::Cycle { roles } => {
+                write!(f, "roles inherit each other: {}", roles.join(" -> "))
+            }
+        }
+    }
+}
+
+/// [`PolicyErrors`] contains every problem found while loading a [`Policy`], in the order they were found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyErrors(pub Vec<PolicyError>);
+
+impl PolicyErrors {
+    pub fn into_inner(self) -> Vec<PolicyError> {
+        self.0
+    }
+}
+
+impl Display for PolicyErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, error) in self.0.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+
+            write!(f, "{error}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for PolicyErrors {}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Document<Item> {
+    roles: BTreeMap<String, RoleDocument<Item>>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RoleDocument<Item> {
+    #[serde(default = "Vec::new")]
+    inherits: Vec<Item>,
+    #[serde(default = "Vec::new")]
+    grant: Vec<Item>,
+    #[serde(default = "Vec::new")]
+    deny: Vec<Item>,
+}
+
+/// A string of the document together with its byte offset.
+struct Entry {
+    value: String,
+    offset: usize,
+}
+
+impl<T> RoleDocument<T> {
+    fn map_entries<E>(
+        self,
+        f: &mut impl FnMut(T) -> Result<Entry, E>,
+    ) -> Result<RoleDocument<Entry>, E> {
+        let mut map = |entries: Vec<T>| entries.into_iter().map(&mut *f).collect::<Result<_, E>>();
+
+        Ok(RoleDocument {
+            inherits: map(self.inherits)?,
+            grant: map(self.grant)?,
+            deny: map(self.deny)?,
+        })
+    }
+}
+
+/// A document being resolved, used to give errors their location.
+struct Source<'a> {
+    text: &'a str,
+    file: Option<&'a Path>,
+}
+
+impl Source<'_> {
+    fn location(&self, offset: usize) -> Location {
+        let before = &self.text[..offset.min(self.text.len())];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+        Location {
+            file: self.file.map(Path::to_path_buf),
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+
+    fn error(&self, offset: Option<usize>, kind: PolicyErrorKind) -> PolicyErrors {
+        PolicyErrors(vec![PolicyError {
+            location: offset.map(|offset| self.location(offset)),
+            kind,
+        }])
+    }
+}
+
+/// [`Policy`] contains the resolved set of every role of a policy document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Policy<S> {
+    roles: BTreeMap<String, S>,
+    definitions: BTreeMap<String, RoleDefinition>,
+}
+
+/// A role as written in the policy document, before it was resolved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoleDefinition {
+    pub inherits: Vec<String>,
+    pub grant: Vec<String>,
+    pub deny: Vec<String>,
+}
+
+impl From<&RoleDocument<Entry>> for RoleDefinition {
+    fn from(role: &RoleDocument<Entry>) -> Self {
+        let values = |entries: &[Entry]| entries.iter().map(|entry| entry.value.clone()).collect();
+
+        RoleDefinition {
+            inherits: values(&role.inherits),
+            grant: values(&role.grant),
+            deny: values(&role.deny),
+        }
+    }
+}
+
+impl<S> Policy<S> {
+    /// The resolved set of `role`.
+    pub fn role(&self, role: &str) -> Option<&S> {
+        self.roles.get(role)
+    }
+
+    /// The roles and their resolved sets, ordered by name.
+    pub fn roles(&self) -> &BTreeMap<String, S> {
+        &self.roles
+    }
+
+    pub fn into_roles(self) -> BTreeMap<String, S> {
+        self.roles
+    }
+
+    /// The definition of `role` in the document, which can be used to explain where its set comes from.
+    pub fn definition(&self, role: &str) -> Option<&RoleDefinition> {
+        self.definitions.get(role)
+    }
+}
+
+impl<S> Policy<S>
+where
+    S: FromNode + Set<Empty = S> + Clone,
+    for<'a> S: UnionAssign<&'a S> + DifferenceAssign<&'a S>,
+{
+    /// Loads a policy from a TOML document.
+    pub fn from_toml(text: &str) -> Result<Self, PolicyErrors> {
+        Self::parse_toml(&Source { text, file: None })
+    }
+
+    /// Loads a policy from a YAML document.
+    pub fn from_yaml(text: &str) -> Result<Self, PolicyErrors> {
+        Self::parse_yaml(&Source { text, file: None })
+    }
+
+    /// Loads a policy from a JSON document.
+    pub fn from_json(text: &str) -> Result<Self, PolicyErrors> {
+        Self::parse_json(&Source { text, file: None })
+    }
+
+    /// Loads a policy from a `.toml`, `.yaml`, `.yml` or `.json` file, including the file in the location of errors.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, PolicyErrors> {
+        let path = path.as_ref();
+        let error = |kind| {
+            PolicyErrors(vec![PolicyError {
+                location: None,
+                kind,
+            }])
+        };
+
+        let parse = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Self::parse_toml,
+            Some("yaml" | "yml") => Self::parse_yaml,
+            Some("json") => Self::parse_json,
+            _ => return Err(error(PolicyErrorKind::UnsupportedFormat)),
+        };
+
+        let text =
+            std::fs::read_to_string(path).map_err(|io| error(PolicyErrorKind::Io(io.kind())))?;
+
+        parse(&Source {
+            text: &text,
+            file: Some(path),
+        })
+    }
+
+    fn parse_toml(source: &Source<'_>) -> Result<Self, PolicyErrors> {
+        let document: Document<toml::Spanned<String>> =
+            toml::from_str(source.text).map_err(|error| {
+                source.error(
+                    error.span().map(|span| span.start),
+                    PolicyErrorKind::Parse(error.message().to_string()),
+                )
+            })?;
+
+        let roles = document
+            .roles
+            .into_iter()
+            .map(|(name, role)| {
+                let role = role.map_entries(&mut |entry| {
+                    Ok::<_, PolicyErrors>(Entry {
+                        offset: entry.span().start,
+                        value: entry.into_inner(),
+                    })
+                })?;
+
+                Ok((name, role))
+            })
+            .collect::<Result<_, PolicyErrors>>()?;
+
+        Self::resolve(source, &roles)
+    }
+
+    fn parse_yaml(source: &Source<'_>) -> Result<Self, PolicyErrors> {
+        let offset = |location: serde_saphyr::Location| {
+            location
+                .span()
+                .byte_offset()
+                .and_then(|offset| usize::try_from(offset).ok())
+        };
+
+        let document: Document<serde_saphyr::Spanned<String>> = serde_saphyr::from_str(source.text)
+            .map_err(|error| {
+                source.error(
+                    error.location().and_then(offset),
+                    PolicyErrorKind::Parse(error.without_snippet().to_string()),
+                )
+            })?;
+
+        let roles = document
+            .roles
+            .into_iter()
+            .map(|(name, role)| {
+                let role = role.map_entries(&mut |entry| {
+                    Ok::<_, PolicyErrors>(Entry {
+                        offset: offset(entry.referenced).unwrap_or(0),
+                        value: entry.value,
+                    })
+                })?;
+
+                Ok((name, role))
+            })
+            .collect::<Result<_, PolicyErrors>>()?;
+
+        Self::resolve(source, &roles)
+    }
+
+    fn parse_json(source: &Source<'_>) -> Result<Self, PolicyErrors> {
+        let json_error = |error: serde_json::Error, offset: usize| {
+            let location = source.location(offset);
+
+            PolicyErrors(vec![PolicyError {
+                location: Some(Location {
+                    line: location.line + error.line() - 1,
+                    column: if error.line() == 1 {
+                        location.column + error.column() - 1
+                    } else {
+                        error.column()
+                    },
+                    ..location
+                }),
+                kind: PolicyErrorKind::Parse(error.to_string()),
+            }])
+        };
+
+        // Raw values borrow from the text, so their address gives their offset in it.
+        let document: Document<&serde_json::value::RawValue> =
+            serde_json::from_str(source.text).map_err(|error| json_error(error, 0))?;
+
+        let roles = document
+            .roles
+            .into_iter()
+            .map(|(name, role)| {
+                let role = role.map_entries(&mut |entry| {
+                    let offset = entry.get().as_ptr() as usize - source.text.as_ptr() as usize;
+
+                    serde_json::from_str(entry.get())
+                        .map(|value| Entry { value, offset })
+                        .map_err(|error| json_error(error, offset))
+                })?;
+
+                Ok((name, role))
+            })
+            .collect::<Result<_, PolicyErrors>>()?;
+
+        Self::resolve(source, &roles)
+    }
+
+    fn resolve(
+        source: &Source<'_>,
+        roles: &BTreeMap<String, RoleDocument<Entry>>,
+    ) -> Result<Self, PolicyErrors> {
+        let mut resolver = Resolver {
+            source,
+            roles,
+            stack: Vec::new(),
+            resolved: BTreeMap::new(),
+            errors: Vec::new(),
+        };
+
+        for role in roles.keys() {
+            resolver.resolve(role);
+        }
+
+        if !resolver.errors.is_empty() {
+            return Err(PolicyErrors(resolver.errors));
+        }
+
+        Ok(Policy {
+            roles: resolver
+                .resolved
+                .into_iter()
+                .filter_map(|(role, set)| Some((role.to_string(), set?)))
+                .collect(),
+            definitions: roles
+                .iter()
+                .map(|(role, definition)| (role.clone(), definition.into()))
+                .collect(),
+        })
+    }
+}
+
+struct Resolver<'a, S> {
+    source: &'a Source<'a>,
+    roles: &'a BTreeMap<String, RoleDocument<Entry>>,
+    /// The roles currently being resolved, to detect cycles.
+    stack: Vec<&'a str>,
+    /// The set of every resolved role, or [`None`] if the role has errors.
+    resolved: BTreeMap<&'a str, Option<S>>,
+    errors: Vec<PolicyError>,
+}
+
+impl<'a, S> Resolver<'a, S>
+where
+    S: FromNode + Set<Empty = S> + Clone,
+    for<'b> S: UnionAssign<&'b S> + DifferenceAssign<&'b S>,
+{
+    fn error(&mut self, entry: &Entry, kind: PolicyErrorKind) {
+        self.errors.push(PolicyError {
+            location: Some(self.source.location(entry.offset)),
+            kind,
+        });
+    }
+
+    fn node(&mut self, role: &str, entry: &Entry) -> Option<S> {
+        let segments: Vec<&str> = entry.value.split('.').collect();
+
+        S::from_node(&segments)
+            .map_err(|error| {
+                self.error(
+                    entry,
+                    PolicyErrorKind::UnknownNode {
+                        role: role.to_string(),
+                        node: entry.value.clone(),
+                        error,
+                    },
+                )
+            })
+            .ok()
+    }
+
+    fn resolve(&mut self, role: &'a str) -> Option<S> {
+        if let Some(set) = self.resolved.get(role) {
+            return set.clone();
+        }
+
+        let roles = self.roles;
+        let definition = &roles[role];
+        let mut set = S::empty();
+        let mut valid = true;
+
+        self.stack.push(role);
+
+        for entry in &definition.inherits {
+            let Some((inherited, _)) = roles.get_key_value(&entry.value) else {
+                self.error(
+                    entry,
+                    PolicyErrorKind::UnknownRole {
+                        role: role.to_string(),
+                        inherits: entry.value.clone(),
+                    },
+                );
+                valid = false;
+                continue;
+            };
+
+            if let Some(start) = self.stack.iter().position(|role| *role == inherited) {
+                let mut cycle: Vec<String> = self.stack[start..]
+                    .iter()
+                    .map(|role| role.to_string())
+                    .collect();
+                cycle.push(inherited.clone());
+
+                self.error(entry, PolicyErrorKind::Cycle { roles: cycle });
+                valid = false;
+                continue;
+            }
+
+            match self.resolve(inherited) {
+                Some(inherited) => set.union_assign(&inherited),
+                None => valid = false,
+            }
+        }
+
+        for entry in &definition.grant {
+            match self.node(role, entry) {
+                Some(node) => set.union_assign(&node),
+                None => valid = false,
+            }
+        }
+
+        for entry in &definition.deny {
+            match self.node(role, entry) {
+                Some(node) => set.difference_assign(&node),
+                None => valid = false,
+            }
+        }
+
+        self.stack.pop();
+
+        let set = valid.then_some(set);
+        self.resolved.insert(role, set.clone());
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    #[allow(unused_imports)]
+    use super::*;
+
+    type Perms = HashMap<String, WildcardBTreeMap<String, bool>>;
+
+    const TOML: &str = r#"
+[roles.member]
+grant = ["clans.*"]
+
+[roles.moderator]
+inherits = ["member"]
+grant = ["bans.all"]
+deny = ["clans.secret"]
+"#;
+
+    #[test]
+    fn toml_test() {
+        let policy = Policy::<Perms>::from_toml(TOML).unwrap();
+
+        let moderator = policy.role("moderator").unwrap();
+        assert!(moderator["clans"].value_of(&"public".to_string()));
+        assert!(!moderator["clans"].value_of(&"secret".to_string()));
+        assert!(moderator["bans"].value_of(&"all".to_string()));
+
+        assert!(!policy.role("member").unwrap().contains_key("bans"));
+        assert_eq!(
+            policy.definition("moderator").unwrap().deny,
+            ["clans.secret"]
+        );
+    }
+
+    #[test]
+    fn yaml_test() {
+        let policy = Policy::<Perms>::from_yaml(
+            "roles:\n  member:\n    grant: [\"clans.*\"]\n  moderator:\n    inherits: [member]\n    grant:\n      - bans.all\n    deny:\n      - clans.secret\n",
+        )
+        .unwrap();
+
+        assert_eq!(policy, Policy::<Perms>::from_toml(TOML).unwrap());
+
+        let errors = Policy::<Perms>::from_yaml("roles:\n  a:\n    grant:\n      - clans.*.x\n")
+            .unwrap_err()
+            .into_inner();
+
+        assert_eq!(
+            errors[0].location,
+            Some(Location {
+                file: None,
+                line: 4,
+                column: 9
+            })
+        );
+
+        let errors = Policy::<Perms>::from_yaml("roles:\n  a:\n    grants: []\n")
+            .unwrap_err()
+            .into_inner();
+
+        assert_eq!(errors[0].location.as_ref().unwrap().line, 3);
+        assert!(matches!(errors[0].kind, PolicyErrorKind::Parse(_)));
+    }
+
+    #[rstest]
+    #[case(
+        r#"{ "roles": { "a": { "grant": ["x"] } } }"#,
+        1,
+        31,
+        NodeError::Incomplete
+    )]
+    #[case("{\n  \"roles\": {\n    \"a\": {\n      \"grant\": [\"x.y.z\"]\n    }\n  }\n}", 4, 17, NodeError::Unexpected("z".to_string()))]
+    fn unknown_node_tests(
+        #[case] json: &str,
+        #[case] line: usize,
+        #[case] column: usize,
+        #[case] error: NodeError,
+    ) {
+        let errors = Policy::<Perms>::from_json(json).unwrap_err().into_inner();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].location,
+            Some(Location {
+                file: None,
+                line,
+                column
+            })
+        );
+        assert!(
+            matches!(&errors[0].kind, PolicyErrorKind::UnknownNode { error: e, .. } if *e == error)
+        );
+    }
+
+    #[test]
+    fn cycle_test() {
+        let errors = Policy::<Perms>::from_toml(
+            r#"
+[roles.a]
+inherits = ["b"]
+
+[roles.b]
+inherits = ["a", "c"]
+"#,
+        )
+        .unwrap_err()
+        .into_inner();
+
+        assert_eq!(
+            errors
+                .iter()
+                .map(|error| (error.location.as_ref().unwrap().line, error.kind.clone()))
+                .collect::<Vec<_>>(),
+            [
+                (
+                    6,
+                    PolicyErrorKind::Cycle {
+                        roles: vec!["a".to_string(), "b".to_string(), "a".to_string()]
+                    }
+                ),
+                (
+                    6,
+                    PolicyErrorKind::UnknownRole {
+                        role: "b".to_string(),
+                        inherits: "c".to_string()
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_error_test() {
+        let errors = Policy::<Perms>::from_toml("[roles.a]\ngrants = []\n")
+            .unwrap_err()
+            .into_inner();
+
+        assert_eq!(errors[0].location.as_ref().unwrap().line, 2);
+        assert!(matches!(errors[0].kind, PolicyErrorKind::Parse(_)));
+    }
+
+    #[test]
+    fn load_test() {
+        let path = std::env::temp_dir().join(format!("finit-policy-{}.toml", std::process::id()));
+        std::fs::write(&path, "[roles.a]\ngrant = [\"clans.*.x\"]\n").unwrap();
+
+        let error = Policy::<Perms>::load(&path).unwrap_err().to_string();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            error,
+            format!(
+                "{}:2:10: role `a` has unknown node `clans.*.x`: unexpected segment `x` after a flag",
+                path.display()
+            )
+        );
+    }
+}
@@ -0,0 +1,380 @@
+//! This module documents the stable [`serde`] representations of the set types in this crate, and contains alternative representations that can be picked with `#[serde(with = "...")]`.
+//!
+//! The representations below are considered part of the public API, and will not change without a major version bump.
+//!
+//! Type | Representation | JSON example
+//! --- | --- | ---
+//! `bool` | A boolean. | `true`
+//! [`Option<T>`] | `null` or the representation of `T`. | `null`
+//! [`Box<T>`] | The representation of `T`. | `true`
+//! `[T; N]` | A sequence of `N` values. | `[true, false]`
+//! `(T1, T2, ...)` | A sequence of values. | `[true, {"a": true}]`
+//! [`std::collections::HashMap`], [`std::collections::BTreeMap`], `im::HashMap`, `im::OrdMap` | A map from keys to values. | `{"a": true}`
+//! [`crate::collections::WildcardHashMap`], [`crate::collections::WildcardBTreeMap`], `WildcardImHashMap` | A map of the non-empty parts `wildcard_value`, `wildcard_exceptions` and `rest_list`. | `{"wildcard_value": true, "wildcard_exceptions": {"a": true}}`
+//! [`crate::overlay::TriState`] | `"unset"`, `"allow"` or `"deny"`. | `"deny"`
+//! [`crate::overlay::Overlay`] | A sequence of layers, from the lowest priority to the highest. | `[{"a": "allow"}, {"a": "deny"}]`
+//! [`crate::layered::LayeredPermissions`] | A sequence of layers with `name`, `priority`, `grants` and `denies`, from the highest priority to the lowest. Deserializing sorts the layers by priority. | `[{"name": "user", "priority": 10, "grants": {"a": true}, "denies": {}}]`
+//! [`crate::collections::Timed`] | A map from expiries to the parts of the set that expire then. | `{"10": ["fly"], "20": ["glow"]}`
+//! [`crate::collections::Conditional`] | A map of `unconditional` and `conditional`, which is a sequence of pairs of conditions and grants. | `{"unconditional": ["chat"], "conditional": [["BusinessHours", ["refund"]]]}`
+//! [`crate::collections::Scoped`] | A map of the `grants` of the scope and its `children`, which are a wildcard map of child scopes. Both are left out when empty. | `{"grants": ["read"], "children": {"rest_list": {"acme": {"grants": ["read", "write"]}}}}`
+//! [`crate::delta::Delta`] | A map of `added` and `removed`. | `{"added": {"a": true}, "removed": {}}`
+//! [`struct@crate::delegation::Grantable`] | A map of `has` and `may_grant`. | `{"has": {"a": true}, "may_grant": {}}`
+//! Derived structs | Whatever [`serde::Serialize`] and [`serde::Deserialize`] are derived as, usually a map of field names to values. | `{"kick": true, "ban": false}`
+//!
+//! ## Flat wildcard maps
+//! Wildcard maps can also be represented as a single flat map which is easier to edit by hand, using [`flat`]:
+//! - `"*"` is the wildcard value.
+//! - `"-key"` is the exception of `key`.
+//! - `"key"` is the rest list value of `key`. Keys starting with `-` or `\`, and the key `*`, are escaped with a leading `\`.
+//!
+//! ```
+//! # use std::collections::HashMap;
+//! # use finit::collections::WildcardHashMap;
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct ClanPerms {
+//!     #[serde(with = "finit::representation::flat")]
+//!     kick: WildcardHashMap<String, bool>,
+//! }
+//!
+//! let perms: ClanPerms = serde_json::from_str(r#"{"kick": {"*": true, "-admin": true}}"#).unwrap();
+//!
+//! assert_eq!(perms.kick.wildcard_value(), &true);
+//! assert_eq!(perms.kick.wildcard_exceptions(), &HashMap::from([("admin".to_string(), true)]));
+//! ```
+//!
+//! The keys of flat maps are written with [`core::fmt::Display`] and read with [`core::str::FromStr`].
+//! The values use their normal representation.
+//! Deserializing checks that the map is canonical: there are no empty values, every exception is part of the wildcard value and every rest list value is disjoint from it.
+//!
+//! ## Picking the representation with the derive
+//! With the `derive` feature, the `represent` attribute picks the representation of fields with `#[finit(representation = "flat")]` or `#[finit(representation = "default")]`.
+//! It has to be placed above `#[derive(Serialize, Deserialize)]`.
+//!
+//! ```
+//! # #[cfg(feature = "derive")]
+//! # {
+//! # use finit::collections::WildcardHashMap;
+//! #[finit::representation::represent]
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct ClanPerms {
+//!     #[finit(representation = "flat")]
+//!     kick: WildcardHashMap<String, bool>,
+//!     #[finit(representation = "default")]
+//!     ban: WildcardHashMap<String, bool>,
+//! }
+//!
+//! let perms: ClanPerms = serde_json::from_str(r#"{"kick": {"*": true}, "ban": {}}"#).unwrap();
+//!
+//! assert_eq!(perms.kick.wildcard_value(), &true);
+//! # }
+//! ```
+
+#[cfg(feature = "derive")]
+pub use finit_derive::represent;
+
+/// The flat representation of wildcard maps, for use with `#[serde(with = "finit::representation::flat")]`.
+///
+/// See the [module level documentation](super) for the format. Use [`Flat`](flat::Flat) to pick the representation without a serde attribute.
+#[cfg(feature = "alloc")]
+pub mod flat {
+    use alloc::collections::BTreeMap;
+    use alloc::format;
+    use alloc::string::{String, ToString};
+    use core::fmt::{self, Display};
+    use core::marker::PhantomData;
+    use core::str::FromStr;
+    #[cfg(feature = "std")]
+    use core::hash::Hash;
+    #[cfg(feature = "std")]
+    use std::collections::HashMap;
+
+    use serde::de::{Error, MapAccess, Visitor};
+    use serde::ser::SerializeMap;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::Set;
+    use crate::collections::WildcardBTreeMap;
+    use crate::operations::{DifferenceAssign, IntersectionAssign};
+    #[cfg(feature = "im")]
+    use crate::collections::WildcardImHashMap;
+    #[cfg(feature = "std")]
+    use crate::collections::WildcardHashMap;
+    #[cfg(feature = "im")]
+    use im::HashMap as ImHashMap;
+
+    const WILDCARD_KEY: &str = "*";
+    const EXCEPTION_PREFIX: char = '-';
+    const ESCAPE_PREFIX: char = '\\';
+
+    /// [`FlatRepresentation`] is implemented by the types that can be represented as a flat map.
+    pub trait FlatRepresentation: Sized {
+        fn serialize_flat<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+
+        fn deserialize_flat<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+    }
+
+    pub fn serialize<T: FlatRepresentation, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        value.serialize_flat(serializer)
+    }
+
+    pub fn deserialize<'de, T: FlatRepresentation, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        T::deserialize_flat(deserializer)
+    }
+
+    /// [`Flat`] wraps a wildcard map so that it is (de)serialized in the flat representation.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Flat<T>(pub T);
+
+    impl<T: FlatRepresentation> Serialize for Flat<T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            self.0.serialize_flat(serializer)
+        }
+    }
+
+    impl<'de, T: FlatRepresentation> Deserialize<'de> for Flat<T> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            T::deserialize_flat(deserializer).map(Flat)
+        }
+    }
+
+    fn rest_key<Key: Display>(key: &Key) -> String {
+        let key = key.to_string();
+
+        if key == WILDCARD_KEY || key.starts_with(EXCEPTION_PREFIX) || key.starts_with(ESCAPE_PREFIX)
+        {
+            format!("{ESCAPE_PREFIX}{key}")
+        } else {
+            key
+        }
+    }
+
+    fn exception_key<Key: Display>(key: &Key) -> String {
+        format!("{EXCEPTION_PREFIX}{key}")
+    }
+
+    fn parse_key<Key: FromStr, E: Error>(key: &str) -> Result<Key, E>
+    where
+        Key::Err: Display,
+    {
+        key.parse()
+            .map_err(|error| E::custom(format_args!("invalid key {key:?}: {error}")))
+    }
+
+    macro_rules! impl_flat_representation {
+        ($wildcard_map:ident, $map:ident, Key: ($($bounds:tt)*) $(, Value: $($value_bounds:tt)*)?) => {
+            impl<Key, Value> FlatRepresentation for $wildcard_map<Key, Value>
+            where
+                Key: $($bounds)* + Display + FromStr,
+                Key::Err: Display,
+                Value: Set<Empty = Value> + Clone $(+ $($value_bounds)*)? + Serialize + for<'de> Deserialize<'de>,
+                for<'a> Value: DifferenceAssign<&'a Value> + IntersectionAssign<&'a Value>,
+            {
+                fn serialize_flat<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    let wildcard_value = (!self.wildcard_value().is_empty()).then(|| self.wildcard_value());
+                    let len = usize::from(wildcard_value.is_some())
+                        + self.wildcard_exceptions().len()
+                        + self.rest_list().len();
+
+                    let mut map = serializer.serialize_map(Some(len))?;
+
+                    if let Some(wildcard_value) = wildcard_value {
+                        map.serialize_entry(WILDCARD_KEY, wildcard_value)?;
+                    }
+
+                    for (key, value) in self.wildcard_exceptions() {
+                        map.serialize_entry(&exception_key(key), value)?;
+                    }
+
+                    for (key, value) in self.rest_list() {
+                        map.serialize_entry(&rest_key(key), value)?;
+                    }
+
+                    map.end()
+                }
+
+                fn deserialize_flat<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    struct FlatVisitor<Key, Value>(PhantomData<(Key, Value)>);
+
+                    impl<'de, Key, Value> Visitor<'de> for FlatVisitor<Key, Value>
+                    where
+                        Key: $($bounds)* + Display + FromStr,
+                        Key::Err: Display,
+                        Value: Set<Empty = Value> + Clone $(+ $($value_bounds)*)? + Deserialize<'de>,
+                        for<'a> Value: DifferenceAssign<&'a Value> + IntersectionAssign<&'a Value>,
+                    {
+                        type Value = $wildcard_map<Key, Value>;
+
+                        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                            formatter.write_str("a flat wildcard map")
+                        }
+
+                        fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+                            let mut wildcard_value = None;
+                            let mut wildcard_exceptions = $map::new();
+                            let mut rest_list = $map::new();
+
+                            while let Some(key) = access.next_key::<String>()? {
+                                let value: Value = access.next_value()?;
+
+                                if key != WILDCARD_KEY && value.is_empty() {
+                                    return Err(A::Error::custom(format_args!("empty value for key {key:?}")));
+                                }
+
+                                let (list, key) = if key == WILDCARD_KEY {
+                                    if wildcard_value.replace(value).is_some() {
+                                        return Err(A::Error::custom("duplicate wildcard key"));
+                                    }
+                                    continue;
+                                } else if let Some(key) = key.strip_prefix(EXCEPTION_PREFIX) {
+                                    (&mut wildcard_exceptions, key)
+                                } else if let Some(key) = key.strip_prefix(ESCAPE_PREFIX) {
+                                    (&mut rest_list, key)
+                                } else {
+                                    (&mut rest_list, key.as_str())
+                                };
+
+                                if list.insert(parse_key(key)?, value).is_some() {
+                                    return Err(A::Error::custom(format_args!("duplicate key {key:?}")));
+                                }
+                            }
+
+                            let wildcard_value = wildcard_value.unwrap_or_else(Value::empty);
+
+                            if !crate::collections::is_canonical(
+                                &wildcard_value,
+                                wildcard_exceptions.values(),
+                                rest_list.values(),
+                            ) {
+                                return Err(A::Error::custom(
+                                    "exceptions must be part of the wildcard value and rest list values disjoint from it",
+                                ));
+                            }
+
+                            Ok($wildcard_map::from_parts(
+                                wildcard_value,
+                                wildcard_exceptions,
+                                rest_list,
+                            ))
+                        }
+                    }
+
+                    deserializer.deserialize_map(FlatVisitor(PhantomData))
+                }
+            }
+        };
+    }
+
+    impl_flat_representation!(WildcardBTreeMap, BTreeMap, Key: (Ord + Eq + Clone));
+    #[cfg(feature = "std")]
+    impl_flat_representation!(WildcardHashMap, HashMap, Key: (Hash + Eq + Clone));
+    #[cfg(feature = "im")]
+    impl_flat_representation!(WildcardImHashMap, ImHashMap, Key: (Hash + Eq + Clone), Value: Clone);
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use core::fmt::Debug;
+
+    use maplit::{btreemap, hashmap};
+    use rstest::*;
+    use serde::{Deserialize, Serialize};
+
+    use crate::collections::{WildcardBTreeMap, WildcardHashMap};
+
+    use super::flat::{Flat, FlatRepresentation};
+
+    // These cases pin the default representations, which must stay stable.
+    #[rstest]
+    #[case::bool(true, "true")]
+    #[case::option(Some(true), "true")]
+    #[case::none(Option::<bool>::None, "null")]
+    #[case::boxed(Box::new(true), "true")]
+    #[case::array([true, false], "[true,false]")]
+    #[case::tuple((true, btreemap! { 1 => true }), "[true,{\"1\":true}]")]
+    #[case::btreemap(btreemap! { "a".to_string() => true }, "{\"a\":true}")]
+    #[case::wildcard_btreemap(WildcardBTreeMap::<String, bool>::from_parts(
+        true,
+        btreemap! { "a".to_string() => true },
+        btreemap! {},
+    ), "{\"wildcard_exceptions\":{\"a\":true},\"wildcard_value\":true}")]
+    #[case::empty_wildcard_btreemap(WildcardBTreeMap::<String, bool>::from_parts(
+        false,
+        btreemap! {},
+        btreemap! { "a".to_string() => true },
+    ), "{\"rest_list\":{\"a\":true}}")]
+    fn default_representation_tests<T>(#[case] value: T, #[case] json: &str)
+    where
+        T: Serialize + for<'de> Deserialize<'de> + PartialEq + Debug,
+    {
+        assert_eq!(serde_json::to_string(&value).unwrap(), json);
+        assert_eq!(serde_json::from_str::<T>(json).unwrap(), value);
+    }
+
+    #[rstest]
+    #[case::empty(WildcardBTreeMap::<String, bool>::from_parts(
+        false,
+        btreemap! {},
+        btreemap! {},
+    ), "{}")]
+    #[case::all_parts(WildcardBTreeMap::<String, [bool; 2]>::from_parts(
+        [true, false],
+        btreemap! { "a".to_string() => [true, false] },
+        btreemap! { "b".to_string() => [false, true] },
+    ), "{\"*\":[true,false],\"-a\":[true,false],\"b\":[false,true]}")]
+    #[case::escaped_keys(WildcardBTreeMap::<String, [bool; 2]>::from_parts(
+        [true, false],
+        btreemap! { "-a".to_string() => [true, false] },
+        btreemap! {
+            "*".to_string() => [false, true],
+            "-b".to_string() => [false, true],
+            "\\c".to_string() => [false, true],
+        },
+    ), "{\"*\":[true,false],\"--a\":[true,false],\"\\\\*\":[false,true],\"\\\\-b\":[false,true],\"\\\\\\\\c\":[false,true]}")]
+    #[case::nested(WildcardBTreeMap::<u32, WildcardBTreeMap<u32, bool>>::from_parts(
+        WildcardBTreeMap::from_parts(false, btreemap! {}, btreemap! { 1 => true }),
+        btreemap! {},
+        btreemap! { 1 => WildcardBTreeMap::from_parts(false, btreemap! {}, btreemap! { 2 => true }) },
+    ), "{\"*\":{\"rest_list\":{\"1\":true}},\"1\":{\"rest_list\":{\"2\":true}}}")]
+    fn flat_representation_tests<T>(#[case] value: T, #[case] json: &str)
+    where
+        T: FlatRepresentation + Clone + PartialEq + Debug,
+    {
+        assert_eq!(serde_json::to_string(&Flat(value.clone())).unwrap(), json);
+        assert_eq!(serde_json::from_str::<Flat<T>>(json).unwrap(), Flat(value));
+    }
+
+    #[test]
+    fn flat_hashmap_test() {
+        let value = WildcardHashMap::<i32, bool>::from_parts(
+            true,
+            hashmap! { -1 => true },
+            hashmap! {},
+        );
+
+        assert_eq!(
+            serde_json::to_string(&Flat(value.clone())).unwrap(),
+            "{\"*\":true,\"--1\":true}"
+        );
+        assert_eq!(
+            serde_json::from_str::<Flat<WildcardHashMap<i32, bool>>>("{\"*\":true,\"--1\":true}")
+                .unwrap(),
+            Flat(value)
+        );
+    }
+
+    #[rstest]
+    #[case::duplicate_wildcard("{\"*\":true,\"*\":true}")]
+    #[case::duplicate_key("{\"1\":true,\"\\\\1\":true}")]
+    #[case::invalid_key("{\"a\":true}")]
+    #[case::exception_without_wildcard("{\"-1\":true}")]
+    #[case::rest_list_overlapping_wildcard("{\"*\":true,\"1\":true}")]
+    #[case::empty_value("{\"1\":false}")]
+    fn flat_invalid_tests(#[case] json: &str) {
+        assert!(serde_json::from_str::<Flat<WildcardBTreeMap<u32, bool>>>(json).is_err());
+    }
+}
@@ -0,0 +1,172 @@
+//! This module contains helpers for requiring that a set contains another set, such as [`Require`] and the [`require!`](crate::require) macro.
+//!
+//! Instead of building a full required set by hand and calling [`crate::comparisons::SubsetOf::subset_of`], the [`require!`](crate::require) macro builds the minimal required set from a list of paths and reports what is missing.
+use core::fmt::{Debug, Display};
+
+use crate::Set;
+use crate::operations::DifferenceAssign;
+
+/// [`MissingPermissions`] is returned by [`Require::require`] when the held set does not contain the required set.
+/// It contains the difference between the required set and the held set, ie. everything that was required but is not held.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingPermissions<T>(pub T);
+
+impl<T> MissingPermissions<T> {
+    /// Returns the set of missing values.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: Debug> Display for MissingPermissions<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "missing permissions: {:?}", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Debug> std::error::Error for MissingPermissions<T> {}
+
+/// [`Require`] will check if Self contains Required, like [`crate::comparisons::SupersetOf`], but returns the missing values (Required - Self) as an error instead of a bool.
+///
+/// This is implemented for every set where Required implements [`DifferenceAssign`] with Self, which includes all structs deriving [`DifferenceAssign`].
+pub trait Require<Required> {
+    fn require(&self, required: Required) -> Result<(), MissingPermissions<Required>>;
+}
+
+impl<T, Required> Require<Required> for T
+where
+    Required: Set,
+    for<'a> Required: DifferenceAssign<&'a T>,
+{
+    fn require(&self, mut required: Required) -> Result<(), MissingPermissions<Required>> {
+        required.difference_assign(self);
+
+        if required.is_empty() {
+            Ok(())
+        } else {
+            Err(MissingPermissions(required))
+        }
+    }
+}
+
+/// [`SetEntry`] gives mutable access to the value of a key in a keyed set, inserting an empty value if the key is missing.
+///
+/// This is what the `[key]` segments of a [`require!`](crate::require) path use. It is implemented for the std, alloc and im maps and for the wildcard maps in [`crate::collections`], where the entry is in the rest list.
+pub trait SetEntry {
+    type Key;
+    type Value;
+
+    fn set_entry(&mut self, key: Self::Key) -> &mut Self::Value;
+}
+
+/// Returns an empty set of the same type as `value`. Used by [`require!`](crate::require) to infer the type of the required set.
+#[doc(hidden)]
+pub fn empty_of<T: Set>(_value: &T) -> T::Empty {
+    T::empty()
+}
+
+/// Checks that a held set contains a minimal required set built from a list of `path => value` entries, returning `Result<(), MissingPermissions<T>>`.
+///
+/// Paths start with a field name and continue with `.field` and `[key]` segments, where keys are looked up with [`SetEntry`]. Every field not mentioned in a path is left empty.
+///
+/// The required set starts as [`Set::empty`] and the paths are plain field accesses on it, so it does not use any metadata of the derives: the fields only need to be visible where the macro is used.
+///
+/// ```
+/// # use std::collections::HashMap;
+/// # use finit::require;
+/// let held: HashMap<u32, HashMap<u32, bool>> = HashMap::from([(1, HashMap::from([(2, true)]))]);
+///
+/// assert!(require!(held, [1][2] => true).is_ok());
+///
+/// let missing = require!(held, [1][2] => true, [1][3] => true).unwrap_err();
+/// assert_eq!(missing.into_inner(), HashMap::from([(1, HashMap::from([(3, true)]))]));
+/// ```
+#[macro_export]
+macro_rules! require {
+    ($held:expr $(, $($entries:tt)*)?) => {{
+        let held = &$held;
+        #[allow(unused_mut)]
+        let mut required = $crate::requirements::empty_of(held);
+        $($crate::require!(@start required $($entries)*);)?
+        $crate::requirements::Require::require(held, required)
+    }};
+    (@start $required:ident) => {};
+    (@start $required:ident [$key:expr] $($rest:tt)*) => {
+        $crate::require!(@entry $required [(*$crate::requirements::SetEntry::set_entry(&mut $required, $key))] $($rest)*)
+    };
+    (@start $required:ident $field:tt $($rest:tt)*) => {
+        $crate::require!(@entry $required [$required.$field] $($rest)*)
+    };
+    (@entry $required:ident [$($place:tt)*] . $field:tt $($rest:tt)*) => {
+        $crate::require!(@entry $required [$($place)*.$field] $($rest)*)
+    };
+    (@entry $required:ident [$($place:tt)*] [$key:expr] $($rest:tt)*) => {
+        $crate::require!(@entry $required [(*$crate::requirements::SetEntry::set_entry(&mut $($place)*, $key))] $($rest)*)
+    };
+    (@entry $required:ident [$($place:tt)*] => $value:expr $(, $($rest:tt)*)?) => {
+        $($place)* = $value;
+        $($crate::require!(@start $required $($rest)*);)?
+    };
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::collections::HashMap;
+
+    use maplit::hashmap;
+    use rstest::*;
+
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[rstest]
+    #[case::held(hashmap! { 1 => true, 2 => true }, hashmap! { 1 => true }, Ok(()))]
+    #[case::empty_required(hashmap! { 1 => true }, hashmap! {}, Ok(()))]
+    #[case::partially_held(hashmap! { 1 => true }, hashmap! { 1 => true, 2 => true }, Err(MissingPermissions(hashmap! { 2 => true })))]
+    #[case::nothing_held(hashmap! {}, hashmap! { 1 => true }, Err(MissingPermissions(hashmap! { 1 => true })))]
+    fn require_tests(
+        #[case] held: HashMap<i32, bool>,
+        #[case] required: HashMap<i32, bool>,
+        #[case] result: Result<(), MissingPermissions<HashMap<i32, bool>>>,
+    ) {
+        assert_eq!(held.require(required), result);
+    }
+
+    #[test]
+    fn require_macro_nested_keys() {
+        let held = hashmap! {
+            1 => hashmap! { 10 => true },
+            2 => hashmap! { 20 => true },
+        };
+
+        assert_eq!(require!(held), Ok(()));
+        assert_eq!(require!(held, [1][10] => true, [2][20] => true,), Ok(()));
+        assert_eq!(
+            require!(held, [1][10] => true, [1][11] => true, [3] => hashmap! { 30 => true }),
+            Err(MissingPermissions(hashmap! {
+                1 => hashmap! { 11 => true },
+                3 => hashmap! { 30 => true },
+            }))
+        );
+    }
+
+    #[test]
+    fn require_macro_wildcard_keys() {
+        use crate::collections::{WildcardBTreeMap, WildcardHashMap};
+        use crate::operations::DifferenceAssign;
+
+        let mut held =
+            WildcardHashMap::new(WildcardBTreeMap::from(maplit::btreemap! { 1 => true }));
+        held.difference_assign(&hashmap! { "muted" => WildcardBTreeMap::new(true) });
+
+        assert_eq!(require!(held, ["general"][1] => true), Ok(()));
+        assert_eq!(
+            require!(held, ["general"][1] => true, ["general"][2] => true, ["muted"][1] => true),
+            Err(MissingPermissions(WildcardHashMap::from(hashmap! {
+                "general" => WildcardBTreeMap::from(maplit::btreemap! { 2 => true }),
+                "muted" => WildcardBTreeMap::from(maplit::btreemap! { 1 => true }),
+            })))
+        );
+    }
+}
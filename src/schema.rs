@@ -0,0 +1,330 @@
+//! This module contains the [`PermissionSchema`] trait, which describes the shape of a set type, such as which fields are flags, which are keyed maps and which maps allow wildcards.
+//!
+//! This is useful for generating user interfaces or documentation for a permission struct. With the `serde` feature, a [`Schema`] can be serialized as JSON.
+//!
+//! ```
+//! # use std::collections::HashMap;
+//! # use finit::schema::{KeyKind, PermissionSchema, Schema};
+//! assert_eq!(
+//!     HashMap::<String, bool>::schema(),
+//!     Schema::Map {
+//!         key: KeyKind::String,
+//!         value: Box::new(Schema::Flag),
+//!         wildcard: false,
+//!     }
+//! );
+//! ```
+//!
+//! Recursive types are not supported, since their schema would be infinitely large.
+//!
+//! [`PermissionSchema`] is implemented for:
+//! - `bool`, [`Option`], [`Box`], arrays and tuples.
+//! - `BTreeMap` and `BTreeSet`, as well as [`WildcardBTreeMap`].
+//! - With `std`, `HashMap` and `HashSet`, as well as [`WildcardHashMap`](crate::collections::WildcardHashMap).
+//! - With `hashbrown`, `hashbrown::HashMap` and `hashbrown::HashSet`.
+//! - With `heapless`, `heapless::LinearMap` and `heapless::index_map::FnvIndexMap`, as well as `HeaplessWildcardMap`.
+//! - With `im`, `im::HashMap` and `im::OrdMap`, as well as `WildcardImHashMap`.
+//! - Structs deriving it.
+//!
+//! The collections that add structure around a set, such as [`Timed`](crate::collections::Timed), [`Conditional`](crate::collections::Conditional), [`Scoped`](crate::collections::Scoped), [`Overlay`](crate::overlay::Overlay) and [`LayeredPermissions`](crate::layered::LayeredPermissions), do not implement it, since [`Schema`] cannot describe expiries, conditions, scopes or layers.
+
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::Set;
+use crate::collections::WildcardBTreeMap;
+
+#[cfg(feature = "derive")]
+pub use finit_derive::PermissionSchema;
+
+/// [`PermissionSchema`] describes the shape of a set type as a [`Schema`].
+pub trait PermissionSchema {
+    fn schema() -> Schema;
+}
+
+/// [`PermissionKey`] describes the kind of a key type of a keyed set.
+pub trait PermissionKey {
+    const KIND: KeyKind;
+}
+
+/// The shape of a set type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", rename_all = "snake_case")
+)]
+pub enum Schema {
+    /// A single flag, like `bool`.
+    Flag,
+    /// A value that may be missing, like [`Option`].
+    Optional { value: Box<Schema> },
+    /// A fixed number of values of the same shape, like arrays.
+    Array { len: usize, item: Box<Schema> },
+    /// A fixed number of values of different shapes, like tuples.
+    Tuple { items: Vec<Schema> },
+    /// A set of keys, like [`BTreeSet`].
+    Set { key: KeyKind },
+    /// A set of values indexed by keys. `wildcard` is true if a value can be given for every key at once.
+    Map {
+        key: KeyKind,
+        value: Box<Schema>,
+        wildcard: bool,
+    },
+    /// A struct of named (or numbered, for tuple structs) fields.
+    Struct {
+        name: &'static str,
+        fields: Vec<Field>,
+    },
+}
+
+/// A field of a [`Schema::Struct`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Field {
+    pub name: &'static str,
+    pub schema: Schema,
+}
+
+/// The kind of a key type of a [`Schema::Map`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum KeyKind {
+    String,
+    Char,
+    Bool,
+    UnsignedInteger,
+    SignedInteger,
+}
+
+macro_rules! impl_permission_key {
+    ($kind:ident: $($t:ty),*) => {
+        $(
+            impl PermissionKey for $t {
+                const KIND: KeyKind = KeyKind::$kind;
+            }
+        )*
+    };
+}
+
+impl_permission_key!(String: String, &str, Box<str>);
+#[cfg(feature = "std")]
+impl_permission_key!(String: crate::symbol::Symbol, crate::symbol::StrictSymbol);
+impl_permission_key!(Char: char);
+impl_permission_key!(Bool: bool);
+impl_permission_key!(UnsignedInteger: u8, u16, u32, u64, u128, usize);
+impl_permission_key!(SignedInteger: i8, i16, i32, i64, i128, isize);
+
+impl PermissionSchema for bool {
+    fn schema() -> Schema {
+        Schema::Flag
+    }
+}
+
+impl<Value: PermissionSchema> PermissionSchema for Option<Value> {
+    fn schema() -> Schema {
+        Schema::Optional {
+            value: Box::new(Value::schema()),
+        }
+    }
+}
+
+impl<Value: PermissionSchema> PermissionSchema for Box<Value> {
+    fn schema() -> Schema {
+        Value::schema()
+    }
+}
+
+impl<const N: usize, Value: PermissionSchema> PermissionSchema for [Value; N] {
+    fn schema() -> Schema {
+        Schema::Array {
+            len: N,
+            item: Box::new(Value::schema()),
+        }
+    }
+}
+
+macro_rules! impl_tuples {
+    ($first:ident $(, $rest:ident)*) => {
+        impl<$first: PermissionSchema $(, $rest: PermissionSchema)*> PermissionSchema for ($first, $($rest,)*) {
+            fn schema() -> Schema {
+                Schema::Tuple {
+                    items: Vec::from([$first::schema() $(, $rest::schema())*]),
+                }
+            }
+        }
+
+        impl_tuples!($($rest),*);
+    };
+    () => {};
+}
+
+impl_tuples!(
+    T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15, T16
+);
+
+macro_rules! impl_map_schema {
+    ($map:ident, $wildcard:literal, Key: ($($bounds:tt)*) $(, Value: $($value_bounds:tt)*)?) => {
+        impl<Key: PermissionKey + $($bounds)*, Value: PermissionSchema + Set<Empty = Value> $(+ $($value_bounds)*)?> PermissionSchema
+            for $map<Key, Value>
+        {
+            fn schema() -> Schema {
+                Schema::Map {
+                    key: Key::KIND,
+                    value: Box::new(Value::schema()),
+                    wildcard: $wildcard,
+                }
+            }
+        }
+    };
+}
+
+macro_rules! impl_set_schema {
+    ($set:ident, Key: ($($bounds:tt)*)) => {
+        impl<Key: PermissionKey + $($bounds)*> PermissionSchema for $set<Key> {
+            fn schema() -> Schema {
+                Schema::Set { key: Key::KIND }
+            }
+        }
+    };
+}
+
+impl_map_schema!(BTreeMap, false, Key: (Ord));
+impl_map_schema!(WildcardBTreeMap, true, Key: (Ord + Eq + Clone));
+impl_set_schema!(BTreeSet, Key: (Ord));
+
+#[cfg(feature = "std")]
+mod std_impl {
+    use super::*;
+    use crate::collections::WildcardHashMap;
+    use core::hash::Hash;
+    use std::collections::{HashMap, HashSet};
+
+    impl_map_schema!(HashMap, false, Key: (Hash + Eq));
+    impl_map_schema!(WildcardHashMap, true, Key: (Hash + Eq + Clone));
+    impl_set_schema!(HashSet, Key: (Hash + Eq));
+}
+
+#[cfg(feature = "hashbrown")]
+mod hashbrown_impl {
+    use super::*;
+    use core::hash::Hash;
+    use hashbrown::{HashMap, HashSet};
+
+    impl_map_schema!(HashMap, false, Key: (Hash + Eq));
+    impl_set_schema!(HashSet, Key: (Hash + Eq));
+}
+
+#[cfg(feature = "heapless")]
+mod heapless_impl {
+    use super::*;
+    use crate::collections::HeaplessWildcardMap;
+    use core::hash::Hash;
+    use heapless::LinearMap;
+    use heapless::index_map::FnvIndexMap;
+
+    macro_rules! impl_heapless_schema {
+        ($map:ident, $wildcard:literal, Key: ($($bounds:tt)*)) => {
+            impl<Key: PermissionKey + $($bounds)*, Value: PermissionSchema + Set<Empty = Value>, const N: usize> PermissionSchema
+                for $map<Key, Value, N>
+            {
+                fn schema() -> Schema {
+                    Schema::Map {
+                        key: Key::KIND,
+                        value: Box::new(Value::schema()),
+                        wildcard: $wildcard,
+                    }
+                }
+            }
+        };
+    }
+
+    impl_heapless_schema!(LinearMap, false, Key: (Eq));
+    impl_heapless_schema!(FnvIndexMap, false, Key: (Hash + Eq));
+    impl_heapless_schema!(HeaplessWildcardMap, true, Key: (Eq + Clone));
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    #[allow(unused_imports)]
+    use super::*;
+
+    use std::collections::{HashMap, HashSet};
+
+    use crate::collections::WildcardHashMap;
+
+    #[rstest]
+    #[case::bool(bool::schema(), Schema::Flag)]
+    #[case::option(Option::<bool>::schema(), Schema::Optional { value: Box::new(Schema::Flag) })]
+    #[case::boxed(Box::<bool>::schema(), Schema::Flag)]
+    #[case::array(<[bool; 3]>::schema(), Schema::Array { len: 3, item: Box::new(Schema::Flag) })]
+    #[case::tuple(<(bool, Option<bool>)>::schema(), Schema::Tuple {
+        items: vec![Schema::Flag, Schema::Optional { value: Box::new(Schema::Flag) }],
+    })]
+    #[case::btreemap(BTreeMap::<u32, bool>::schema(), Schema::Map {
+        key: KeyKind::UnsignedInteger,
+        value: Box::new(Schema::Flag),
+        wildcard: false,
+    })]
+    #[case::wildcard_hashmap(WildcardHashMap::<i64, HashMap<char, bool>>::schema(), Schema::Map {
+        key: KeyKind::SignedInteger,
+        value: Box::new(Schema::Map {
+            key: KeyKind::Char,
+            value: Box::new(Schema::Flag),
+            wildcard: false,
+        }),
+        wildcard: true,
+    })]
+    #[case::btreeset(BTreeSet::<String>::schema(), Schema::Set { key: KeyKind::String })]
+    #[case::hashset(HashSet::<char>::schema(), Schema::Set { key: KeyKind::Char })]
+    fn schema_tests(#[case] schema: Schema, #[case] result: Schema) {
+        assert_eq!(schema, result);
+    }
+
+    #[cfg(feature = "hashbrown")]
+    #[test]
+    fn hashbrown_test() {
+        assert_eq!(
+            hashbrown::HashSet::<u8>::schema(),
+            Schema::Set {
+                key: KeyKind::UnsignedInteger
+            }
+        );
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn heapless_test() {
+        assert_eq!(
+            crate::collections::HeaplessWildcardMap::<u8, heapless::LinearMap<char, bool, 2>, 4>::schema(),
+            Schema::Map {
+                key: KeyKind::UnsignedInteger,
+                value: Box::new(Schema::Map {
+                    key: KeyKind::Char,
+                    value: Box::new(Schema::Flag),
+                    wildcard: false,
+                }),
+                wildcard: true,
+            }
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_test() {
+        let schema = WildcardBTreeMap::<String, Option<bool>>::schema();
+
+        assert_eq!(
+            serde_json::to_string(&schema).unwrap(),
+            "{\"kind\":\"map\",\"key\":\"string\",\"value\":{\"kind\":\"optional\",\"value\":{\"kind\":\"flag\"}},\"wildcard\":true}"
+        );
+    }
+}
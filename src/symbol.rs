@@ -0,0 +1,370 @@
+//! This module contains [`Symbol`], an interned string that can be used as the key of keyed sets instead of [`String`].
+//!
+//! Permission maps often repeat the same few names, such as guild or channel names, across many sets, and operations like [`crate::operations::UnionAssign`] clone keys whenever they insert them. A [`Symbol`] stores each distinct name once in a global interner, so cloning, hashing and comparing keys for equality never touches the string itself.
+//!
+//! ```
+//! # use std::collections::HashMap;
+//! # use finit::operations::UnionAssign;
+//! # use finit::symbol::Symbol;
+//! let mut perms = HashMap::from([(Symbol::intern("general"), true)]);
+//! perms.union_assign(&HashMap::from([(Symbol::intern("random"), true)]));
+//!
+//! assert!(perms.contains_key(&Symbol::intern("random")));
+//! assert_eq!(Symbol::intern("general").as_str(), "general");
+//! ```
+//!
+//! Deserializing and decoding intern the names they read, so a set written by one process can be read by another.
+//!
+//! Interned names are never freed, so symbols should only be created for names from a bounded set. For untrusted input, use [`StrictSymbol`] instead, which only accepts names that were interned before with [`Symbol::intern`] and rejects unknown names, so the input cannot grow the interner.
+//!
+//! ```
+//! # use finit::symbol::{StrictSymbol, Symbol};
+//! Symbol::intern("general");
+//!
+//! assert_eq!(Symbol::lookup("general"), Some(Symbol::intern("general")));
+//! assert_eq!(Symbol::lookup("never interned"), None);
+//! ```
+
+use std::collections::HashSet;
+use std::fmt::{Debug, Display};
+use std::hash::{Hash, Hasher};
+use std::sync::{Mutex, OnceLock};
+
+/// An interned string. Two symbols are equal if they were interned from equal strings.
+///
+/// Symbols are ordered by their strings, so a [`std::collections::BTreeMap`] keyed by symbols has the same order as one keyed by strings.
+#[derive(Clone, Copy)]
+pub struct Symbol(&'static str);
+
+fn interner() -> &'static Mutex<HashSet<&'static str>> {
+    static INTERNER: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+
+    INTERNER.get_or_init(Default::default)
+}
+
+impl Symbol {
+    /// Returns the symbol of `name`, interning it if it has not been interned before.
+    pub fn intern(name: &str) -> Self {
+        let mut interner = interner()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if let Some(interned) = interner.get(name) {
+            return Symbol(interned);
+        }
+
+        let interned: &'static str = Box::leak(name.into());
+        interner.insert(interned);
+
+        Symbol(interned)
+    }
+
+    /// Returns the symbol of `name` if it has been interned before, without interning it.
+    pub fn lookup(name: &str) -> Option<Self> {
+        interner()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(name)
+            .map(|interned| Symbol(interned))
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl PartialEq for Symbol {
+    fn eq(&self, other: &Self) -> bool {
+        // Every name is only interned once, so equal names share the same address.
+        std::ptr::eq(self.0, other.0)
+    }
+}
+
+impl Eq for Symbol {}
+
+impl Hash for Symbol {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::ptr::hash(self.0, state);
+    }
+}
+
+impl PartialOrd for Symbol {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Symbol {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        if self == other {
+            return std::cmp::Ordering::Equal;
+        }
+
+        self.0.cmp(other.0)
+    }
+}
+
+impl Debug for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(self.0, f)
+    }
+}
+
+impl Display for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self.0, f)
+    }
+}
+
+impl AsRef<str> for Symbol {
+    fn as_ref(&self) -> &str {
+        self.0
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(name: &str) -> Self {
+        Symbol::intern(name)
+    }
+}
+
+impl From<String> for Symbol {
+    fn from(name: String) -> Self {
+        Symbol::intern(&name)
+    }
+}
+
+impl From<Symbol> for String {
+    fn from(symbol: Symbol) -> Self {
+        symbol.0.to_string()
+    }
+}
+
+/// A [`Symbol`] that is only deserialized and decoded from names that were interned before, for keys read from untrusted input.
+///
+/// It is serialized and encoded like a [`Symbol`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct StrictSymbol(pub Symbol);
+
+impl Debug for StrictSymbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&self.0, f)
+    }
+}
+
+impl Display for StrictSymbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl From<Symbol> for StrictSymbol {
+    fn from(symbol: Symbol) -> Self {
+        StrictSymbol(symbol)
+    }
+}
+
+impl From<StrictSymbol> for Symbol {
+    fn from(symbol: StrictSymbol) -> Self {
+        symbol.0
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::{StrictSymbol, Symbol};
+
+    impl serde::Serialize for Symbol {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(self.0)
+        }
+    }
+
+    impl serde::Serialize for StrictSymbol {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            self.0.serialize(serializer)
+        }
+    }
+
+    /// Interns every name, unless `strict` is set, in which case only names that were interned before are accepted.
+    struct SymbolVisitor {
+        strict: bool,
+    }
+
+    impl serde::de::Visitor<'_> for SymbolVisitor {
+        type Value = Symbol;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            if self.strict {
+                formatter.write_str("an interned symbol")
+            } else {
+                formatter.write_str("a string")
+            }
+        }
+
+        fn visit_str<E: serde::de::Error>(self, name: &str) -> Result<Symbol, E> {
+            if !self.strict {
+                return Ok(Symbol::intern(name));
+            }
+
+            Symbol::lookup(name)
+                .ok_or_else(|| E::invalid_value(serde::de::Unexpected::Str(name), &self))
+        }
+    }
+
+    impl<'de> serde::Deserialize<'de> for Symbol {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_str(SymbolVisitor { strict: false })
+        }
+    }
+
+    impl<'de> serde::Deserialize<'de> for StrictSymbol {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer
+                .deserialize_str(SymbolVisitor { strict: true })
+                .map(StrictSymbol)
+        }
+    }
+}
+
+#[cfg(feature = "codec")]
+mod codec_impl {
+    use super::{StrictSymbol, Symbol};
+    use crate::codec::{Decode, DecodeError, Decoder, Encode, Encoder};
+
+    impl Encode for Symbol {
+        fn encode(&self, encoder: &mut Encoder) {
+            self.0.encode(encoder);
+        }
+    }
+
+    impl Encode for StrictSymbol {
+        fn encode(&self, encoder: &mut Encoder) {
+            self.0.encode(encoder);
+        }
+    }
+
+    /// Reads a name, which is encoded like a string.
+    fn decode_name<'a>(decoder: &mut Decoder<'a>) -> Result<&'a str, DecodeError> {
+        let len = decoder.read_len()?;
+
+        core::str::from_utf8(decoder.read_bytes(len)?).map_err(|_| DecodeError::InvalidUtf8)
+    }
+
+    impl Decode for Symbol {
+        fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+            decode_name(decoder).map(Symbol::intern)
+        }
+    }
+
+    impl Decode for StrictSymbol {
+        fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+            let name = decode_name(decoder)?;
+
+            Symbol::lookup(name)
+                .map(StrictSymbol)
+                .ok_or(DecodeError::UnknownSymbol)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+
+    use rstest::*;
+
+    #[allow(unused_imports)]
+    use super::*;
+    use crate::operations::{DifferenceAssign, UnionAssign};
+
+    #[rstest]
+    #[case("general", "general", true)]
+    #[case("general", "random", false)]
+    #[case("", "", true)]
+    fn eq_tests(#[case] a: &str, #[case] b: &str, #[case] expected: bool) {
+        assert_eq!(Symbol::intern(a) == Symbol::intern(b), expected);
+        assert_eq!(Symbol::intern(a).as_str(), a);
+    }
+
+    #[test]
+    fn order_test() {
+        let map: BTreeMap<Symbol, bool> = ["b", "c", "a"]
+            .into_iter()
+            .map(|name| (Symbol::intern(name), true))
+            .collect();
+
+        assert_eq!(
+            map.keys().map(Symbol::as_str).collect::<Vec<_>>(),
+            ["a", "b", "c"]
+        );
+    }
+
+    #[test]
+    fn map_operations_test() {
+        let mut map = HashMap::from([(Symbol::from("a"), true)]);
+        map.union_assign(&HashMap::from([(Symbol::from("b".to_string()), true)]));
+        map.difference_assign(&HashMap::from([(Symbol::intern("a"), true)]));
+
+        assert_eq!(map, HashMap::from([(Symbol::intern("b"), true)]));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_test() {
+        let map = BTreeMap::from([(Symbol::intern("general"), true)]);
+
+        let json = serde_json::to_string(&map).unwrap();
+        assert_eq!(json, r#"{"general":true}"#);
+        assert_eq!(
+            serde_json::from_str::<BTreeMap<Symbol, bool>>(&json).unwrap(),
+            map
+        );
+
+        // A name that this process has not interned yet, as if the set was serialized by another process.
+        let fresh = r#"{"first interned by serialize_test":true}"#;
+        assert_eq!(Symbol::lookup("first interned by serialize_test"), None);
+        assert!(serde_json::from_str::<BTreeMap<StrictSymbol, bool>>(fresh).is_err());
+
+        let map = serde_json::from_str::<BTreeMap<Symbol, bool>>(fresh).unwrap();
+        assert_eq!(
+            map,
+            BTreeMap::from([(Symbol::intern("first interned by serialize_test"), true)])
+        );
+        assert_eq!(serde_json::to_string(&map).unwrap(), fresh);
+        assert!(serde_json::from_str::<BTreeMap<StrictSymbol, bool>>(fresh).is_ok());
+    }
+
+    #[cfg(feature = "codec")]
+    #[test]
+    fn codec_test() {
+        let map = BTreeMap::from([(Symbol::intern("general"), true)]);
+
+        assert_eq!(
+            crate::codec::to_bytes(&map),
+            crate::codec::to_bytes(&BTreeMap::from([("general".to_string(), true)]))
+        );
+        assert_eq!(
+            crate::codec::from_bytes(&crate::codec::to_bytes(&map)),
+            Ok(map)
+        );
+        // A name that this process has not interned yet, as if the set was encoded by another process.
+        let fresh = crate::codec::to_bytes(&BTreeMap::from([(
+            "first interned by codec_test".to_string(),
+            true,
+        )]));
+        assert_eq!(Symbol::lookup("first interned by codec_test"), None);
+        assert_eq!(
+            crate::codec::from_bytes::<BTreeMap<StrictSymbol, bool>>(&fresh),
+            Err(crate::codec::DecodeError::UnknownSymbol)
+        );
+
+        let map = crate::codec::from_bytes::<BTreeMap<Symbol, bool>>(&fresh).unwrap();
+        assert_eq!(
+            map,
+            BTreeMap::from([(Symbol::intern("first interned by codec_test"), true)])
+        );
+        assert_eq!(crate::codec::to_bytes(&map), fresh);
+        assert!(crate::codec::from_bytes::<BTreeMap<StrictSymbol, bool>>(&fresh).is_ok());
+    }
+}
@@ -86,3 +86,535 @@ fn derive_test(#[case] mut a: Test1, #[case] b: Test1, #[case] c: Test1) {
     a.union_assign(&b);
     assert_eq!(a, c);
 }
+
+#[test]
+fn derive_test_fold() {
+    use finit::operations::SetIterator;
+
+    let sets = || {
+        [(true, false), (true, true), (false, true)]
+            .into_iter()
+            .map(|(field1, field2)| Test1 { field1, field2 })
+    };
+
+    assert_eq!(
+        sets().union_all(),
+        Test1 {
+            field1: true,
+            field2: true,
+        }
+    );
+    assert!(sets().intersection_all().is_empty());
+    assert_eq!(
+        sets().skip(1).intersection_all(),
+        Test1 {
+            field1: false,
+            field2: true,
+        }
+    );
+    assert_eq!(
+        sets().skip(1).difference_all(),
+        Test1 {
+            field1: true,
+            field2: false,
+        }
+    );
+}
+
+#[derive(Set, DifferenceAssign, PartialEq, Debug)]
+struct Test2 {
+    nested: Test1,
+    keyed: std::collections::HashMap<i32, Test1>,
+}
+
+#[test]
+fn derive_test_require() {
+    use finit::requirements::MissingPermissions;
+
+    let held = Test2 {
+        nested: Test1 {
+            field1: true,
+            field2: false,
+        },
+        keyed: maplit::hashmap! {
+            1 => Test1 {
+                field1: false,
+                field2: true,
+            },
+        },
+    };
+
+    assert_eq!(
+        finit::require!(held, nested.field1 => true, keyed[1].field2 => true),
+        Ok(())
+    );
+
+    assert_eq!(
+        finit::require!(held, nested.field2 => true, keyed[1].field2 => true, keyed[2].field1 => true),
+        Err(MissingPermissions(Test2 {
+            nested: Test1 {
+                field1: false,
+                field2: true,
+            },
+            keyed: maplit::hashmap! {
+                2 => Test1 {
+                    field1: true,
+                    field2: false,
+                },
+            },
+        }))
+    );
+}
+
+#[derive(Set, DifferenceAssign, PartialEq, Debug)]
+struct Test13 {
+    channels: finit::collections::WildcardHashMap<&'static str, bool>,
+    roles: finit::collections::WildcardBTreeMap<u32, bool>,
+}
+
+#[test]
+fn derive_test_require_wildcard() {
+    use finit::collections::{WildcardBTreeMap, WildcardHashMap};
+    use finit::requirements::MissingPermissions;
+
+    let mut channels = WildcardHashMap::new(true);
+    channels.difference_assign(&maplit::hashmap! { "admin" => true });
+    let held = Test13 {
+        channels,
+        roles: WildcardBTreeMap::from(maplit::btreemap! { 1 => true }),
+    };
+
+    assert_eq!(
+        finit::require!(held, channels["general"] => true, roles[1] => true),
+        Ok(())
+    );
+
+    assert_eq!(
+        finit::require!(held, channels["general"] => true, channels["admin"] => true, roles[2] => true),
+        Err(MissingPermissions(Test13 {
+            channels: WildcardHashMap::from(maplit::hashmap! { "admin" => true }),
+            roles: WildcardBTreeMap::from(maplit::btreemap! { 2 => true }),
+        }))
+    );
+}
+
+#[cfg(feature = "codec")]
+#[derive(Set, finit::codec::Encode, finit::codec::Decode, PartialEq, Debug)]
+struct Test1Codec {
+    field1: bool,
+    field2: bool,
+}
+
+#[cfg(feature = "codec")]
+#[derive(Set, finit::codec::Encode, finit::codec::Decode, PartialEq, Debug)]
+struct Test3 {
+    field1: bool,
+    keyed: std::collections::BTreeMap<u32, Test1Codec>,
+    field2: bool,
+}
+
+#[cfg(feature = "codec")]
+#[test]
+fn derive_test_codec() {
+    use finit::codec::{DecodeError, FORMAT_VERSION, from_bytes, to_bytes};
+
+    let value = Test3 {
+        field1: true,
+        keyed: maplit::btreemap! {
+            7 => Test1Codec {
+                field1: false,
+                field2: true,
+            },
+        },
+        field2: true,
+    };
+
+    let bytes = to_bytes(&value);
+    assert_eq!(bytes, [FORMAT_VERSION, 0b11, 1, 7, 0b10]);
+    assert_eq!(from_bytes(&bytes), Ok(value));
+
+    assert_eq!(
+        from_bytes::<Test3>(&[FORMAT_VERSION, 0b111, 0]),
+        Err(DecodeError::InvalidPadding)
+    );
+    assert_eq!(
+        from_bytes::<Test3>(&[FORMAT_VERSION, 0b11, 1, 7, 0]),
+        Err(DecodeError::EmptyValue)
+    );
+}
+
+#[allow(dead_code)]
+#[derive(finit::schema::PermissionSchema)]
+struct Test4 {
+    flag: bool,
+    clans: finit::collections::WildcardHashMap<String, Test5>,
+}
+
+#[allow(dead_code)]
+#[derive(Set, finit::schema::PermissionSchema)]
+struct Test5 {
+    kick: bool,
+    ban: Option<bool>,
+}
+
+#[test]
+fn derive_test_schema() {
+    use finit::schema::{Field, KeyKind, PermissionSchema, Schema};
+
+    assert_eq!(
+        Test4::schema(),
+        Schema::Struct {
+            name: "Test4",
+            fields: vec![
+                Field {
+                    name: "flag",
+                    schema: Schema::Flag,
+                },
+                Field {
+                    name: "clans",
+                    schema: Schema::Map {
+                        key: KeyKind::String,
+                        value: Box::new(Schema::Struct {
+                            name: "Test5",
+                            fields: vec![
+                                Field {
+                                    name: "kick",
+                                    schema: Schema::Flag,
+                                },
+                                Field {
+                                    name: "ban",
+                                    schema: Schema::Optional {
+                                        value: Box::new(Schema::Flag),
+                                    },
+                                },
+                            ],
+                        }),
+                        wildcard: true,
+                    },
+                },
+            ],
+        }
+    );
+}
+
+/// A count of uses that fails to grow above 10.
+#[derive(PartialEq, Debug)]
+struct Quota(u8);
+
+#[derive(PartialEq, Debug)]
+struct QuotaExceeded;
+
+impl Set for Quota {
+    type Empty = Self;
+
+    fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    fn empty() -> Self {
+        Quota(0)
+    }
+}
+
+impl finit::operations::TryUnionAssign<&Quota> for Quota {
+    type Error = QuotaExceeded;
+
+    fn try_union_assign(&mut self, rhs: &Quota) -> Result<(), QuotaExceeded> {
+        match self.0 + rhs.0 {
+            uses @ 0..=10 => self.0 = uses,
+            _ => return Err(QuotaExceeded),
+        }
+
+        Ok(())
+    }
+}
+
+impl finit::operations::TryUnion<&Quota> for Quota {
+    type Output = Self;
+    type Error = QuotaExceeded;
+
+    fn try_union(mut self, rhs: &Quota) -> Result<Self, QuotaExceeded> {
+        finit::operations::TryUnionAssign::try_union_assign(&mut self, rhs)?;
+        Ok(self)
+    }
+}
+
+impl finit::operations::IntoFieldError<QuotaExceeded> for QuotaExceeded {
+    fn into_field_error(self, field: &'static str) -> finit::operations::FieldError<QuotaExceeded> {
+        finit::operations::FieldError::new(self).in_field(field)
+    }
+}
+
+#[derive(Set, finit::operations::TryUnionAssign, finit::operations::TryUnion, PartialEq, Debug)]
+#[finit(error = QuotaExceeded)]
+struct Test6 {
+    admin: bool,
+    uploads: Quota,
+}
+
+#[derive(Set, finit::operations::TryUnionAssign, PartialEq, Debug)]
+#[finit(error = QuotaExceeded)]
+struct Test7 {
+    owner: bool,
+    limits: Test6,
+}
+
+#[test]
+fn derive_test_try_operations() {
+    use finit::operations::{TryUnion, TryUnionAssign};
+
+    let mut value = Test7 {
+        owner: false,
+        limits: Test6 {
+            admin: false,
+            uploads: Quota(4),
+        },
+    };
+    let rhs = Test7 {
+        owner: true,
+        limits: Test6 {
+            admin: true,
+            uploads: Quota(6),
+        },
+    };
+
+    assert_eq!(value.try_union_assign(&rhs), Ok(()));
+    assert_eq!(value.limits.uploads, Quota(10));
+
+    let error = value.try_union_assign(&rhs).unwrap_err();
+    assert_eq!(error.path(), ["limits", "uploads"]);
+    assert_eq!(error.into_inner(), QuotaExceeded);
+
+    let deep_error = (0..10).fold(finit::operations::FieldError::new("full"), |error, _| {
+        error.in_field("nested")
+    });
+    assert_eq!(deep_error.path().len(), finit::operations::FIELD_PATH_DEPTH);
+    assert_eq!(
+        finit::operations::FieldError::new("full")
+            .in_field("uploads")
+            .in_field("limits")
+            .to_string(),
+        "limits.uploads: full"
+    );
+
+    assert_eq!(
+        Test6 {
+            admin: false,
+            uploads: Quota(1),
+        }
+        .try_union(&rhs.limits),
+        Ok(Test6 {
+            admin: true,
+            uploads: Quota(7),
+        })
+    );
+}
+
+#[cfg(feature = "policy")]
+#[derive(Set, UnionAssign, DifferenceAssign, finit::policy::FromNode, Clone, PartialEq, Debug)]
+struct Test8 {
+    chat: bool,
+    clans: finit::collections::WildcardHashMap<String, Test9>,
+}
+
+#[cfg(feature = "policy")]
+#[derive(
+    Set,
+    UnionAssign,
+    DifferenceAssign,
+    IntersectionAssign,
+    finit::policy::FromNode,
+    Clone,
+    PartialEq,
+    Debug,
+)]
+struct Test9 {
+    kick: bool,
+    ban: bool,
+}
+
+#[cfg(feature = "policy")]
+#[test]
+fn derive_test_policy() {
+    use finit::policy::{NodeError, Policy, PolicyErrorKind};
+
+    let policy = Policy::<Test8>::from_toml(
+        r#"
+[roles.member]
+grant = ["chat", "clans.*.kick"]
+
+[roles.moderator]
+inherits = ["member"]
+grant = ["clans.*.ban"]
+deny = ["clans.secret.kick"]
+"#,
+    )
+    .unwrap();
+
+    let moderator = policy.role("moderator").unwrap();
+    assert!(moderator.chat);
+    assert_eq!(
+        moderator.clans.value_of(&"public".to_string()),
+        Test9 {
+            kick: true,
+            ban: true
+        }
+    );
+    assert_eq!(
+        moderator.clans.value_of(&"secret".to_string()),
+        Test9 {
+            kick: false,
+            ban: true
+        }
+    );
+
+    let errors = Policy::<Test8>::from_toml("[roles.a]\ngrant = [\"chat\", \"clans.*.mute\"]\n")
+        .unwrap_err()
+        .into_inner();
+    assert_eq!(
+        errors[0].kind,
+        PolicyErrorKind::UnknownNode {
+            role: "a".to_string(),
+            node: "clans.*.mute".to_string(),
+            error: NodeError::UnknownField("mute".to_string()),
+        }
+    );
+    assert_eq!(errors[0].location.as_ref().unwrap().column, 18);
+}
+
+#[derive(
+    Set,
+    UnionAssign,
+    DifferenceAssign,
+    finit::comparisons::SubsetOf,
+    finit::delegation::Grantable,
+    Clone,
+    PartialEq,
+    Debug,
+)]
+struct Test10 {
+    chat: bool,
+    #[finit(grantable)]
+    moderation: Test11,
+}
+
+#[derive(
+    Set,
+    UnionAssign,
+    DifferenceAssign,
+    finit::comparisons::SubsetOf,
+    finit::delegation::Grantable,
+    Clone,
+    PartialEq,
+    Debug,
+)]
+struct Test11 {
+    kick: bool,
+    ban: bool,
+}
+
+#[test]
+fn derive_test_grantable() {
+    use finit::delegation::{Excess, GrantableLayers, delegate};
+
+    let has = Test10 {
+        chat: true,
+        moderation: Test11 {
+            kick: true,
+            ban: true,
+        },
+    };
+    let may_grant = Test10 {
+        chat: true,
+        moderation: Test11 {
+            kick: true,
+            ban: false,
+        },
+    };
+
+    let admin = GrantableTest10::from_layers(has.clone(), may_grant.clone());
+    assert_eq!(admin.moderation.ban, finit::delegation::Grantable::new(true, false));
+    assert_eq!(admin.has(), has);
+    assert_eq!(admin.may_grant(), may_grant);
+
+    let requested = Test10 {
+        chat: false,
+        moderation: Test11 {
+            kick: true,
+            ban: true,
+        },
+    };
+    let excess = Test10 {
+        chat: false,
+        moderation: Test11 {
+            kick: false,
+            ban: true,
+        },
+    };
+    assert_eq!(
+        delegate(&admin.may_grant(), &requested),
+        Err(Excess(excess.clone()))
+    );
+    assert_eq!(admin.delegate(&requested), Err(Excess(excess.clone())));
+
+    let mut moderator = GrantableTest10::from_layers(Test10::empty(), Test10::empty());
+    let error = admin
+        .grant(
+            &mut moderator,
+            &GrantableTest10::from_layers(requested.clone(), Test10::empty()),
+        )
+        .unwrap_err()
+        .into_inner();
+    assert_eq!(error.has(), excess);
+    assert!(error.may_grant().is_empty());
+    assert!(moderator.has().is_empty());
+
+    let kick = Test10 {
+        chat: false,
+        moderation: Test11 {
+            kick: true,
+            ban: false,
+        },
+    };
+    assert!(
+        admin
+            .grant(
+                &mut moderator,
+                &GrantableTest10::from_layers(kick.clone(), kick.clone()),
+            )
+            .is_ok()
+    );
+    assert_eq!(moderator.has(), kick);
+    assert_eq!(moderator.may_grant(), kick);
+}
+
+#[cfg(feature = "serde")]
+#[finit::representation::represent]
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+struct Test12 {
+    #[finit(representation = "flat")]
+    kick: finit::collections::WildcardBTreeMap<String, bool>,
+    #[finit(representation = "default")]
+    ban: finit::collections::WildcardBTreeMap<String, bool>,
+    mute: finit::collections::WildcardBTreeMap<String, bool>,
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn derive_test_represent() {
+    use finit::collections::WildcardBTreeMap;
+    use finit::operations::DifferenceAssign;
+
+    let mut kick = WildcardBTreeMap::new(true);
+    kick.difference_assign(&std::collections::BTreeMap::from([("admin".to_string(), true)]));
+
+    let value = Test12 {
+        kick,
+        ban: WildcardBTreeMap::new(true),
+        mute: WildcardBTreeMap::empty(),
+    };
+    let json = r#"{"kick":{"*":true,"-admin":true},"ban":{"wildcard_value":true},"mute":{}}"#;
+
+    assert_eq!(serde_json::to_string(&value).unwrap(), json);
+    assert_eq!(serde_json::from_str::<Test12>(json).unwrap(), value);
+}
@@ -0,0 +1,241 @@
+//! Tower middleware that only lets requests through if the permissions they hold contain the permissions required by the route.
+//!
+//! The held set is read from the request extensions, where an earlier layer such as an authentication middleware puts it. Requests without one hold nothing.
+//! Requests that are missing part of the required set get a `403 Forbidden` response, whose extensions contain the missing set as [`MissingPermissions`].
+//!
+//! ```
+//! # use std::collections::HashSet;
+//! # use std::convert::Infallible;
+//! # use finit_tower::RequirePermissions;
+//! # use http::{Request, Response, StatusCode};
+//! # use tower::{ServiceBuilder, ServiceExt, service_fn};
+//! # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+//! let service = ServiceBuilder::new()
+//!     .layer(RequirePermissions::new(HashSet::from(["ban"])).report(|missing| format!("{missing:?}")))
+//!     .service(service_fn(|_: Request<()>| async {
+//!         Ok::<_, Infallible>(Response::new(String::from("banned")))
+//!     }));
+//!
+//! let mut request = Request::new(());
+//! request.extensions_mut().insert(HashSet::from(["kick"]));
+//!
+//! let response = service.oneshot(request).await.unwrap();
+//! assert_eq!(response.status(), StatusCode::FORBIDDEN);
+//! assert_eq!(response.body(), r#"{"ban"}"#);
+//! # });
+//! ```
+//!
+//! With axum, the layer is added to the routes that require the set with `.route_layer(RequirePermissions::new(required))`.
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use finit::Set;
+use finit::operations::DifferenceAssign;
+use finit::requirements::{MissingPermissions, Require};
+use futures_util::future::{Either, Ready, ready};
+use http::{Request, Response, StatusCode};
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Formats the missing set into the body of a `403 Forbidden` response.
+type Report<S> = Arc<dyn Fn(&S) -> String + Send + Sync>;
+
+/// A [`Layer`] that requires requests to hold a set, see the [crate] documentation.
+pub struct RequirePermissions<S> {
+    required: Arc<S>,
+    report: Option<Report<S>>,
+}
+
+impl<S> RequirePermissions<S> {
+    pub fn new(required: S) -> Self {
+        Self {
+            required: Arc::new(required),
+            report: None,
+        }
+    }
+
+    /// Writes the missing set into the body of `403 Forbidden` responses with `report`, which are empty otherwise.
+    ///
+    /// `report` may capture state, such as the names to show for every permission.
+    pub fn report(mut self, report: impl Fn(&S) -> String + Send + Sync + 'static) -> Self {
+        self.report = Some(Arc::new(report));
+        self
+    }
+}
+
+impl<S> Clone for RequirePermissions<S> {
+    fn clone(&self) -> Self {
+        Self {
+            required: self.required.clone(),
+            report: self.report.clone(),
+        }
+    }
+}
+
+impl<S: Debug> Debug for RequirePermissions<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RequirePermissions")
+            .field("required", &self.required)
+            .field("report", &self.report.as_ref().map(|_| "..."))
+            .finish()
+    }
+}
+
+impl<Inner, S> Layer<Inner> for RequirePermissions<S> {
+    type Service = RequirePermissionsService<Inner, S>;
+
+    fn layer(&self, inner: Inner) -> Self::Service {
+        RequirePermissionsService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// The [`Service`] created by [`RequirePermissions`], which calls `Inner` for requests that hold the required set.
+#[derive(Debug, Clone)]
+pub struct RequirePermissionsService<Inner, S> {
+    inner: Inner,
+    layer: RequirePermissions<S>,
+}
+
+impl<Inner, S> RequirePermissionsService<Inner, S> {
+    /// The `403 Forbidden` response for a request that is missing `missing`.
+    fn forbidden<ResBody: From<String>>(&self, missing: MissingPermissions<S>) -> Response<ResBody>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        let body = self
+            .layer
+            .report
+            .as_ref()
+            .map(|report| report(&missing.0))
+            .unwrap_or_default();
+
+        let mut response = Response::new(ResBody::from(body));
+        *response.status_mut() = StatusCode::FORBIDDEN;
+        response.extensions_mut().insert(missing);
+        response
+    }
+}
+
+impl<Inner, S, ReqBody, ResBody> Service<Request<ReqBody>> for RequirePermissionsService<Inner, S>
+where
+    Inner: Service<Request<ReqBody>, Response = Response<ResBody>>,
+    ResBody: From<String>,
+    S: Set<Empty = S> + Clone + Send + Sync + 'static,
+    for<'a> S: DifferenceAssign<&'a S>,
+{
+    type Response = Response<ResBody>;
+    type Error = Inner::Error;
+    type Future = Either<Inner::Future, Ready<Result<Response<ResBody>, Inner::Error>>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        let held = request.extensions().get::<S>();
+        let required = S::clone(&self.layer.required);
+
+        match held.unwrap_or(&S::empty()).require(required) {
+            Ok(()) => Either::Left(self.inner.call(request)),
+            Err(missing) => Either::Right(ready(Ok(self.forbidden(missing)))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::convert::Infallible;
+
+    use maplit::hashmap;
+    use tower::{ServiceBuilder, ServiceExt, service_fn};
+
+    use super::*;
+
+    type Perms = HashMap<&'static str, bool>;
+
+    async fn call(layer: RequirePermissions<Perms>, held: Option<Perms>) -> Response<String> {
+        let service =
+            ServiceBuilder::new()
+                .layer(layer)
+                .service(service_fn(|_: Request<()>| async {
+                    Ok::<_, Infallible>(Response::new(String::from("ok")))
+                }));
+
+        let mut request = Request::new(());
+        if let Some(held) = held {
+            request.extensions_mut().insert(held);
+        }
+
+        service.oneshot(request).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn allowed_test() {
+        let response = call(
+            RequirePermissions::new(hashmap! { "kick" => true }),
+            Some(hashmap! { "kick" => true, "ban" => true }),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.body(), "ok");
+    }
+
+    #[tokio::test]
+    async fn forbidden_test() {
+        let response = call(
+            RequirePermissions::new(hashmap! { "kick" => true, "ban" => true }),
+            Some(hashmap! { "kick" => true }),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(response.body(), "");
+        assert_eq!(
+            response.extensions().get::<MissingPermissions<Perms>>(),
+            Some(&MissingPermissions(hashmap! { "ban" => true }))
+        );
+    }
+
+    #[tokio::test]
+    async fn report_test() {
+        let response = call(
+            RequirePermissions::new(hashmap! { "ban" => true }).report(|missing| {
+                let mut keys = missing.keys().copied().collect::<Vec<_>>();
+                keys.sort();
+                keys.join(",")
+            }),
+            None,
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(response.body(), "ban");
+    }
+
+    #[tokio::test]
+    async fn capturing_report_test() {
+        let names = hashmap! { "ban" => "Ban members" };
+        let response = call(
+            RequirePermissions::new(hashmap! { "ban" => true })
+                .report(move |missing| missing.keys().map(|key| names[key]).collect()),
+            Some(hashmap! { "kick" => true }),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(response.body(), "Ban members");
+    }
+
+    #[tokio::test]
+    async fn empty_required_test() {
+        let response = call(RequirePermissions::new(Perms::new()), None).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}